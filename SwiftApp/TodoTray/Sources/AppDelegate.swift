@@ -21,6 +21,7 @@ class AppDelegate: NSObject, NSApplicationDelegate {
     func applicationWillTerminate(_ notification: Notification) {
         os_log("applicationWillTerminate", log: logger, type: .info)
         // Cleanup
+        statusBarController?.shutdown()
         statusBarController = nil
     }
 }
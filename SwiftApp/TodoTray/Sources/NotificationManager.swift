@@ -4,9 +4,25 @@ import UserNotifications
 /// Manages notifications using UNUserNotificationCenter
 class NotificationManager: NSObject {
     static let shared = NotificationManager()
+
+    private static let taskCompletedCategory = "TASK_COMPLETED"
+    private static let undoAction = "UNDO"
+
+    /// Called with the undo token when Undo is chosen on a completion notification
+    var onUndoCompletion: ((String) -> Void)?
     
     private override init() {
         super.init()
+        let undo = UNNotificationAction(identifier: Self.undoAction, title: "Undo", options: [])
+        let taskCompleted = UNNotificationCategory(
+            identifier: Self.taskCompletedCategory,
+            actions: [undo],
+            intentIdentifiers: [],
+            options: []
+        )
+        let center = UNUserNotificationCenter.current()
+        center.setNotificationCategories([taskCompleted])
+        center.delegate = self
     }
     
     func requestAuthorization() {
@@ -19,18 +35,23 @@ class NotificationManager: NSObject {
         }
     }
     
-    func showOverdue(count: Int, taskNames: [String]) {
+    func showOverdue(alert: OverdueAlert) {
         let content = UNMutableNotificationContent()
         
-        if count == 1 {
-            content.title = "Task Overdue"
-            content.subtitle = taskNames.first ?? "Task needs attention"
+        // The core picks the title by the most pressing task's priority
+        content.title = alert.title
+        if alert.taskNames.count == 1 {
+            content.subtitle = alert.taskNames.first ?? "Task needs attention"
         } else {
-            content.title = "\(count) Tasks Overdue"
-            content.subtitle = "\(count) tasks need attention"
+            content.subtitle = truncate(alert.taskNames.joined(separator: ", "), maxLength: 80)
         }
         content.body = "Click to view in Todo Tray"
-        content.sound = .default
+        if alert.isUrgent {
+            content.sound = .defaultCritical
+            content.interruptionLevel = .timeSensitive
+        } else {
+            content.sound = alert.topPriority <= 2 ? .default : nil
+        }
         
         let request = UNNotificationRequest(
             identifier: "overdue-\(UUID().uuidString)",
@@ -41,12 +62,16 @@ class NotificationManager: NSObject {
         UNUserNotificationCenter.current().add(request)
     }
     
-    func showTaskCompleted(taskName: String) {
+    func showTaskCompleted(taskName: String, undoToken: String?) {
         let content = UNMutableNotificationContent()
         content.title = "Task Completed"
         content.subtitle = truncate(taskName, maxLength: 50)
         content.body = "Great job!"
         content.sound = .default
+        if let undoToken {
+            content.categoryIdentifier = Self.taskCompletedCategory
+            content.userInfo = ["undoToken": undoToken]
+        }
         
         let request = UNNotificationRequest(
             identifier: "completed-\(UUID().uuidString)",
@@ -57,6 +82,58 @@ class NotificationManager: NSObject {
         UNUserNotificationCenter.current().add(request)
     }
     
+    func showRefreshFailed(message: String) {
+        let content = UNMutableNotificationContent()
+        content.title = "Refresh Failed"
+        content.body = truncate(message, maxLength: 200)
+
+        let request = UNNotificationRequest(
+            identifier: "refresh-failed-\(UUID().uuidString)",
+            content: content,
+            trigger: nil
+        )
+
+        UNUserNotificationCenter.current().add(request)
+    }
+
+    func showGitHubAlert(notifications: [GithubNotification]) {
+        guard let first = notifications.first else { return }
+        let content = UNMutableNotificationContent()
+
+        if notifications.count == 1 {
+            content.title = "GitHub Notification"
+            content.subtitle = truncate(first.title, maxLength: 50)
+            content.body = "\(first.repository) · \(first.reason)"
+        } else {
+            content.title = "\(notifications.count) GitHub Notifications"
+            content.subtitle = "Review requests and mentions need attention"
+            content.body = "Click to view in Todo Tray"
+        }
+        content.sound = .default
+
+        let request = UNNotificationRequest(
+            identifier: "github-\(UUID().uuidString)",
+            content: content,
+            trigger: nil
+        )
+
+        UNUserNotificationCenter.current().add(request)
+    }
+    
+    func showAllClear() {
+        let content = UNMutableNotificationContent()
+        content.title = "All Caught Up"
+        content.body = "No overdue tasks left."
+
+        let request = UNNotificationRequest(
+            identifier: "all-clear-\(UUID().uuidString)",
+            content: content,
+            trigger: nil
+        )
+
+        UNUserNotificationCenter.current().add(request)
+    }
+
     private func truncate(_ string: String, maxLength: Int) -> String {
         if string.count <= maxLength {
             return string
@@ -65,3 +142,17 @@ class NotificationManager: NSObject {
         return String(string[..<index]) + "…"
     }
 }
+
+extension NotificationManager: UNUserNotificationCenterDelegate {
+    func userNotificationCenter(
+        _ center: UNUserNotificationCenter,
+        didReceive response: UNNotificationResponse,
+        withCompletionHandler completionHandler: @escaping () -> Void
+    ) {
+        if response.actionIdentifier == Self.undoAction,
+           let undoToken = response.notification.request.content.userInfo["undoToken"] as? String {
+            onUndoCompletion?(undoToken)
+        }
+        completionHandler()
+    }
+}
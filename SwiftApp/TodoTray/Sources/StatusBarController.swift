@@ -23,6 +23,18 @@ private final class TodoistSnoozeMenuPayload: NSObject {
     }
 }
 
+private final class GitHubSnoozeMenuPayload: NSObject {
+    let accountName: String
+    let threadId: String
+    let durationLabel: String
+
+    init(accountName: String, threadId: String, durationLabel: String) {
+        self.accountName = accountName
+        self.threadId = threadId
+        self.durationLabel = durationLabel
+    }
+}
+
 private final class CalendarEventMenuPayload: NSObject {
     let webUrl: String
 
@@ -65,12 +77,27 @@ class StatusBarController: NSObject {
         } catch {
             os_log("Failed to initialize Rust core: %{public}@", log: logger, type: .error, error.localizedDescription)
             showError("Failed to initialize: \(error.localizedDescription)")
+            if !configFileExists() {
+                offerConfigTemplate()
+            }
             return
         }
         
+        NotificationManager.shared.onUndoCompletion = { [weak self] undoToken in
+            self?.undoCompletion(undoToken)
+        }
+
         // Build initial menu
         rebuildMenu()
         os_log("Initial menu built", log: logger, type: .info)
+
+        // Data goes stale while asleep; refresh as soon as the Mac wakes
+        NSWorkspace.shared.notificationCenter.addObserver(
+            self,
+            selector: #selector(systemDidWake),
+            name: NSWorkspace.didWakeNotification,
+            object: nil
+        )
         
         os_log("StatusBarController init completed", log: logger, type: .info)
     }
@@ -107,6 +134,38 @@ class StatusBarController: NSObject {
         statusItem.menu = menu
     }
     
+    /// Whether config.toml exists where the core looks for it
+    private func configFileExists() -> Bool {
+        guard let support = FileManager.default.urls(for: .applicationSupportDirectory, in: .userDomainMask).first else {
+            return true
+        }
+        let path = support.appendingPathComponent("todo-tray/config.toml").path
+        return FileManager.default.fileExists(atPath: path)
+    }
+
+    /// Add "Create Config File…" to the error menu for a first run
+    private func offerConfigTemplate() {
+        guard let menu = statusItem.menu else { return }
+        let index = max(menu.numberOfItems - 1, 0)
+        menu.insertItem(createMenuItem("Create Config File…", action: #selector(createConfigFile), keyEquivalent: ""), at: index)
+        menu.insertItem(.separator(), at: index + 1)
+    }
+
+    /// Write the starting config and open it for editing
+    @objc func createConfigFile() {
+        do {
+            let path = try writeDefaultConfig()
+            NSWorkspace.shared.open(URL(fileURLWithPath: path))
+            let alert = NSAlert()
+            alert.messageText = "Config Created"
+            alert.informativeText = "Add your Todoist API token to \(path), then relaunch Todo Tray."
+            NSApp.activate(ignoringOtherApps: true)
+            alert.runModal()
+        } catch {
+            showError("Failed to create config: \(error.localizedDescription)")
+        }
+    }
+
     /// Update the menu bar title
     private func updateMenuBar() {
         guard let state = currentState else {
@@ -122,7 +181,9 @@ class StatusBarController: NSObject {
         
         var title: String
         
-        if overdue > 0 && github > 0 {
+        if let configured = state.title {
+            title = configured
+        } else if overdue > 0 && github > 0 {
             title = "!\(overdue) + \(github)"
         } else if overdue > 0 {
             title = "!\(overdue)"
@@ -139,7 +200,8 @@ class StatusBarController: NSObject {
         }
         
         statusItem.button?.title = title
-        statusItem.button?.toolTip = "Todo Tray - \(overdue) overdue, \(today) today, \(linear) linear in progress, \(github) GitHub notifications, \(calendar) calendar events"
+        statusItem.button?.toolTip = "Todo Tray - \(state.summary)"
+        statusItem.button?.setAccessibilityLabel(core.accessibilitySummary(state: state))
         os_log("Menu bar title updated to: %{public}@", log: logger, type: .info, title)
     }
     
@@ -156,67 +218,30 @@ class StatusBarController: NSObject {
             return
         }
         
-        // Check if we should show tomorrow section (after noon)
-        let showTomorrow = Calendar.current.component(.hour, from: Date()) >= 12
-        
-        // Overdue section
-        if !state.tasks.overdue.isEmpty {
-            menu.addItem(createHeader("Overdue"))
-            for task in state.tasks.overdue {
-                menu.addItem(createTaskItem(task))
-            }
-            menu.addItem(.separator())
-        }
-        
-        // Today section
-        if !state.tasks.today.isEmpty {
-            menu.addItem(createHeader("Today"))
-            for task in state.tasks.today {
-                menu.addItem(createTaskItem(task))
-            }
-            menu.addItem(.separator())
-        }
-        
-        // Tomorrow section (only after noon)
-        if showTomorrow && !state.tasks.tomorrow.isEmpty {
-            menu.addItem(createHeader("Tomorrow"))
-            for task in state.tasks.tomorrow {
-                menu.addItem(createTaskItem(task))
-            }
+        // The single most pressing item across tasks and meetings
+        if let next = state.nextUp {
+            let title = next.displayTime.isEmpty ? next.title : "\(next.title) · \(next.displayTime)"
+            menu.addItem(createHeader("Next: \(title)"))
             menu.addItem(.separator())
         }
 
-        // Linear in-progress section
-        if !state.tasks.inProgress.isEmpty {
-            menu.addItem(createHeader("Linear · In Progress"))
-            for task in state.tasks.inProgress {
-                menu.addItem(createTaskItem(task))
-            }
-            menu.addItem(.separator())
-        }
-        
-        // GitHub notifications grouped by account
-        for section in state.githubNotifications where !section.notifications.isEmpty {
-            menu.addItem(createHeader("GitHub · \(section.accountName)"))
-            for notification in section.notifications {
-                menu.addItem(createGitHubNotificationItem(notification, accountName: section.accountName))
+        // Tasks, GitHub and calendar blocks in the configured order
+        let order = state.sectionOrder.isEmpty
+            ? [MenuSectionKind.tasks, .github, .calendar]
+            : state.sectionOrder.map { $0.kind }
+        for kind in order {
+            switch kind {
+            case .tasks: addTaskSections(to: menu, state: state)
+            case .github: addGitHubSections(to: menu, state: state)
+            case .calendar: addCalendarSections(to: menu, state: state)
             }
-            menu.addItem(.separator())
         }
 
-        // Calendar events grouped by feed/account
-        for section in state.calendarEvents where !section.events.isEmpty {
-            menu.addItem(createHeader("Calendar · \(section.accountName)"))
-            for event in section.events {
-                menu.addItem(createCalendarEventItem(event))
-            }
-            menu.addItem(.separator())
-        }
-        
         // No tasks message
         if state.tasks.overdue.isEmpty
             && state.tasks.today.isEmpty
-            && (!showTomorrow || state.tasks.tomorrow.isEmpty)
+            && state.tasks.tomorrow.isEmpty
+            && state.tasks.other.isEmpty
             && state.tasks.inProgress.isEmpty
             && state.githubNotifications.allSatisfy({ $0.notifications.isEmpty })
             && state.calendarEvents.allSatisfy({ $0.events.isEmpty })
@@ -225,10 +250,78 @@ class StatusBarController: NSObject {
             item.isEnabled = false
             menu.addItem(.separator())
         }
+
+        // Offline for a while: one muted note instead of a banner per source
+        if state.isStale {
+            let item = menu.addItem(
+                withTitle: "Offline · showing last known data",
+                action: nil,
+                keyEquivalent: ""
+            )
+            item.isEnabled = false
+            menu.addItem(.separator())
+        } else if !state.sourceErrors.isEmpty {
+            // Sources that failed on the last refresh (their cached data is still shown above)
+            for sourceError in state.sourceErrors {
+                let label = sourceError.accountName.map { "\(sourceError.source.capitalized) · \($0)" }
+                    ?? sourceError.source.capitalized
+                let item = menu.addItem(
+                    withTitle: "⚠️ \(label): \(sourceError.message)",
+                    action: nil,
+                    keyEquivalent: ""
+                )
+                item.isEnabled = false
+            }
+            menu.addItem(.separator())
+        }
+
+        // Local completion feedback; Todoist tasks closed by mistake can be reopened
+        if state.completedTodayCount > 0 {
+            let item = menu.addItem(
+                withTitle: "✓ \(state.completedTodayCount) completed today",
+                action: nil,
+                keyEquivalent: ""
+            )
+            let reopenable = state.recentlyCompleted.filter { $0.source == "todoist" }
+            if reopenable.isEmpty {
+                item.isEnabled = false
+            } else {
+                let submenu = NSMenu(title: "Completed Today")
+                for task in reopenable {
+                    let reopen = createMenuItem("Reopen \(task.content)", action: #selector(reopenTask(_:)))
+                    reopen.representedObject = task.id
+                    submenu.addItem(reopen)
+                }
+                item.submenu = submenu
+            }
+            menu.addItem(.separator())
+        }
         
         // Controls
+        if !state.readOnly {
+            menu.addItem(createMenuItem("Add Task…", action: #selector(quickAddTask), keyEquivalent: "n"))
+        }
         menu.addItem(createMenuItem("Refresh", action: #selector(refresh), keyEquivalent: "r"))
+        if let lastUpdated = state.lastUpdated.flatMap({ ISO8601DateFormatter().date(from: $0) }) {
+            let relative = RelativeDateTimeFormatter().localizedString(for: lastUpdated, relativeTo: Date())
+            let item = menu.addItem(withTitle: "Updated \(relative)", action: nil, keyEquivalent: "")
+            item.isEnabled = false
+        }
+        let focus = createMenuItem("Focus Mode", action: #selector(toggleFocusMode), keyEquivalent: "")
+        focus.state = state.focusMode ? .on : .off
+        menu.addItem(focus)
+        let sources = NSMenuItem(title: "Sources", action: nil, keyEquivalent: "")
+        let sourcesMenu = NSMenu()
+        for (source, title) in [("todoist", "Todoist"), ("linear", "Linear"), ("github", "GitHub"), ("calendar", "Calendar")] {
+            let item = createMenuItem(title, action: #selector(toggleSource(_:)))
+            item.representedObject = source
+            item.state = state.disabledSources.contains(source) ? .off : .on
+            sourcesMenu.addItem(item)
+        }
+        sources.submenu = sourcesMenu
+        menu.addItem(sources)
         menu.addItem(createAutostartItem(state.autostartEnabled))
+        menu.addItem(createMenuItem("Copy Diagnostics", action: #selector(copyDiagnostics)))
         menu.addItem(.separator())
         menu.addItem(createMenuItem("Quit", action: #selector(quit), keyEquivalent: "q"))
         
@@ -254,6 +347,9 @@ class StatusBarController: NSObject {
         if task.source == "todoist" && task.canComplete {
             return createTodoistTaskSubmenu(task)
         }
+        if task.source == "linear" && task.canComplete {
+            return createLinearTaskSubmenu(task)
+        }
 
         let action: Selector? = if task.canComplete {
             #selector(completeTask(_:))
@@ -263,13 +359,22 @@ class StatusBarController: NSObject {
             nil
         }
 
-        let item = NSMenuItem(title: task.content, action: action, keyEquivalent: "")
+        let item = NSMenuItem(title: taskTitle(task), action: action, keyEquivalent: "")
+        if task.canComplete {
+            applyShortcut(task, to: item)
+        }
         item.target = action != nil ? self : nil
         item.isEnabled = action != nil
         
         // Create custom view with right-aligned time
-        let taskView = TaskMenuItemView(title: task.content, time: task.displayTime)
+        let taskView = TaskMenuItemView(title: taskTitle(task), time: task.displayTime)
         item.view = taskView
+        if let dueTime = task.dueTime, dueTime != task.displayTime {
+            item.toolTip = "Due at \(dueTime)"
+        }
+        if let deadline = task.deadline {
+            item.toolTip = [item.toolTip, "Deadline \(deadline)"].compactMap { $0 }.joined(separator: " · ")
+        }
         if task.canComplete {
             item.representedObject = task.id
         } else if let openUrl = task.openUrl {
@@ -279,11 +384,152 @@ class StatusBarController: NSObject {
         return item
     }
 
+    /// Bind the task's ⌘1–⌘9 completion shortcut, if the core assigned one
+    private func applyShortcut(_ task: TodoTask, to item: NSMenuItem) {
+        guard let shortcut = task.shortcut else { return }
+        item.keyEquivalent = String(shortcut)
+        item.keyEquivalentModifierMask = .command
+    }
+
+    private func addTaskSections(to menu: NSMenu, state: AppState) {
+        // Project grouping, the compact layout and capped sections use sections pre-built by the core
+        let useSections = state.grouping == .byProject
+            || state.menuLayout == .compact
+            || state.maxItemsPerSection != nil
+        if useSections {
+            for section in state.sections {
+                if state.menuLayout == .compact {
+                    let total = section.tasks.count + Int(section.hiddenCount)
+                    menu.addItem(createHeader("\(section.title) (\(total))"))
+                } else {
+                    menu.addItem(createHeader(section.title))
+                }
+                for task in section.tasks {
+                    menu.addItem(createTaskItem(task))
+                }
+                if section.hiddenCount > 0 {
+                    menu.addItem(createMenuItem("…and \(section.hiddenCount) more", action: #selector(openTodoist)))
+                }
+                menu.addItem(.separator())
+            }
+        }
+
+        // Overdue section
+        if !useSections && !state.tasks.overdue.isEmpty {
+            menu.addItem(createHeader("Overdue"))
+            for task in state.tasks.overdue {
+                menu.addItem(createTaskItem(task))
+            }
+            if !state.readOnly && state.tasks.overdue.contains(where: { $0.source == "todoist" }) {
+                menu.addItem(createMenuItem("Move All to Today", action: #selector(rescheduleOverdueToToday)))
+            }
+            menu.addItem(.separator())
+        }
+        
+        // Today section
+        if !useSections && !state.tasks.today.isEmpty {
+            menu.addItem(createHeader("Today"))
+            for task in state.tasks.today {
+                menu.addItem(createTaskItem(task))
+            }
+            if !state.readOnly && state.tasks.today.contains(where: { $0.source == "todoist" && $0.canComplete }) {
+                menu.addItem(createMenuItem("Complete All Today…", action: #selector(completeAllToday)))
+            }
+            menu.addItem(.separator())
+        }
+        
+        // Tomorrow section (the core leaves it empty before show_tomorrow_after_hour)
+        if !useSections && !state.tasks.tomorrow.isEmpty {
+            menu.addItem(createHeader("Tomorrow"))
+            for task in state.tasks.tomorrow {
+                menu.addItem(createTaskItem(task))
+            }
+            menu.addItem(.separator())
+        }
+
+        // Custom filters can return tasks that aren't due by tomorrow
+        if !useSections && !state.tasks.other.isEmpty {
+            menu.addItem(createHeader("Other"))
+            for task in state.tasks.other {
+                menu.addItem(createTaskItem(task))
+            }
+            menu.addItem(.separator())
+        }
+
+        // Linear in-progress section
+        if !useSections && !state.tasks.inProgress.isEmpty {
+            menu.addItem(createHeader("Linear · In Progress"))
+            for task in state.tasks.inProgress {
+                menu.addItem(createTaskItem(task))
+            }
+            menu.addItem(.separator())
+        }
+    }
+
+    private func addGitHubSections(to menu: NSMenu, state: AppState) {
+        // GitHub notifications grouped by account
+        for section in state.githubNotifications where !section.notifications.isEmpty {
+            menu.addItem(createHeader("GitHub · \(section.displayName ?? section.accountName)"))
+            for notification in section.notifications {
+                menu.addItem(createGitHubNotificationItem(notification, accountName: section.accountName))
+            }
+            if section.hasMore {
+                menu.addItem(createMenuItem("More on GitHub…", action: #selector(openGitHubInbox)))
+            }
+            menu.addItem(.separator())
+        }
+    }
+
+    private func addCalendarSections(to menu: NSMenu, state: AppState) {
+        // Calendar events grouped by feed/account
+        for section in state.calendarEvents where !section.events.isEmpty {
+            menu.addItem(createHeader("Calendar · \(section.accountName)"))
+            for event in section.events {
+                menu.addItem(createCalendarEventItem(event))
+            }
+            menu.addItem(.separator())
+        }
+    }
+
+    /// Task title with an amber marker when it's due within the "due soon" window
+    /// and a flag when its deadline is today or has passed
+    private func taskTitle(_ task: TodoTask) -> String {
+        var title = task.urgency == .dueSoon ? "🟠 \(task.content)" : task.content
+        if task.isDeadlineNear {
+            title = "🚩 \(title)"
+        }
+        return task.commentCount > 0 ? "\(title) 💬" : title
+    }
+
     private func createTodoistTaskSubmenu(_ task: TodoTask) -> NSMenuItem {
-        let item = NSMenuItem(title: "\(task.content) · \(task.displayTime)", action: nil, keyEquivalent: "")
+        let item = NSMenuItem(title: "\(taskTitle(task)) · \(task.displayTime)", action: nil, keyEquivalent: "")
+        item.indentationLevel = Int(task.indentLevel)
+        // Cleaned-up titles still show the original text on hover
+        if task.rawContent != task.content {
+            item.toolTip = task.rawContent
+        }
         let submenu = NSMenu(title: task.content)
 
+        // Tasks in shared projects say who they're assigned to
+        if let assignee = task.assigneeName {
+            let by = task.assignerName.map { " by \($0)" } ?? ""
+            let assigned = NSMenuItem(title: "Assigned to \(assignee)\(by)", action: nil, keyEquivalent: "")
+            assigned.isEnabled = false
+            submenu.addItem(assigned)
+            submenu.addItem(.separator())
+        }
+        if let reminder = task.nextReminder.flatMap({ ISO8601DateFormatter().date(from: $0) }) {
+            let formatter = DateFormatter()
+            formatter.dateStyle = Calendar.current.isDateInToday(reminder) ? .none : .short
+            formatter.timeStyle = .short
+            let item = NSMenuItem(title: "⏰ Reminder \(formatter.string(from: reminder))", action: nil, keyEquivalent: "")
+            item.isEnabled = false
+            submenu.addItem(item)
+            submenu.addItem(.separator())
+        }
+
         let resolve = NSMenuItem(title: "Resolve", action: #selector(completeTask(_:)), keyEquivalent: "")
+        applyShortcut(task, to: resolve)
         resolve.target = self
         resolve.representedObject = task.id
         submenu.addItem(resolve)
@@ -302,29 +548,93 @@ class StatusBarController: NSObject {
             submenu.addItem(snooze)
         }
 
+        let move = NSMenuItem(title: "Move to Project…", action: #selector(moveTask(_:)), keyEquivalent: "")
+        move.target = self
+        move.representedObject = task.id
+        submenu.addItem(move)
+
+        submenu.addItem(NSMenuItem.separator())
+        let delete = NSMenuItem(title: "Delete…", action: #selector(deleteTask(_:)), keyEquivalent: "")
+        delete.target = self
+        delete.representedObject = task.id
+        submenu.addItem(delete)
+
+        item.submenu = submenu
+        return item
+    }
+    
+    private func createLinearTaskSubmenu(_ task: TodoTask) -> NSMenuItem {
+        let item = NSMenuItem(title: "\(taskTitle(task)) · \(task.displayTime)", action: nil, keyEquivalent: "")
+        let submenu = NSMenu(title: task.content)
+
+        if let openUrl = task.openUrl {
+            let open = NSMenuItem(title: "Open in Linear", action: #selector(openLinearTask(_:)), keyEquivalent: "")
+            open.target = self
+            open.representedObject = openUrl
+            submenu.addItem(open)
+        }
+
+        let complete = NSMenuItem(title: "Complete", action: #selector(completeTask(_:)), keyEquivalent: "")
+        applyShortcut(task, to: complete)
+        complete.target = self
+        complete.representedObject = task.id
+        submenu.addItem(complete)
+
         item.submenu = submenu
         return item
     }
     
     /// Create a GitHub notification item that opens in browser and resolves it.
     private func createGitHubNotificationItem(_ notification: GithubNotification, accountName: String) -> NSMenuItem {
-        let item = NSMenuItem(title: notification.title, action: #selector(openGitHubNotification(_:)), keyEquivalent: "")
-        item.target = self
-        
-        let view = TaskMenuItemView(
-            title: "\(notification.title) (\(notification.reason))",
-            time: notification.repository
-        )
-        item.view = view
-        item.representedObject = GitHubNotificationMenuPayload(
+        // Review requests and mentions are waiting on the user; badge them
+        let badge = notification.isActionable ? "👀 " : ""
+        let title = "\(badge)\(kindIcon(notification.kind)) \(notification.title) (\(notification.reason))"
+        let item = NSMenuItem(title: "\(title) · \(notification.repository)", action: nil, keyEquivalent: "")
+        let submenu = NSMenu(title: notification.title)
+
+        let open = NSMenuItem(title: "Open on GitHub", action: #selector(openGitHubNotification(_:)), keyEquivalent: "")
+        open.target = self
+        open.representedObject = GitHubNotificationMenuPayload(
             accountName: accountName,
             threadId: notification.threadId,
             webUrl: notification.webUrl
         )
-        
+        submenu.addItem(open)
+
+        // Snoozing only hides the thread here; it stays unread on GitHub
+        let durations = (currentState?.snoozeDurations.isEmpty == false)
+            ? (currentState?.snoozeDurations ?? [])
+            : ["30m", "1d"]
+        for duration in durations {
+            let snooze = NSMenuItem(
+                title: "Snooze \(duration)",
+                action: #selector(snoozeGitHubNotification(_:)),
+                keyEquivalent: ""
+            )
+            snooze.target = self
+            snooze.representedObject = GitHubSnoozeMenuPayload(
+                accountName: accountName,
+                threadId: notification.threadId,
+                durationLabel: duration
+            )
+            submenu.addItem(snooze)
+        }
+
+        item.submenu = submenu
         return item
     }
     
+    private func kindIcon(_ kind: GithubNotificationKind) -> String {
+        switch kind {
+        case .pullRequest: return "⇄"
+        case .issue: return "◎"
+        case .release: return "🏷"
+        case .discussion: return "💬"
+        case .checkSuite: return "✓"
+        case .other: return "•"
+        }
+    }
+
     /// Create autostart toggle menu item
     private func createAutostartItem(_ enabled: Bool) -> NSMenuItem {
         let title = enabled ? "✓ Autostart" : "Autostart"
@@ -338,7 +648,10 @@ class StatusBarController: NSObject {
         let item = NSMenuItem(title: event.title, action: action, keyEquivalent: "")
         item.target = action != nil ? self : nil
         item.isEnabled = action != nil
-        item.view = TaskMenuItemView(title: event.title, time: event.displayTime)
+        let title = event.location.map { "\(event.title) · \($0)" } ?? event.title
+        // Events that don't block time, like focus holds, say so
+        let time = event.isFree ? "\(event.displayTime) · free" : event.displayTime
+        item.view = TaskMenuItemView(title: title, time: time)
         if let url = event.openUrl {
             item.representedObject = CalendarEventMenuPayload(webUrl: url)
         }
@@ -347,6 +660,11 @@ class StatusBarController: NSObject {
     
     // MARK: - Actions
     
+    @objc func systemDidWake() {
+        os_log("System woke from sleep", log: logger, type: .info)
+        core?.notifyResumed()
+    }
+
     @objc func refresh() {
         os_log("Refresh triggered", log: logger, type: .info)
         // Use async task wrapper to avoid confusion with Swift.Task
@@ -378,6 +696,7 @@ class StatusBarController: NSObject {
             state.tasks.overdue.removeAll { $0.id == taskId }
             state.tasks.today.removeAll { $0.id == taskId }
             state.tasks.tomorrow.removeAll { $0.id == taskId }
+            state.tasks.other.removeAll { $0.id == taskId }
             state.tasks.inProgress.removeAll { $0.id == taskId }
             state.overdueCount = UInt32(state.tasks.overdue.count)
             state.todayCount = UInt32(state.tasks.today.count)
@@ -413,6 +732,22 @@ class StatusBarController: NSObject {
         NSWorkspace.shared.open(url)
     }
 
+    @objc func openTodoist() {
+        // Close the menu immediately for better UX
+        statusItem.menu?.cancelTracking()
+
+        guard let url = URL(string: "https://app.todoist.com/app/today") else { return }
+        NSWorkspace.shared.open(url)
+    }
+
+    @objc func openGitHubInbox() {
+        // Close the menu immediately for better UX
+        statusItem.menu?.cancelTracking()
+
+        guard let url = URL(string: "https://github.com/notifications") else { return }
+        NSWorkspace.shared.open(url)
+    }
+
     @objc func snoozeTodoistTask(_ sender: NSMenuItem) {
         guard let payload = sender.representedObject as? TodoistSnoozeMenuPayload else { return }
         os_log(
@@ -438,6 +773,36 @@ class StatusBarController: NSObject {
         }
     }
     
+    @objc func snoozeGitHubNotification(_ sender: NSMenuItem) {
+        guard let payload = sender.representedObject as? GitHubSnoozeMenuPayload else { return }
+        os_log(
+            "Snooze GitHub notification account=%{public}@ thread=%{public}@ by %{public}@",
+            log: logger,
+            type: .info,
+            payload.accountName,
+            payload.threadId,
+            payload.durationLabel
+        )
+
+        // Close the menu immediately for better UX
+        statusItem.menu?.cancelTracking()
+
+        guard let core else { return }
+        DispatchQueue.global(qos: .utility).async { [weak self] in
+            do {
+                try core.snoozeGithubNotification(
+                    accountName: payload.accountName,
+                    threadId: payload.threadId,
+                    durationLabel: payload.durationLabel
+                )
+            } catch {
+                DispatchQueue.main.async { [weak self] in
+                    self?.showError("Failed to snooze GitHub notification: \(error.localizedDescription)")
+                }
+            }
+        }
+    }
+
     @objc func openGitHubNotification(_ sender: NSMenuItem) {
         guard let payload = sender.representedObject as? GitHubNotificationMenuPayload else { return }
         os_log(
@@ -456,6 +821,9 @@ class StatusBarController: NSObject {
             return
         }
         NSWorkspace.shared.open(url)
+
+        // Read-only mode leaves the notification unread
+        if currentState?.readOnly == true { return }
         
         // Optimistically remove the notification and update counts.
         if var state = currentState {
@@ -515,6 +883,227 @@ class StatusBarController: NSObject {
         }
     }
     
+    @objc func reopenTask(_ sender: NSMenuItem) {
+        guard let taskId = sender.representedObject as? String, let core else { return }
+        os_log("Reopen task: %{public}@", log: logger, type: .info, taskId)
+        DispatchQueue.global(qos: .utility).async { [weak self] in
+            do {
+                try core.reopenTask(taskId: taskId)
+            } catch {
+                DispatchQueue.main.async { [weak self] in
+                    self?.showError("Failed to reopen task: \(error.localizedDescription)")
+                }
+            }
+        }
+    }
+
+    private func undoCompletion(_ undoToken: String) {
+        guard let core else { return }
+        DispatchQueue.global(qos: .utility).async { [weak self] in
+            do {
+                try core.undoComplete(undoToken: undoToken)
+            } catch {
+                guard let self else { return }
+                os_log("Undo failed: %{public}@", log: self.logger, type: .error, error.localizedDescription)
+            }
+        }
+    }
+
+    /// Delete a Todoist task after confirming; unlike completing, it can't be undone
+    @objc func deleteTask(_ sender: NSMenuItem) {
+        guard let taskId = sender.representedObject as? String, let core else { return }
+        statusItem.menu?.cancelTracking()
+
+        let title = currentState?.tasks.overdue
+            .first(where: { $0.id == taskId })?.content
+            ?? [currentState?.tasks.today, currentState?.tasks.tomorrow, currentState?.tasks.other]
+                .compactMap { $0 }
+                .flatMap { $0 }
+                .first(where: { $0.id == taskId })?.content
+        let alert = NSAlert()
+        alert.messageText = "Delete \"\(title ?? "this task")\"?"
+        alert.informativeText = "The task is deleted from Todoist and can't be undone."
+        alert.alertStyle = .warning
+        alert.addButton(withTitle: "Delete")
+        alert.addButton(withTitle: "Cancel")
+        NSApp.activate(ignoringOtherApps: true)
+        guard alert.runModal() == .alertFirstButtonReturn else { return }
+
+        os_log("Delete task: %{public}@", log: logger, type: .info, taskId)
+        DispatchQueue.global(qos: .utility).async { [weak self] in
+            do {
+                try core.deleteTask(taskId: taskId)
+            } catch {
+                DispatchQueue.main.async { [weak self] in
+                    self?.showError("Failed to delete task: \(error.localizedDescription)")
+                }
+            }
+        }
+    }
+
+    /// Pick a project for a Todoist task and move it there
+    @objc func moveTask(_ sender: NSMenuItem) {
+        guard let taskId = sender.representedObject as? String, let core else { return }
+        statusItem.menu?.cancelTracking()
+
+        DispatchQueue.global(qos: .userInitiated).async { [weak self] in
+            let projects: [TodoistProject]
+            do {
+                projects = try core.listProjects()
+            } catch {
+                DispatchQueue.main.async { [weak self] in
+                    self?.showError("Failed to load projects: \(error.localizedDescription)")
+                }
+                return
+            }
+            DispatchQueue.main.async { [weak self] in
+                self?.pickProject(for: taskId, from: projects)
+            }
+        }
+    }
+
+    private func pickProject(for taskId: String, from projects: [TodoistProject]) {
+        guard let core, !projects.isEmpty else { return }
+        let picker = NSPopUpButton(frame: NSRect(x: 0, y: 0, width: 300, height: 26), pullsDown: false)
+        picker.addItems(withTitles: projects.map(\.name))
+        let alert = NSAlert()
+        alert.messageText = "Move to Project"
+        alert.accessoryView = picker
+        alert.addButton(withTitle: "Move")
+        alert.addButton(withTitle: "Cancel")
+        NSApp.activate(ignoringOtherApps: true)
+        guard alert.runModal() == .alertFirstButtonReturn else { return }
+        let project = projects[picker.indexOfSelectedItem]
+
+        os_log("Move task %{public}@ to project %{public}@", log: logger, type: .info, taskId, project.id)
+        DispatchQueue.global(qos: .utility).async { [weak self] in
+            do {
+                try core.moveTask(taskId: taskId, projectId: project.id)
+            } catch {
+                DispatchQueue.main.async { [weak self] in
+                    self?.showError("Failed to move task: \(error.localizedDescription)")
+                }
+            }
+        }
+    }
+
+    @objc func rescheduleOverdueToToday() {
+        guard let core else { return }
+        statusItem.menu?.cancelTracking()
+
+        os_log("Reschedule overdue tasks to today", log: logger, type: .info)
+        DispatchQueue.global(qos: .utility).async { [weak self] in
+            do {
+                _ = try core.rescheduleAllOverdueToToday()
+            } catch {
+                DispatchQueue.main.async { [weak self] in
+                    self?.showError("Failed to reschedule tasks: \(error.localizedDescription)")
+                }
+            }
+        }
+    }
+
+    /// Add a Todoist task in quick-add syntax and show how Todoist read it
+    @objc func quickAddTask() {
+        guard let core else { return }
+        statusItem.menu?.cancelTracking()
+
+        let field = NSTextField(frame: NSRect(x: 0, y: 0, width: 300, height: 24))
+        field.placeholderString = "buy milk tomorrow #Shopping @errands"
+        let alert = NSAlert()
+        alert.messageText = "Add Task"
+        alert.informativeText = "Dates, #projects and @labels are picked up like in Todoist."
+        alert.accessoryView = field
+        alert.addButton(withTitle: "Add")
+        alert.addButton(withTitle: "Cancel")
+        alert.window.initialFirstResponder = field
+        NSApp.activate(ignoringOtherApps: true)
+        guard alert.runModal() == .alertFirstButtonReturn else { return }
+        let text = field.stringValue
+
+        os_log("Quick add task", log: logger, type: .info)
+        DispatchQueue.global(qos: .utility).async { [weak self] in
+            do {
+                let added = try core.quickAddTask(text: text)
+                var details = [added.dueString, added.projectName.map { "#\($0)" }].compactMap { $0 }
+                details += added.labels.map { "@\($0)" }
+                DispatchQueue.main.async {
+                    let confirmation = NSAlert()
+                    confirmation.messageText = "Added \"\(added.content)\""
+                    confirmation.informativeText = details.isEmpty ? "No date, project or labels" : details.joined(separator: " · ")
+                    confirmation.runModal()
+                }
+            } catch {
+                DispatchQueue.main.async { [weak self] in
+                    self?.showError("Failed to add task: \(error.localizedDescription)")
+                }
+            }
+        }
+    }
+
+    /// Complete every Todoist task due today after confirming
+    @objc func completeAllToday() {
+        guard let core else { return }
+        statusItem.menu?.cancelTracking()
+
+        let count = currentState?.tasks.today.filter { $0.source == "todoist" && $0.canComplete }.count ?? 0
+        let alert = NSAlert()
+        alert.messageText = "Complete \(count) task\(count == 1 ? "" : "s") due today?"
+        alert.informativeText = "Linear issues are left alone."
+        alert.addButton(withTitle: "Complete All")
+        alert.addButton(withTitle: "Cancel")
+        NSApp.activate(ignoringOtherApps: true)
+        guard alert.runModal() == .alertFirstButtonReturn else { return }
+
+        os_log("Complete all today tasks", log: logger, type: .info)
+        DispatchQueue.global(qos: .utility).async { [weak self] in
+            do {
+                _ = try core.completeAllToday()
+            } catch {
+                DispatchQueue.main.async { [weak self] in
+                    self?.showError("Failed to complete tasks: \(error.localizedDescription)")
+                }
+            }
+        }
+    }
+
+    /// Put the setup and source health on the clipboard for a bug report
+    @objc func copyDiagnostics() {
+        guard let core else { return }
+        let pasteboard = NSPasteboard.general
+        pasteboard.clearContents()
+        pasteboard.setString(core.diagnostics().report, forType: .string)
+    }
+
+    /// Show only overdue tasks, or everything again
+    @objc func toggleFocusMode() {
+        let enabled = !(currentState?.focusMode ?? false)
+        os_log("Focus mode: %{public}@", log: logger, type: .info, enabled ? "on" : "off")
+        core?.setFocusMode(enabled: enabled)
+    }
+
+    /// Turn a source off or back on without editing config.toml
+    @objc func toggleSource(_ sender: NSMenuItem) {
+        guard let source = sender.representedObject as? String, let core else { return }
+        let enabled = currentState?.disabledSources.contains(source) ?? false
+        os_log("Source %{public}@: %{public}@", log: logger, type: .info, source, enabled ? "on" : "off")
+        DispatchQueue.global(qos: .utility).async { [weak self] in
+            do {
+                try core.setSourceEnabled(source: source, enabled: enabled)
+            } catch {
+                DispatchQueue.main.async { [weak self] in
+                    self?.showError("Failed to toggle \(source): \(error.localizedDescription)")
+                }
+            }
+        }
+    }
+
+    /// Stop Rust background work before the app exits
+    func shutdown() {
+        NSWorkspace.shared.notificationCenter.removeObserver(self)
+        core?.shutdown()
+    }
+
     @objc func quit() {
         os_log("Quit requested", log: logger, type: .info)
         NSApp.terminate(nil)
@@ -16,15 +16,39 @@ class TodoTrayEventHandler: EventHandler {
         }
     }
     
-    func onTaskCompleted(taskName: String) {
+    func onTaskCompleted(taskName: String, undoToken: String?) {
         DispatchQueue.main.async {
-            NotificationManager.shared.showTaskCompleted(taskName: taskName)
+            NotificationManager.shared.showTaskCompleted(taskName: taskName, undoToken: undoToken)
         }
     }
     
-    func onError(error: String) {
+    func onError(error: String, severity: ErrorSeverity) {
         DispatchQueue.main.async { [weak self] in
-            self?.controller?.showError(error)
+            switch severity {
+            case .blocking:
+                self?.controller?.showError(error)
+            case .transient:
+                // The menu keeps showing the last data; just let the user know
+                NotificationManager.shared.showRefreshFailed(message: error)
+            }
+        }
+    }
+
+    func onGithubAlert(notifications: [GithubNotification]) {
+        DispatchQueue.main.async {
+            NotificationManager.shared.showGitHubAlert(notifications: notifications)
+        }
+    }
+
+    func onAllClear() {
+        DispatchQueue.main.async {
+            NotificationManager.shared.showAllClear()
+        }
+    }
+
+    func onOverdueAlert(alert: OverdueAlert) {
+        DispatchQueue.main.async {
+            NotificationManager.shared.showOverdue(alert: alert)
         }
     }
 }
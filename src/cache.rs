@@ -0,0 +1,151 @@
+//! On-disk snapshot of the last refreshed state, used to seed the UI on launch.
+
+use crate::calendar::CalendarEventSection;
+use crate::core::{summarize_counts, AppState};
+use crate::github::GithubNotificationSection;
+use crate::json_store;
+use crate::task::{build_task_sections, TaskList};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const CACHE_VERSION: u32 = 1;
+
+/// Cached task and section data from the last refresh
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    version: u32,
+    tasks: TaskList,
+    github_notifications: Vec<GithubNotificationSection>,
+    calendar_events: Vec<CalendarEventSection>,
+    #[serde(default)]
+    last_updated: Option<String>,
+}
+
+impl StateSnapshot {
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            version: CACHE_VERSION,
+            tasks: state.tasks.clone(),
+            github_notifications: state.github_notifications.clone(),
+            calendar_events: state.calendar_events.clone(),
+            last_updated: state.last_updated.clone(),
+        }
+    }
+
+    /// Seed `state` with cached data. The state stays marked as loading until
+    /// the first live refresh replaces it.
+    pub fn apply_to(self, state: &mut AppState) {
+        state.overdue_count = self.tasks.overdue.len() as u32;
+        state.today_count = self.tasks.today.len() as u32;
+        state.tomorrow_count = self.tasks.tomorrow.len() as u32;
+        state.in_progress_count = self.tasks.in_progress.len() as u32;
+        state.github_notification_count = self
+            .github_notifications
+            .iter()
+            .map(|section| section.notifications.len() as u32)
+            .sum();
+        state.calendar_event_count = self
+            .calendar_events
+            .iter()
+            .map(|section| section.events.len() as u32)
+            .sum();
+        let mut tasks = self.tasks;
+        state.sections = build_task_sections(
+            &mut tasks,
+            state.grouping,
+            state.menu_layout,
+            state.max_items_per_section.map(|max| max as usize),
+        );
+        state.tasks = tasks;
+        state.github_notifications = self.github_notifications;
+        state.calendar_events = self.calendar_events;
+        state.last_updated = self.last_updated;
+        state.summary = summarize_counts(state);
+        state.is_loading = true;
+    }
+}
+
+/// Get the path to the state cache file
+pub fn cache_path() -> Result<PathBuf> {
+    json_store::path("state-cache.json")
+}
+
+/// Load a snapshot, ignoring missing, corrupt, or outdated cache files.
+pub fn load(path: &Path) -> Option<StateSnapshot> {
+    json_store::load_or_default::<Option<StateSnapshot>>(path)
+        .filter(|snapshot| snapshot.version == CACHE_VERSION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale::Language;
+    use crate::task::TodoTask;
+    use std::fs;
+
+    fn temp_cache_path(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("todo-tray-cache-{}-{}", std::process::id(), name))
+            .join("state-cache.json")
+    }
+
+    fn cached_state() -> AppState {
+        let task = TodoTask::from_linear(
+            "1".to_string(),
+            "ENG-1".to_string(),
+            "Ship it".to_string(),
+            None,
+            "In Progress".to_string(),
+            2,
+            None,
+            Language::English,
+        );
+        AppState {
+            tasks: TaskList {
+                in_progress: vec![task],
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn snapshot_round_trips_through_disk() {
+        let path = temp_cache_path("round-trip");
+
+        json_store::save(&path, &StateSnapshot::from_state(&cached_state())).unwrap();
+        let loaded = load(&path).expect("cache loads");
+        let mut state = AppState::default();
+        loaded.apply_to(&mut state);
+
+        assert_eq!(state.in_progress_count, 1);
+        assert_eq!(state.tasks.in_progress[0].content, "[ENG-1] Ship it");
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn seeded_state_is_marked_stale() {
+        let mut state = AppState::default();
+
+        StateSnapshot::from_state(&cached_state()).apply_to(&mut state);
+
+        assert!(state.is_loading);
+    }
+
+    #[test]
+    fn corrupt_or_outdated_cache_is_ignored() {
+        let path = temp_cache_path("corrupt");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        fs::write(&path, "{not json").unwrap();
+        assert!(load(&path).is_none());
+
+        let mut outdated =
+            serde_json::to_value(StateSnapshot::from_state(&cached_state())).unwrap();
+        outdated["version"] = serde_json::json!(0);
+        fs::write(&path, outdated.to_string()).unwrap();
+        assert!(load(&path).is_none());
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
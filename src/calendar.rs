@@ -1,52 +1,199 @@
 //! iCalendar feed client and parser for today's events.
 
+use crate::clock::{Clock, SystemClock};
+use crate::http::HttpTransport;
+use crate::locale::{Language, Text};
+use crate::time_format::TimeFormat;
 use anyhow::{Context, Result};
-use chrono::{DateTime, Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime, Utc};
+use chrono::{
+    DateTime, Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime, NaiveTime, Utc,
+};
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::Duration;
+use std::sync::Arc;
 
-#[derive(uniffi::Record, Clone, Debug)]
+#[derive(uniffi::Record, Clone, Debug, Serialize, Deserialize)]
 pub struct CalendarEvent {
     pub event_id: String,
     pub title: String,
     pub start_at: Option<String>, // RFC3339
     pub end_at: Option<String>,   // RFC3339
     pub display_time: String,
+    /// Date-only event covering whole days rather than a time slot
+    #[serde(default)]
+    pub is_all_day: bool,
+    #[serde(default)]
+    pub location: Option<String>,
+    /// The configured attendee's reply: "accepted", "declined", "tentative", ...
+    #[serde(default)]
+    pub my_response: Option<String>,
+    /// Marked `TRANSP:TRANSPARENT`: on the calendar, but the time shows as free
+    #[serde(default)]
+    pub is_free: bool,
     pub open_url: Option<String>,
 }
 
-#[derive(uniffi::Record, Clone, Debug, Default)]
+#[derive(uniffi::Record, Clone, Debug, Default, Serialize, Deserialize)]
 pub struct CalendarEventSection {
     pub account_name: String,
     pub events: Vec<CalendarEvent>,
+    /// Events that hadn't ended yet when the feed was fetched
+    #[serde(default)]
+    pub remaining_count: u32,
+}
+
+impl CalendarEventSection {
+    /// Set `remaining_count` to the events still running or upcoming at `now`.
+    ///
+    /// An event without an end time ends when it starts; one without either
+    /// always counts.
+    pub fn count_remaining(&mut self, now: DateTime<Utc>) {
+        self.remaining_count = self
+            .events
+            .iter()
+            .filter(|event| {
+                let end = event.end_at.as_deref().or(event.start_at.as_deref());
+                end.and_then(|end| DateTime::parse_from_rfc3339(end).ok())
+                    .is_none_or(|end| end > now)
+            })
+            .count() as u32;
+    }
 }
 
 pub struct CalendarClient {
+    /// Builds requests; `http` sends them
     client: Client,
+    http: Arc<dyn HttpTransport>,
     account_name: String,
+    /// Section title to use instead of the feed's own calendar name
+    display_name: Option<String>,
     ical_url: String,
+    time_format: TimeFormat,
+    language: Language,
+    /// Attendee address whose reply fills `CalendarEvent::my_response`
+    my_email: Option<String>,
+    hide_declined: bool,
+    filter: EventFilter,
 }
 
-impl CalendarClient {
-    pub fn new(account_name: String, ical_url: String) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .expect("Failed to create HTTP client");
+/// Events of a feed to leave out, besides those not happening today
+#[derive(Clone, Copy, Debug)]
+struct EventFilter {
+    /// Leave out events with `STATUS:CANCELLED`
+    hide_cancelled: bool,
+    /// Leave out timed events entirely outside these hours
+    working_hours: Option<WorkingHours>,
+    hide_all_day: bool,
+}
 
+/// What a feed shows unless configured otherwise: all but cancelled events
+impl Default for EventFilter {
+    fn default() -> Self {
+        Self {
+            hide_cancelled: true,
+            working_hours: None,
+            hide_all_day: false,
+        }
+    }
+}
+
+/// Local time of day range, e.g. 09:00-17:00
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WorkingHours {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl WorkingHours {
+    /// Parse "HH:MM" start and end times; the end must come after the start.
+    pub fn parse(start: &str, end: &str) -> Result<Self, String> {
+        let time = |value: &str| {
+            NaiveTime::parse_from_str(value.trim(), "%H:%M")
+                .map_err(|_| format!("Invalid working hours time '{}', expected HH:MM", value))
+        };
+        let (start, end) = (time(start)?, time(end)?);
+        if end <= start {
+            return Err(format!(
+                "Working hours must end after they start ({}-{})",
+                start.format("%H:%M"),
+                end.format("%H:%M")
+            ));
+        }
+        Ok(Self { start, end })
+    }
+
+    /// Whether an event from `start` to `end` overlaps these hours on `day`.
+    fn overlaps(&self, day: NaiveDate, start: DateTime<Local>, end: DateTime<Local>) -> bool {
+        let at = |time: NaiveTime| day.and_time(time).and_local_timezone(Local).earliest();
+        match (at(self.start), at(self.end)) {
+            (Some(window_start), Some(window_end)) => start < window_end && end > window_start,
+            // The hours fall in a DST gap; don't hide anything over it.
+            _ => true,
+        }
+    }
+}
+
+impl CalendarClient {
+    /// Requests are sent through `http`, shared with the other clients.
+    pub fn new(
+        account_name: String,
+        ical_url: String,
+        time_format: TimeFormat,
+        my_email: Option<String>,
+        hide_declined: bool,
+        http: Arc<dyn HttpTransport>,
+    ) -> Self {
+        let client = Client::new();
         Self {
             client,
+            http,
             account_name,
+            display_name: None,
             ical_url,
+            time_format,
+            language: Language::default(),
+            my_email,
+            hide_declined,
+            filter: EventFilter::default(),
         }
     }
 
+    /// Title the section `display_name` rather than the feed's X-WR-CALNAME.
+    pub fn with_display_name(mut self, display_name: Option<String>) -> Self {
+        self.display_name = display_name;
+        self
+    }
+
+    /// Label all-day events in `language`.
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Whether to leave out events with STATUS:CANCELLED (the default)
+    pub fn with_hide_cancelled(mut self, hide_cancelled: bool) -> Self {
+        self.filter.hide_cancelled = hide_cancelled;
+        self
+    }
+
+    /// Only show timed events that overlap `working_hours`, and leave out
+    /// all-day events when `hide_all_day` is set.
+    pub fn with_working_hours(
+        mut self,
+        working_hours: Option<WorkingHours>,
+        hide_all_day: bool,
+    ) -> Self {
+        self.filter.working_hours = working_hours;
+        self.filter.hide_all_day = hide_all_day;
+        self
+    }
+
+    #[tracing::instrument(name = "calendar_fetch", skip_all, fields(account = %self.account_name, events))]
     pub async fn get_today_events(&self) -> Result<CalendarEventSection> {
         let response = self
-            .client
-            .get(&self.ical_url)
-            .send()
+            .http
+            .send(self.client.get(&self.ical_url))
             .await
             .with_context(|| {
                 format!(
@@ -57,7 +204,7 @@ impl CalendarClient {
 
         if !response.status().is_success() {
             let status = response.status();
-            let body = response.text().await.unwrap_or_default();
+            let body = response.text().unwrap_or_default();
             return Err(anyhow::anyhow!(
                 "Calendar feed error for account '{}' ({}): {}",
                 self.account_name,
@@ -66,7 +213,7 @@ impl CalendarClient {
             ));
         }
 
-        let body = response.text().await.with_context(|| {
+        let body = response.text().with_context(|| {
             format!(
                 "Failed to read calendar feed body for account '{}'",
                 self.account_name
@@ -74,22 +221,38 @@ impl CalendarClient {
         })?;
 
         let parsed_feed = parse_ical_feed(&body);
-        let section_name = if parsed_feed.calendar_name.trim().is_empty() {
+        let section_name = if let Some(display_name) = &self.display_name {
+            display_name.clone()
+        } else if parsed_feed.calendar_name.trim().is_empty() {
             self.account_name.clone()
         } else {
             parsed_feed.calendar_name
         };
 
-        let now_local = Local::now();
-        let today = now_local.date_naive();
-        let day_start_local = local_midnight(today)?;
-        let day_end_local = day_start_local + ChronoDuration::days(1);
+        let (today, day_start_local, day_end_local) = day_window(&SystemClock)?;
 
         let mut events = parsed_feed
             .events
             .into_iter()
             .filter_map(|event| {
-                raw_event_to_calendar_event(event, today, day_start_local, day_end_local)
+                let my_response = self
+                    .my_email
+                    .as_deref()
+                    .and_then(|email| attendee_response(&event, email));
+                let mut event = raw_event_to_calendar_event(
+                    event,
+                    today,
+                    day_start_local,
+                    day_end_local,
+                    self.time_format,
+                    self.language,
+                    &self.filter,
+                )?;
+                event.my_response = my_response;
+                Some(event)
+            })
+            .filter(|event| {
+                !(self.hide_declined && event.my_response.as_deref() == Some("declined"))
             })
             .collect::<Vec<_>>();
 
@@ -99,10 +262,12 @@ impl CalendarClient {
             (None, Some(_)) => std::cmp::Ordering::Greater,
             (None, None) => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
         });
+        tracing::Span::current().record("events", events.len());
 
         Ok(CalendarEventSection {
             account_name: section_name,
             events,
+            remaining_count: 0,
         })
     }
 }
@@ -117,10 +282,18 @@ struct ParsedFeed {
 struct RawEvent {
     uid: Option<String>,
     summary: Option<String>,
+    location: Option<String>,
+    description: Option<String>,
+    /// (email, PARTSTAT) for each ATTENDEE line
+    attendees: Vec<(String, String)>,
     url: Option<String>,
     conference_url: Option<String>,
     starts_at: Option<EventTime>,
     ends_at: Option<EventTime>,
+    /// STATUS: TENTATIVE, CONFIRMED or CANCELLED
+    status: Option<String>,
+    /// TRANSP:TRANSPARENT, i.e. the event doesn't block time
+    transparent: bool,
 }
 
 #[derive(Clone)]
@@ -154,10 +327,20 @@ fn parse_ical_feed(content: &str) -> ParsedFeed {
             match name.as_str() {
                 "UID" => event.uid = Some(value),
                 "SUMMARY" => event.summary = Some(unescape_ical_text(&value)),
+                "LOCATION" => event.location = Some(unescape_ical_text(&value)),
+                "DESCRIPTION" => event.description = Some(unescape_ical_text(&value)),
+                "ATTENDEE" => {
+                    if let Some(partstat) = params.get("PARTSTAT") {
+                        let email = strip_mailto(value.trim()).to_string();
+                        event.attendees.push((email, partstat.to_string()));
+                    }
+                }
                 "URL" => event.url = Some(value),
                 "X-GOOGLE-CONFERENCE" => event.conference_url = Some(value),
                 "DTSTART" => event.starts_at = parse_event_time(&value, &params),
                 "DTEND" => event.ends_at = parse_event_time(&value, &params),
+                "STATUS" => event.status = Some(value.trim().to_uppercase()),
+                "TRANSP" => event.transparent = value.trim().eq_ignore_ascii_case("TRANSPARENT"),
                 _ => {}
             }
             continue;
@@ -176,12 +359,31 @@ fn raw_event_to_calendar_event(
     today: NaiveDate,
     day_start_local: DateTime<Local>,
     day_end_local: DateTime<Local>,
+    time_format: TimeFormat,
+    language: Language,
+    filter: &EventFilter,
 ) -> Option<CalendarEvent> {
+    if filter.hide_cancelled && raw.status.as_deref() == Some("CANCELLED") {
+        return None;
+    }
+    let is_free = raw.transparent;
     let open_url = raw
         .conference_url
         .as_deref()
         .and_then(normalize_event_url)
-        .or_else(|| raw.url.as_deref().and_then(normalize_event_url));
+        .or_else(|| {
+            find_meeting_link(&[
+                raw.url.as_deref(),
+                raw.location.as_deref(),
+                raw.description.as_deref(),
+            ])
+        })
+        .or_else(|| raw.url.as_deref().and_then(normalize_event_url))
+        .or_else(|| raw.location.as_deref().and_then(normalize_event_url));
+    let location = raw
+        .location
+        .map(|location| location.trim().to_string())
+        .filter(|location| !location.is_empty());
     let title = raw
         .summary
         .unwrap_or_else(|| "(Untitled event)".to_string());
@@ -203,7 +405,7 @@ fn raw_event_to_calendar_event(
             };
 
             let is_today = today >= start_date && today < end_exclusive;
-            if !is_today {
+            if !is_today || filter.hide_all_day {
                 return None;
             }
 
@@ -215,8 +417,12 @@ fn raw_event_to_calendar_event(
                 title,
                 start_at: Some(start_local.with_timezone(&Utc).to_rfc3339()),
                 end_at: Some(end_local.with_timezone(&Utc).to_rfc3339()),
-                display_time: "All day".to_string(),
-                open_url: open_url.clone(),
+                display_time: language.template(Text::AllDay).to_string(),
+                is_all_day: true,
+                location,
+                my_response: None,
+                is_free,
+                open_url,
             })
         }
         EventTime::DateTime(start_utc) => {
@@ -230,15 +436,27 @@ fn raw_event_to_calendar_event(
             if start_local >= day_end_local || end_local <= day_start_local {
                 return None;
             }
+            if let Some(hours) = filter.working_hours {
+                if !hours.overlaps(today, start_local, end_local) {
+                    return None;
+                }
+            }
 
-            let display_time = if end_local > start_local {
-                format!(
-                    "{}-{}",
-                    start_local.format("%H:%M"),
-                    end_local.format("%H:%M")
-                )
+            // Mark ends outside today with an ellipsis rather than showing a
+            // time from another day, which would read as "23:00-01:00".
+            let starts_before = start_local < day_start_local;
+            let ends_after = end_local > day_end_local;
+            let start_label = if starts_before {
+                "…".to_string()
             } else {
-                start_local.format("%H:%M").to_string()
+                time_format.format_time(&start_local)
+            };
+            let display_time = if ends_after {
+                format!("{}-…", start_label)
+            } else if end_local > start_local {
+                format!("{}-{}", start_label, time_format.format_time(&end_local))
+            } else {
+                start_label
             };
 
             Some(CalendarEvent {
@@ -247,12 +465,82 @@ fn raw_event_to_calendar_event(
                 start_at: Some(start_local.with_timezone(&Utc).to_rfc3339()),
                 end_at: Some(end_local.with_timezone(&Utc).to_rfc3339()),
                 display_time,
+                is_all_day: false,
+                location,
+                my_response: None,
+                is_free,
                 open_url,
             })
         }
     }
 }
 
+/// Reply of the attendee with address `email`, lowercased (e.g. "declined").
+fn attendee_response(event: &RawEvent, email: &str) -> Option<String> {
+    let email = email.trim();
+    event
+        .attendees
+        .iter()
+        .find(|(attendee, _)| attendee.eq_ignore_ascii_case(email))
+        .map(|(_, partstat)| partstat.to_lowercase())
+}
+
+/// Video call hosts, most preferred first. Subdomains match too.
+const MEETING_HOSTS: [&str; 3] = ["zoom.us", "teams.microsoft.com", "meet.google.com"];
+
+/// First video call link in `texts`, preferring providers earlier in `MEETING_HOSTS`.
+fn find_meeting_link(texts: &[Option<&str>]) -> Option<String> {
+    let links: Vec<&str> = texts
+        .iter()
+        .flatten()
+        .flat_map(|text| find_urls(text))
+        .collect();
+    MEETING_HOSTS.iter().find_map(|provider| {
+        links
+            .iter()
+            .find(|link| {
+                let host = url_host(link);
+                host == *provider || host.ends_with(&format!(".{}", provider))
+            })
+            .map(|link| link.to_string())
+    })
+}
+
+/// http(s) URLs appearing anywhere in free text.
+fn find_urls(text: &str) -> Vec<&str> {
+    let mut urls = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("http") {
+        let candidate = &rest[start..];
+        if !candidate.starts_with("https://") && !candidate.starts_with("http://") {
+            rest = &candidate["http".len()..];
+            continue;
+        }
+        let end = candidate
+            .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\'' | ')' | ']'))
+            .unwrap_or(candidate.len());
+        urls.push(candidate[..end].trim_end_matches(['.', ',', ';']));
+        rest = &candidate[end..];
+    }
+    urls
+}
+
+fn url_host(url: &str) -> String {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    without_scheme
+        .split(['/', '?', '#', ':'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+fn strip_mailto(value: &str) -> &str {
+    match value.get(..7) {
+        Some(scheme) if scheme.eq_ignore_ascii_case("mailto:") => &value[7..],
+        _ => value,
+    }
+}
+
 fn normalize_event_url(value: &str) -> Option<String> {
     let trimmed = value.trim();
     if trimmed.starts_with("http://") || trimmed.starts_with("https://") {
@@ -335,6 +623,13 @@ fn unescape_ical_text(value: &str) -> String {
         .replace("\\\\", "\\")
 }
 
+/// Today's local date with the start and end of its day, as of `clock`
+fn day_window(clock: &dyn Clock) -> Result<(NaiveDate, DateTime<Local>, DateTime<Local>)> {
+    let today = clock.local_now().date_naive();
+    let day_start = local_midnight(today)?;
+    Ok((today, day_start, day_start + ChronoDuration::days(1)))
+}
+
 fn local_midnight(date: NaiveDate) -> Result<DateTime<Local>> {
     let naive_midnight = date
         .and_hms_opt(0, 0, 0)
@@ -347,7 +642,230 @@ fn local_midnight(date: NaiveDate) -> Result<DateTime<Local>> {
 
 #[cfg(test)]
 mod tests {
-    use super::parse_ical_feed;
+    use super::{
+        attendee_response, day_window, find_meeting_link, local_midnight, parse_ical_feed,
+        raw_event_to_calendar_event, CalendarClient, CalendarEvent, CalendarEventSection,
+        EventFilter, EventTime, RawEvent, WorkingHours,
+    };
+    use crate::clock::FixedClock;
+    use crate::http::MockTransport;
+    use crate::locale::Language;
+    use crate::time_format::TimeFormat;
+    use chrono::{Duration, Local, NaiveDate, TimeZone, Utc};
+
+    #[test]
+    fn parses_and_unescapes_location() {
+        let ics = "BEGIN:VEVENT\r\nUID:abc\r\nLOCATION:Room 4\\, Building B\r\nDTSTART:20260224T090000Z\r\nEND:VEVENT\r\n";
+        let parsed = parse_ical_feed(ics);
+        assert_eq!(
+            parsed.events[0].location.as_deref(),
+            Some("Room 4, Building B")
+        );
+    }
+
+    #[test]
+    fn extracts_zoom_link_from_description() {
+        let ics = "BEGIN:VEVENT\r\nUID:abc\r\nDESCRIPTION:Agenda: https://docs.example.com/agenda\\n\\nJoin Zoom\r\n  Meeting\\nhttps://us02web.zoom.us/j/123456?pwd=abc.\\nMeeting ID: 123\r\nEND:VEVENT\r\n";
+        let parsed = parse_ical_feed(ics);
+        let event = &parsed.events[0];
+
+        assert_eq!(
+            find_meeting_link(&[event.url.as_deref(), event.description.as_deref()]).as_deref(),
+            Some("https://us02web.zoom.us/j/123456?pwd=abc")
+        );
+    }
+
+    #[test]
+    fn meeting_link_prefers_provider_order_over_position() {
+        let text = "Teams: https://teams.microsoft.com/l/meetup-join/1 or <https://zoom.us/j/9>";
+        assert_eq!(
+            find_meeting_link(&[Some(text)]).as_deref(),
+            Some("https://zoom.us/j/9")
+        );
+        assert_eq!(
+            find_meeting_link(&[Some("https://example.com/notzoom.us")]),
+            None
+        );
+    }
+
+    #[test]
+    fn matches_declined_attendee_to_configured_email() {
+        let ics = "BEGIN:VEVENT\r\nUID:abc\r\nATTENDEE;CN=Someone;PARTSTAT=ACCEPTED:mailto:someone@example.com\r\nATTENDEE;CN=Me;PARTSTAT=DECLINED;RSVP=TRUE:MAILTO:me@example.com\r\nEND:VEVENT\r\n";
+        let parsed = parse_ical_feed(ics);
+        let event = &parsed.events[0];
+
+        assert_eq!(
+            attendee_response(event, " Me@Example.com").as_deref(),
+            Some("declined")
+        );
+        assert_eq!(
+            attendee_response(event, "someone@example.com").as_deref(),
+            Some("accepted")
+        );
+        assert_eq!(attendee_response(event, "other@example.com"), None);
+    }
+
+    #[test]
+    fn location_url_is_an_open_url_fallback() {
+        let day = NaiveDate::from_ymd_opt(2026, 2, 24).unwrap();
+        let day_start = local_midnight(day).unwrap();
+        let event = raw_event_to_calendar_event(
+            RawEvent {
+                location: Some("https://zoom.us/j/123".to_string()),
+                starts_at: Some(EventTime::Date(day)),
+                ..Default::default()
+            },
+            day,
+            day_start,
+            day_start + Duration::days(1),
+            TimeFormat::default(),
+            Language::English,
+            &EventFilter::default(),
+        )
+        .unwrap();
+
+        assert_eq!(event.open_url.as_deref(), Some("https://zoom.us/j/123"));
+        assert_eq!(event.location.as_deref(), Some("https://zoom.us/j/123"));
+    }
+
+    fn event_on(day: NaiveDate, starts_at: EventTime, ends_at: EventTime) -> CalendarEvent {
+        let day_start = local_midnight(day).unwrap();
+        raw_event_to_calendar_event(
+            RawEvent {
+                uid: Some("event".to_string()),
+                summary: Some("Event".to_string()),
+                starts_at: Some(starts_at),
+                ends_at: Some(ends_at),
+                ..Default::default()
+            },
+            day,
+            day_start,
+            day_start + Duration::days(1),
+            TimeFormat::default(),
+            Language::English,
+            &EventFilter::default(),
+        )
+        .expect("event overlaps the day")
+    }
+
+    #[test]
+    fn date_events_are_flagged_all_day() {
+        let day = NaiveDate::from_ymd_opt(2026, 2, 24).unwrap();
+
+        let single = event_on(
+            day,
+            EventTime::Date(day),
+            EventTime::Date(day + Duration::days(1)),
+        );
+        assert!(single.is_all_day);
+        assert_eq!(single.display_time, "All day");
+
+        let spanning = event_on(
+            day,
+            EventTime::Date(day - Duration::days(1)),
+            EventTime::Date(day + Duration::days(2)),
+        );
+        assert!(spanning.is_all_day);
+    }
+
+    #[test]
+    fn timed_events_are_not_all_day() {
+        let day = NaiveDate::from_ymd_opt(2026, 2, 24).unwrap();
+        let start = local_midnight(day).unwrap() + Duration::hours(9);
+        let event = event_on(
+            day,
+            EventTime::DateTime(start.with_timezone(&Utc)),
+            EventTime::DateTime((start + Duration::minutes(30)).with_timezone(&Utc)),
+        );
+
+        assert!(!event.is_all_day);
+        assert_eq!(event.display_time, "09:00-09:30");
+    }
+
+    #[test]
+    fn working_hours_leave_out_events_entirely_outside_them() {
+        let day = NaiveDate::from_ymd_opt(2026, 2, 24).unwrap();
+        let day_start = local_midnight(day).unwrap();
+        let filter = |hide_all_day| EventFilter {
+            working_hours: Some(WorkingHours::parse("09:00", "17:00").unwrap()),
+            hide_all_day,
+            ..Default::default()
+        };
+        let keeps = |starts_at, ends_at, filter: &EventFilter| {
+            raw_event_to_calendar_event(
+                RawEvent {
+                    starts_at: Some(starts_at),
+                    ends_at: Some(ends_at),
+                    ..Default::default()
+                },
+                day,
+                day_start,
+                day_start + Duration::days(1),
+                TimeFormat::default(),
+                Language::English,
+                filter,
+            )
+            .is_some()
+        };
+        let timed = |start_minutes: i64, end_minutes: i64| {
+            (
+                EventTime::DateTime(
+                    (day_start + Duration::minutes(start_minutes)).with_timezone(&Utc),
+                ),
+                EventTime::DateTime(
+                    (day_start + Duration::minutes(end_minutes)).with_timezone(&Utc),
+                ),
+            )
+        };
+
+        let (start, end) = timed(8 * 60, 8 * 60 + 45);
+        assert!(!keeps(start, end, &filter(false)));
+        let (start, end) = timed(8 * 60 + 30, 9 * 60 + 30);
+        assert!(keeps(start, end, &filter(false)));
+        let (start, end) = timed(17 * 60, 18 * 60);
+        assert!(!keeps(start, end, &filter(false)));
+
+        let all_day = || {
+            (
+                EventTime::Date(day),
+                EventTime::Date(day + Duration::days(1)),
+            )
+        };
+        let (start, end) = all_day();
+        assert!(keeps(start, end, &filter(false)));
+        let (start, end) = all_day();
+        assert!(!keeps(start, end, &filter(true)));
+    }
+
+    #[test]
+    fn working_hours_must_be_valid_times_in_order() {
+        assert!(WorkingHours::parse("9:00", "17:30").is_ok());
+        assert!(WorkingHours::parse("9am", "17:00").is_err());
+        assert!(WorkingHours::parse("17:00", "09:00").is_err());
+    }
+
+    fn timed_event_on(day: NaiveDate, start_hours: i64, end_hours: i64) -> CalendarEvent {
+        let midnight = local_midnight(day).unwrap();
+        event_on(
+            day,
+            EventTime::DateTime((midnight + Duration::hours(start_hours)).with_timezone(&Utc)),
+            EventTime::DateTime((midnight + Duration::hours(end_hours)).with_timezone(&Utc)),
+        )
+    }
+
+    #[test]
+    fn event_from_yesterday_shows_continuation_start() {
+        let day = NaiveDate::from_ymd_opt(2026, 2, 24).unwrap();
+        assert_eq!(timed_event_on(day, -2, 1).display_time, "…-01:00");
+    }
+
+    #[test]
+    fn event_into_tomorrow_shows_continuation_end() {
+        let day = NaiveDate::from_ymd_opt(2026, 2, 24).unwrap();
+        assert_eq!(timed_event_on(day, 23, 25).display_time, "23:00-…");
+        assert_eq!(timed_event_on(day, -1, 25).display_time, "…-…");
+        assert_eq!(timed_event_on(day, 22, 24).display_time, "22:00-00:00");
+    }
 
     #[test]
     fn parses_calendar_name_and_event_fields() {
@@ -366,4 +884,109 @@ mod tests {
             Some("https://meet.google.com/nsn-dwjm-vrk")
         );
     }
+
+    #[test]
+    fn remaining_count_skips_events_that_already_ended() {
+        let now = Utc::now();
+        let event = |title: &str, start: chrono::DateTime<Utc>| CalendarEvent {
+            event_id: title.to_string(),
+            title: title.to_string(),
+            start_at: Some(start.to_rfc3339()),
+            end_at: Some((start + Duration::hours(1)).to_rfc3339()),
+            display_time: String::new(),
+            is_all_day: false,
+            location: None,
+            my_response: None,
+            is_free: false,
+            open_url: None,
+        };
+        let mut section = CalendarEventSection {
+            account_name: "Work".to_string(),
+            events: vec![
+                event("Standup", now - Duration::hours(3)),
+                event("Review", now + Duration::hours(2)),
+            ],
+            remaining_count: 0,
+        };
+
+        section.count_remaining(now);
+
+        assert_eq!(section.events.len(), 2);
+        assert_eq!(section.remaining_count, 1);
+    }
+
+    #[test]
+    fn day_window_spans_the_clocks_local_day() {
+        let late = Local.with_ymd_and_hms(2026, 3, 10, 23, 59, 0).unwrap();
+
+        let (today, start, end) = day_window(&FixedClock(late.with_timezone(&Utc))).unwrap();
+
+        assert_eq!(today, NaiveDate::from_ymd_opt(2026, 3, 10).unwrap());
+        assert_eq!(start, local_midnight(today).unwrap());
+        assert_eq!(end, local_midnight(today + Duration::days(1)).unwrap());
+        assert!(start <= late && late < end);
+    }
+
+    #[tokio::test]
+    async fn display_name_overrides_the_feed_calendar_name() {
+        let ics = "BEGIN:VCALENDAR\r\nX-WR-CALNAME:Team Calendar\r\nEND:VCALENDAR\r\n";
+        let http = std::sync::Arc::new(MockTransport::default());
+        http.respond(200, ics);
+        http.respond(200, ics);
+        let client = |display_name: Option<&str>| {
+            CalendarClient::new(
+                "work".to_string(),
+                "https://example.com/feed.ics".to_string(),
+                TimeFormat::default(),
+                None,
+                false,
+                http.clone(),
+            )
+            .with_display_name(display_name.map(str::to_string))
+        };
+
+        let named = client(Some("Work")).get_today_events().await.unwrap();
+        assert_eq!(named.account_name, "Work");
+        let unnamed = client(None).get_today_events().await.unwrap();
+        assert_eq!(unnamed.account_name, "Team Calendar");
+    }
+
+    #[tokio::test]
+    async fn cancelled_events_are_left_out_and_transparent_ones_are_free() {
+        let today = Local::now().format("%Y%m%d");
+        let event = |uid: &str, extra: &str| {
+            format!(
+                "BEGIN:VEVENT\r\nUID:{uid}\r\nSUMMARY:{uid}\r\nDTSTART;VALUE=DATE:{today}\r\n{extra}END:VEVENT\r\n"
+            )
+        };
+        let ics = format!(
+            "BEGIN:VCALENDAR\r\n{}{}{}END:VCALENDAR\r\n",
+            event("confirmed", "STATUS:CONFIRMED\r\n"),
+            event("cancelled", "STATUS:CANCELLED\r\n"),
+            event("focus", "TRANSP:TRANSPARENT\r\n"),
+        );
+        let http = std::sync::Arc::new(MockTransport::default());
+        http.respond(200, &ics);
+        http.respond(200, &ics);
+        let client = |hide_cancelled: bool| {
+            CalendarClient::new(
+                "work".to_string(),
+                "https://example.com/feed.ics".to_string(),
+                TimeFormat::default(),
+                None,
+                false,
+                http.clone(),
+            )
+            .with_hide_cancelled(hide_cancelled)
+        };
+
+        let section = client(true).get_today_events().await.unwrap();
+        let titles: Vec<_> = section.events.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, vec!["confirmed", "focus"]);
+        assert!(!section.events[0].is_free);
+        assert!(section.events[1].is_free);
+
+        let section = client(false).get_today_events().await.unwrap();
+        assert_eq!(section.events.len(), 3);
+    }
 }
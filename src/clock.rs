@@ -0,0 +1,33 @@
+//! Source of the current time, so date logic can be tested at fixed instants
+
+use chrono::{DateTime, Local, Utc};
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+
+    fn local_now(&self) -> DateTime<Local> {
+        self.now().with_timezone(&Local)
+    }
+}
+
+/// The system clock, used everywhere outside tests
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock stopped at one instant
+#[cfg(test)]
+#[derive(Clone, Copy, Debug)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+#[cfg(test)]
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
@@ -1,10 +1,20 @@
 //! Configuration management
 
+use crate::config_migration;
+use crate::github::{PageLimits, MAX_PAGE_SIZE};
+use crate::http::TlsSettings;
+use crate::locale::Language;
+use crate::task::{AllDayPosition, GroupingMode, MenuLayout};
+use crate::time_format::TimeFormat;
 use anyhow::{Context, Result};
 use serde::Deserialize;
 use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Commented starting config for new users, with a placeholder token
+const TEMPLATE: &str = include_str!("config_template.toml");
 
 /// Application configuration
 #[derive(Debug, Deserialize)]
@@ -12,9 +22,71 @@ pub struct Config {
     #[serde(alias = "api_token")]
     pub todoist_api_token: String,
 
+    /// Todoist filter for which tasks to fetch (default "today | overdue | tomorrow")
+    #[serde(default)]
+    pub task_filter_query: Option<String>,
+
+    /// Name of a filter saved in Todoist to use instead of `task_filter_query`
+    #[serde(default)]
+    pub todoist_filter: Option<String>,
+
+    /// Todoist API root to use instead of the public one, e.g. a proxy or mock server
+    #[serde(default)]
+    pub todoist_api_base: Option<String>,
+
+    /// Proxy for plain HTTP requests from every client, overriding `HTTP_PROXY`
+    #[serde(default)]
+    pub http_proxy: Option<String>,
+
+    /// Proxy for HTTPS requests from every client, overriding `HTTPS_PROXY`
+    #[serde(default)]
+    pub https_proxy: Option<String>,
+
+    /// Comma-separated hosts that bypass `http_proxy` and `https_proxy`; needs
+    /// one of them set
+    #[serde(default)]
+    pub no_proxy: Option<String>,
+
+    /// Fetch only changed Todoist tasks between refreshes (default filter only)
+    #[serde(default)]
+    pub todoist_incremental_sync: bool,
+
+    /// Look up each Todoist task's next reminder (needs a plan with reminders)
+    #[serde(default)]
+    pub show_reminders: bool,
+
+    /// Mark Todoist tasks as urgent by their deadline when it's closer than
+    /// their due date
+    #[serde(default)]
+    pub deadline_urgency: bool,
+
+    /// Show today's upcoming timed tasks as a countdown ("in 2h")
+    #[serde(default)]
+    pub countdown_times: bool,
+
+    /// Hide Todoist tasks in these projects, by name or id
+    #[serde(default)]
+    pub exclude_projects: Vec<String>,
+
+    /// Only show Todoist tasks with at least one of these labels
+    #[serde(default)]
+    pub include_labels: Vec<String>,
+
+    /// Hide Todoist tasks with any of these labels
+    #[serde(default)]
+    pub exclude_labels: Vec<String>,
+
     #[serde(default)]
     pub linear_api_token: Option<String>,
 
+    /// Linear workflow states to show, matched against state type or name
+    #[serde(default = "default_linear_states")]
+    pub linear_states: Vec<String>,
+
+    /// Workflow state (id, type or name) that completing a Linear issue moves it to
+    #[serde(default = "default_linear_complete_state")]
+    pub linear_complete_state: String,
+
     #[serde(default)]
     pub github_accounts: Vec<GithubAccountConfig>,
 
@@ -24,6 +96,88 @@ pub struct Config {
     #[serde(default = "default_snooze_durations")]
     pub snooze_durations: Vec<String>,
 
+    /// Move day snoozes that land on a Saturday or Sunday on to Monday
+    #[serde(default)]
+    pub skip_weekends: bool,
+
+    /// How tasks are sectioned in the menu: "by_due" (default) or "by_project"
+    #[serde(default)]
+    pub grouping: GroupingMode,
+
+    /// Order of the task, GitHub and calendar blocks, e.g. ["calendar", "tasks", "github"]
+    #[serde(default)]
+    pub section_order: Vec<String>,
+
+    /// "detailed" (default) lists every task; "compact" shows a few per section
+    #[serde(default)]
+    pub menu_layout: MenuLayout,
+
+    /// Show at most this many tasks per menu section, with "…and N more" for the rest
+    #[serde(default)]
+    pub max_items_per_section: Option<usize>,
+
+    /// Only show tomorrow's tasks from this hour (0-23) on; unset shows them all day
+    #[serde(default)]
+    pub show_tomorrow_after_hour: Option<u8>,
+
+    /// Only notify about overdue tasks at this priority or higher, 1 (p1) to
+    /// 4 (p4); unset notifies about all of them
+    #[serde(default)]
+    pub notify_min_priority: Option<u8>,
+
+    /// Hide the next instance of a just-completed recurring Todoist task until
+    /// the next full refresh
+    #[serde(default)]
+    pub collapse_completed_recurring: bool,
+
+    /// Today's tasks without a time of day: "first" or "last" (default) in the today group
+    #[serde(default)]
+    pub all_day_tasks: AllDayPosition,
+
+    /// Show Todoist titles without markdown link and emphasis syntax
+    #[serde(default)]
+    pub clean_task_titles: bool,
+
+    /// With `clean_task_titles`, also drop emoji at the start of titles
+    #[serde(default)]
+    pub strip_title_emoji: bool,
+
+    /// Count only calendar events that haven't ended yet in the menu bar and summary
+    #[serde(default)]
+    pub count_remaining_events: bool,
+
+    /// Menu bar title template with placeholders such as "{overdue}" and "{prs}"
+    #[serde(default)]
+    pub title_format: Option<String>,
+
+    /// Clock used for display times: "24h" (default) or "12h"
+    #[serde(default)]
+    pub time_format: TimeFormat,
+
+    /// Language of relative times and labels: "en" (default) or "sv"
+    #[serde(default, alias = "locale")]
+    pub language: Language,
+
+    /// Timed tasks due within this many minutes are flagged as due soon
+    #[serde(default = "default_due_soon_minutes")]
+    pub due_soon_minutes: u32,
+
+    /// Mark data as stale after this many refreshes in a row reach no source (0 disables)
+    #[serde(default = "default_stale_after_failures")]
+    pub stale_after_failures: u32,
+
+    /// Seconds a completed task can be undone before it is closed (0 closes immediately)
+    #[serde(default = "default_undo_window_seconds")]
+    pub undo_window_seconds: u64,
+
+    /// A manual refresh shows data refreshed within this many seconds while it reloads (0 disables)
+    #[serde(default = "default_max_stale_seconds")]
+    pub max_stale_seconds: u64,
+
+    /// Show data without changing anything: completing, snoozing and marking read are refused
+    #[serde(default)]
+    pub read_only: bool,
+
     #[serde(default)]
     pub autostart: bool,
 }
@@ -33,6 +187,34 @@ pub struct Config {
 pub struct GithubAccountConfig {
     pub name: String,
     pub token: String,
+    /// Menu section title to show instead of `name`
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Notifications requested per page, at most 50 (default 50)
+    #[serde(default = "default_github_per_page")]
+    pub per_page: usize,
+    /// Pages fetched per refresh at most (default 10)
+    #[serde(default = "default_github_max_pages")]
+    pub max_pages: usize,
+    /// Stop fetching once this many unread notifications are found
+    #[serde(default)]
+    pub max_notifications: Option<usize>,
+    #[serde(flatten)]
+    pub tls: TlsConfig,
+}
+
+impl GithubAccountConfig {
+    pub fn display_name(&self) -> Option<&str> {
+        non_blank(self.display_name.as_deref())
+    }
+
+    pub fn page_limits(&self) -> PageLimits {
+        PageLimits {
+            per_page: self.per_page,
+            max_pages: self.max_pages,
+            max_notifications: self.max_notifications,
+        }
+    }
 }
 
 /// iCal feed configuration
@@ -41,12 +223,125 @@ pub struct CalendarFeedConfig {
     pub name: String,
     #[serde(alias = "url")]
     pub ical_url: String,
+    /// Menu section title to show instead of the feed's own calendar name
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Your attendee address in this calendar, to read your meeting replies
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Leave out events you declined (needs `email`)
+    #[serde(default)]
+    pub hide_declined: bool,
+    /// Leave out events marked cancelled in the feed
+    #[serde(default = "default_true")]
+    pub hide_cancelled: bool,
+    /// Start and end ("HH:MM") of the hours whose timed events are shown
+    #[serde(default)]
+    pub working_hours: Option<(String, String)>,
+    /// Leave out all-day events
+    #[serde(default)]
+    pub hide_all_day: bool,
+    #[serde(flatten)]
+    pub tls: TlsConfig,
+}
+
+impl CalendarFeedConfig {
+    pub fn display_name(&self) -> Option<&str> {
+        non_blank(self.display_name.as_deref())
+    }
+}
+
+/// Certificates for a feed or account on a self-hosted server
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct TlsConfig {
+    /// PEM CA certificate to trust, for servers signed by a private CA
+    #[serde(default)]
+    pub ca_cert_path: Option<String>,
+    /// PEM client certificate to present, together with `client_key_path`
+    #[serde(default)]
+    pub client_cert_path: Option<String>,
+    /// PKCS#8 PEM private key of `client_cert_path`
+    #[serde(default)]
+    pub client_key_path: Option<String>,
+}
+
+impl TlsConfig {
+    /// Paths to hand to the HTTP client; the files themselves are checked
+    /// when it is built.
+    pub fn settings(&self) -> Result<TlsSettings, String> {
+        let path = |value: &Option<String>| non_blank(value.as_deref()).map(PathBuf::from);
+        let client_cert = match (path(&self.client_cert_path), path(&self.client_key_path)) {
+            (Some(cert), Some(key)) => Some((cert, key)),
+            (None, None) => None,
+            _ => {
+                return Err("client_cert_path and client_key_path must be set together".to_string())
+            }
+        };
+        Ok(TlsSettings {
+            ca_cert_path: path(&self.ca_cert_path),
+            client_cert,
+        })
+    }
+}
+
+fn non_blank(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
 }
 
 pub fn default_snooze_durations() -> Vec<String> {
     vec!["30m".to_string(), "1d".to_string()]
 }
 
+pub fn default_linear_states() -> Vec<String> {
+    vec!["started".to_string()]
+}
+
+fn default_github_per_page() -> usize {
+    PageLimits::default().per_page
+}
+
+fn default_github_max_pages() -> usize {
+    PageLimits::default().max_pages
+}
+
+fn default_stale_after_failures() -> u32 {
+    3
+}
+
+fn default_undo_window_seconds() -> u64 {
+    5
+}
+
+fn default_max_stale_seconds() -> u64 {
+    300
+}
+
+fn default_due_soon_minutes() -> u32 {
+    60
+}
+
+fn default_linear_complete_state() -> String {
+    "completed".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Parse config text, renaming legacy keys first when there are any. Configs
+/// without them are read straight from the text so errors keep their line
+/// and column.
+fn parse_with_migrations(content: &str) -> Result<(Config, Vec<String>), toml::de::Error> {
+    let mut table: toml::Table = toml::from_str(content)?;
+    let migrations = config_migration::migrate(&mut table);
+    let config = if migrations.is_empty() {
+        toml::from_str(content)?
+    } else {
+        toml::Value::Table(table).try_into()?
+    };
+    Ok((config, migrations))
+}
+
 impl Config {
     /// Load configuration from disk
     pub fn load() -> Result<Self> {
@@ -58,7 +353,8 @@ impl Config {
                 Please create it with your Todoist API token:\n\n\
                 mkdir -p ~/Library/Application\\ Support/todo-tray\n\
                 echo 'todoist_api_token = \"YOUR_TOKEN_HERE\"' > ~/Library/Application\\ Support/todo-tray/config.toml\n\
-                # Optional: linear_api_token = \"YOUR_LINEAR_API_KEY\"\n\n\
+                # Optional: linear_api_token = \"YOUR_LINEAR_API_KEY\"\n\
+                # Optional: linear_states = [\"started\", \"In Review\"]\n\n\
                 # Optional: multiple GitHub accounts\n\
                 [[github_accounts]]\n\
                 name = \"work\"\n\
@@ -76,13 +372,20 @@ impl Config {
 
         let content = fs::read_to_string(&config_path).context("Failed to read config file")?;
 
-        let config: Config = toml::from_str(&content).map_err(|err| {
+        let parse_error = |err: toml::de::Error| {
             anyhow::anyhow!(
                 "Failed to parse config file at {:?}: {}",
                 config_path,
                 err
             )
-        })?;
+        };
+        let (mut config, migrations) = parse_with_migrations(&content).map_err(parse_error)?;
+        if !migrations.is_empty() {
+            tracing::warn!(
+                migrations = %migrations.join("; "),
+                "Config uses old keys; they still work, but consider updating config.toml"
+            );
+        }
 
         if config.todoist_api_token.is_empty() || config.todoist_api_token == "YOUR_TOKEN_HERE" {
             return Err(anyhow::anyhow!(
@@ -91,6 +394,62 @@ impl Config {
             ));
         }
 
+        config.todoist_api_base = config
+            .todoist_api_base
+            .as_deref()
+            .map(|base| base.trim().trim_end_matches('/').to_string())
+            .filter(|base| !base.is_empty());
+        if let Some(base) = &config.todoist_api_base {
+            if !(base.starts_with("http://") || base.starts_with("https://")) {
+                return Err(anyhow::anyhow!(
+                    "todoist_api_base must be an http:// or https:// URL in {:?}",
+                    config_path
+                ));
+            }
+        }
+
+        if config
+            .linear_states
+            .iter()
+            .any(|state| state.trim().is_empty())
+        {
+            return Err(anyhow::anyhow!(
+                "Linear state names cannot be empty in {:?}",
+                config_path
+            ));
+        }
+
+        if config
+            .show_tomorrow_after_hour
+            .is_some_and(|hour| hour > 23)
+        {
+            return Err(anyhow::anyhow!(
+                "show_tomorrow_after_hour must be between 0 and 23 in {:?}",
+                config_path
+            ));
+        }
+
+        if config
+            .notify_min_priority
+            .is_some_and(|priority| !(1..=4).contains(&priority))
+        {
+            return Err(anyhow::anyhow!(
+                "notify_min_priority must be between 1 and 4 in {:?}",
+                config_path
+            ));
+        }
+
+        if let Err(message) = crate::section_order::parse(&config.section_order) {
+            return Err(anyhow::anyhow!("{} in {:?}", message, config_path));
+        }
+
+        if config.max_items_per_section == Some(0) {
+            return Err(anyhow::anyhow!(
+                "max_items_per_section must be at least 1 in {:?}",
+                config_path
+            ));
+        }
+
         let mut seen_names = HashSet::new();
         for account in &config.github_accounts {
             let name = account.name.trim();
@@ -111,6 +470,23 @@ impl Config {
                 ));
             }
 
+            if !(1..=MAX_PAGE_SIZE).contains(&account.per_page) {
+                return Err(anyhow::anyhow!(
+                    "GitHub per_page for account '{}' must be between 1 and {} in {:?}",
+                    name,
+                    MAX_PAGE_SIZE,
+                    config_path
+                ));
+            }
+
+            if account.max_pages == 0 || account.max_notifications == Some(0) {
+                return Err(anyhow::anyhow!(
+                    "GitHub max_pages and max_notifications for account '{}' must be at least 1 in {:?}",
+                    name,
+                    config_path
+                ));
+            }
+
             let key = name.to_lowercase();
             if !seen_names.insert(key) {
                 return Err(anyhow::anyhow!(
@@ -154,9 +530,115 @@ impl Config {
         Ok(config)
     }
 
+    /// Rename old keys in config.toml itself, keeping a copy of the original
+    /// as config.toml.bak. Returns whether the file changed.
+    pub fn migrate_file() -> Result<bool> {
+        let config_path = Self::config_path()?;
+        let content = fs::read_to_string(&config_path).context("Failed to read config file")?;
+        let Some(migrated) = config_migration::rewrite(&content) else {
+            return Ok(false);
+        };
+        fs::write(config_path.with_extension("toml.bak"), &content)
+            .context("Failed to back up config file")?;
+        fs::write(&config_path, migrated).context("Failed to write config file")?;
+        Ok(true)
+    }
+
+    /// Write the commented starting config to `path`, creating its directory.
+    /// An existing file is never overwritten.
+    pub fn write_template(path: &Path) -> Result<()> {
+        if path.exists() {
+            anyhow::bail!("{:?} already exists and was left as is", path);
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create config directory")?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .with_context(|| format!("Failed to create {:?}", path))?;
+        file.write_all(TEMPLATE.as_bytes())
+            .context("Failed to write config file")?;
+        Ok(())
+    }
+
     /// Get the path to the config file
     pub fn config_path() -> Result<PathBuf> {
         let config_dir = dirs::config_dir().context("Could not find config directory")?;
         Ok(config_dir.join("todo-tray").join("config.toml"))
     }
 }
+
+/// Call `on_change` whenever the file at `path` is created, modified or replaced.
+///
+/// Watches the parent directory, since editors often save by renaming a new
+/// file over the old one. Events arrive on the watcher's own thread; the
+/// returned watcher must be kept alive for as long as watching should continue.
+pub fn watch(
+    path: &Path,
+    on_change: impl Fn() + Send + 'static,
+) -> notify::Result<notify::RecommendedWatcher> {
+    use notify::{EventKind, RecursiveMode, Watcher};
+
+    let dir = path
+        .parent()
+        .ok_or_else(|| notify::Error::generic("Config path has no parent directory"))?
+        .to_path_buf();
+    let file_name = path.file_name().map(|name| name.to_os_string());
+
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        let touches_config = event
+            .paths
+            .iter()
+            .any(|changed| changed.file_name().map(|n| n.to_os_string()) == file_name);
+        let is_write = matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        );
+        if touches_config && is_write {
+            on_change();
+        }
+    })?;
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_is_written_once_and_parses() {
+        let dir = std::env::temp_dir().join(format!("todo-tray-template-{}", std::process::id()));
+        let path = dir.join("todo-tray").join("config.toml");
+
+        Config::write_template(&path).unwrap();
+        let config: Config = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(config.todoist_api_token, "YOUR_TOKEN_HERE");
+        assert!(config.github_accounts.is_empty());
+
+        fs::write(&path, "todoist_api_token = \"mine\"\n").unwrap();
+        let error = Config::write_template(&path).unwrap_err();
+        assert!(error.to_string().contains("already exists"));
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "todoist_api_token = \"mine\"\n"
+        );
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn type_errors_keep_their_location_unless_keys_were_migrated() {
+        let content = "todoist_api_token = \"abc\"\n\ndue_soon_minutes = \"five\"\n";
+        let error = parse_with_migrations(content).unwrap_err().to_string();
+        assert!(error.contains("line 3"), "{}", error);
+
+        let (config, migrations) = parse_with_migrations("api_token = \"abc\"\n").unwrap();
+        assert_eq!(config.todoist_api_token, "abc");
+        assert_eq!(migrations.len(), 1);
+    }
+}
@@ -0,0 +1,230 @@
+//! Upgrades for config.toml keys that were renamed
+//!
+//! Only the keys listed here are touched; anything unknown is left for
+//! serde to accept or reject as usual.
+
+use toml::Table;
+
+/// Top-level keys that were renamed, old name first
+const RENAMED_KEYS: &[(&str, &str)] = &[("api_token", "todoist_api_token")];
+
+/// Keys renamed inside each `[[calendar_feeds]]` entry, old name first
+const RENAMED_FEED_KEYS: &[(&str, &str)] = &[("url", "ical_url")];
+
+/// Rename legacy keys in a parsed config, returning a note per change.
+///
+/// When both the old and the new key are set the new one wins and the old
+/// one is dropped.
+pub fn migrate(config: &mut Table) -> Vec<String> {
+    let mut notes = rename_keys(config, RENAMED_KEYS, "");
+    if let Some(toml::Value::Array(feeds)) = config.get_mut("calendar_feeds") {
+        for feed in feeds.iter_mut() {
+            if let toml::Value::Table(feed) = feed {
+                notes.extend(rename_keys(feed, RENAMED_FEED_KEYS, "calendar_feeds."));
+            }
+        }
+    }
+    notes
+}
+
+fn rename_keys(table: &mut Table, renames: &[(&str, &str)], prefix: &str) -> Vec<String> {
+    let mut notes = Vec::new();
+    for (old, new) in renames {
+        let Some(value) = table.remove(*old) else {
+            continue;
+        };
+        if table.contains_key(*new) {
+            notes.push(format!("{prefix}{old} dropped in favour of {prefix}{new}"));
+        } else {
+            table.insert(new.to_string(), value);
+            notes.push(format!("{prefix}{old} renamed to {prefix}{new}"));
+        }
+    }
+    notes
+}
+
+/// config.toml text with legacy keys renamed in place, keeping comments and
+/// layout; `None` when there is nothing to migrate. Old keys whose new name
+/// is already set are commented out.
+pub fn rewrite(content: &str) -> Option<String> {
+    let parsed: Table = toml::from_str(content).ok()?;
+    let mut changed = false;
+    let mut renames: &[(&str, &str)] = RENAMED_KEYS;
+    let mut section: Option<&Table> = Some(&parsed);
+    let mut feed_index = 0;
+    let mut lines = Vec::new();
+    let mut open_string = None;
+
+    for line in content.lines() {
+        // Lines inside a multi-line string are text, not keys or headers
+        let in_string = open_string.is_some();
+        open_string = multiline_string_after(line, open_string);
+        if in_string {
+            lines.push(line.to_string());
+            continue;
+        }
+
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('[') {
+            if trimmed.starts_with("[[calendar_feeds]]") {
+                renames = RENAMED_FEED_KEYS;
+                section = parsed
+                    .get("calendar_feeds")
+                    .and_then(|feeds| feeds.get(feed_index))
+                    .and_then(toml::Value::as_table);
+                feed_index += 1;
+            } else {
+                renames = &[];
+                section = None;
+            }
+            lines.push(line.to_string());
+            continue;
+        }
+
+        let key = trimmed.split('=').next().unwrap_or("").trim();
+        let rename = renames
+            .iter()
+            .find(|(old, _)| trimmed.contains('=') && key == *old);
+        match (rename, section) {
+            (Some((old, new)), Some(table)) => {
+                changed = true;
+                let indent = &line[..line.len() - trimmed.len()];
+                if table.contains_key(*new) {
+                    lines.push(format!("{indent}# {trimmed}"));
+                } else {
+                    lines.push(format!("{indent}{new}{}", &trimmed[old.len()..]));
+                }
+            }
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    if !changed {
+        return None;
+    }
+    let mut rewritten = lines.join("\n");
+    if content.ends_with('\n') {
+        rewritten.push('\n');
+    }
+    Some(rewritten)
+}
+
+/// The multi-line string delimiter (`"""` or `'''`) still open at the end of
+/// `line`, given the one open at its start.
+fn multiline_string_after(line: &str, mut open: Option<&'static str>) -> Option<&'static str> {
+    let mut rest = line;
+    loop {
+        match open {
+            Some(delimiter) => match rest.find(delimiter) {
+                Some(end) => {
+                    rest = &rest[end + delimiter.len()..];
+                    open = None;
+                }
+                None => return open,
+            },
+            None => {
+                let (start, delimiter) = ["\"\"\"", "'''"]
+                    .into_iter()
+                    .filter_map(|delimiter| Some((rest.find(delimiter)?, delimiter)))
+                    .min()?;
+                if rest[..start].contains('#') {
+                    return None;
+                }
+                rest = &rest[start + delimiter.len()..];
+                open = Some(delimiter);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn legacy_api_token_config_loads() {
+        let mut table: Table = toml::from_str(
+            r#"
+            api_token = "legacy"
+
+            [[calendar_feeds]]
+            name = "Work"
+            url = "https://example.com/work.ics"
+            "#,
+        )
+        .unwrap();
+
+        let notes = migrate(&mut table);
+        let config: Config = toml::Value::Table(table).try_into().unwrap();
+
+        assert_eq!(
+            notes,
+            vec![
+                "api_token renamed to todoist_api_token",
+                "calendar_feeds.url renamed to calendar_feeds.ical_url"
+            ]
+        );
+        assert_eq!(config.todoist_api_token, "legacy");
+        assert_eq!(
+            config.calendar_feeds[0].ical_url,
+            "https://example.com/work.ics"
+        );
+    }
+
+    #[test]
+    fn current_config_is_left_untouched() {
+        let content = "# Todo Tray\ntodoist_api_token = \"abc\"\n\n[[calendar_feeds]]\nname = \"Work\"\nical_url = \"https://example.com/work.ics\"\n";
+        let mut table: Table = toml::from_str(content).unwrap();
+        let before = table.clone();
+
+        assert!(migrate(&mut table).is_empty());
+        assert_eq!(table, before);
+        assert_eq!(rewrite(content), None);
+    }
+
+    #[test]
+    fn rewrite_renames_keys_in_place_and_keeps_comments() {
+        let content = "# My tray\napi_token = \"abc\" # from settings\n\n[[calendar_feeds]]\nname = \"Work\"\n  url = \"https://example.com/work.ics\"\n\n[[github_accounts]]\nname = \"work\"\ntoken = \"t\"\nurl = \"kept\"\n";
+
+        let rewritten = rewrite(content).unwrap();
+
+        assert_eq!(
+            rewritten,
+            "# My tray\ntodoist_api_token = \"abc\" # from settings\n\n[[calendar_feeds]]\nname = \"Work\"\n  ical_url = \"https://example.com/work.ics\"\n\n[[github_accounts]]\nname = \"work\"\ntoken = \"t\"\nurl = \"kept\"\n"
+        );
+    }
+
+    #[test]
+    fn rewrite_leaves_multiline_strings_alone() {
+        let content = "api_token = \"abc\"\n\n[[calendar_feeds]]\nname = \"Work\"\nical_url = \"https://example.com/work.ics\"\nnotes = \"\"\"\nurl = \"kept\"\n[not a header]\n\"\"\"\n";
+
+        let rewritten = rewrite(content).unwrap();
+
+        assert_eq!(
+            rewritten,
+            content.replacen("api_token", "todoist_api_token", 1)
+        );
+        assert_eq!(multiline_string_after("a = '''x''' # \"\"\"", None), None);
+        assert_eq!(
+            multiline_string_after("x\"\"\" + '''", Some("\"\"\"")),
+            Some("'''")
+        );
+    }
+
+    #[test]
+    fn old_key_is_dropped_when_the_new_one_is_set() {
+        let content = "api_token = \"old\"\ntodoist_api_token = \"new\"\n";
+        let mut table: Table = toml::from_str(content).unwrap();
+
+        assert_eq!(
+            migrate(&mut table),
+            vec!["api_token dropped in favour of todoist_api_token"]
+        );
+        assert_eq!(table["todoist_api_token"].as_str(), Some("new"));
+        assert_eq!(
+            rewrite(content).unwrap(),
+            "# api_token = \"old\"\ntodoist_api_token = \"new\"\n"
+        );
+    }
+}
@@ -3,27 +3,73 @@
 //! This module provides the main interface exposed to Swift via UniFFI.
 
 use crate::autostart;
-use crate::calendar::{CalendarClient, CalendarEventSection};
-use crate::config::{default_snooze_durations, Config};
-use crate::github::{GithubClient, GithubNotificationSection};
+use crate::cache::{self, StateSnapshot};
+use crate::calendar::{CalendarClient, CalendarEventSection, WorkingHours};
+use crate::config::{default_snooze_durations, Config, TlsConfig};
+use crate::diagnostics::{Diagnostics, HealthLog};
+use crate::github::{GithubClient, GithubNotification, GithubNotificationSection};
+use crate::github_snooze::{self, GithubSnoozes};
+use crate::http::{
+    build_http_client, HttpTransport, InvalidTokenError, ProxySettings, ReqwestTransport,
+    TlsSettings,
+};
+use crate::json_store;
 use crate::linear::LinearClient;
-use crate::task::{group_tasks, TaskList};
-use crate::todoist::TodoistClient;
-use chrono::{DateTime, Utc};
+use crate::locale::{Language, Text};
+use crate::logging;
+use crate::next_up::{self, NextUpItem};
+use crate::overdue_alert::{OverdueAlert, OverdueAlertTracker};
+use crate::section_order::{self, MenuSectionKind, SectionDescriptor};
+use crate::snooze::{Due, SnoozeTarget};
+use crate::source_toggles::{self, DisabledSources};
+use crate::task::{
+    build_task_sections, filter_task_list, flat_today_list, group_tasks, shows_tomorrow,
+    AllDayPosition, GroupingMode, LabelFilter, MenuLayout, TaskList, TaskSection, TitleCleaning,
+    TodoTask,
+};
+use crate::time_format::TimeFormat;
+use crate::title::{self, TitleCounts};
+use crate::todoist::{QuickAddResult, TodoistClient, TodoistProject};
+use chrono::{DateTime, Local, NaiveDate, Timelike, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::sync::LazyLock;
-use std::time::Duration;
-use tokio::sync::Mutex;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use tokio::sync::{watch, Mutex, Notify};
+
+/// Quiet period after the last config file event before reloading
+const CONFIG_RELOAD_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Time between background refreshes
+const REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Background refreshes happen up to this fraction of `REFRESH_INTERVAL`
+/// early or late, so clients started together drift apart
+const REFRESH_JITTER: f64 = 0.1;
+
+/// On the first refresh, each GitHub account and calendar feed after the
+/// first waits a random moment up to this long before fetching
+const INITIAL_FETCH_STAGGER: Duration = Duration::from_secs(2);
+
+/// Longest `shutdown` waits for completions still in their undo window to close
+const SHUTDOWN_CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Wake events closer together than this only refresh once
+const RESUME_DEBOUNCE: Duration = Duration::from_secs(30);
 
 // Global tokio runtime for async operations
 static TOKIO_RUNTIME: LazyLock<tokio::runtime::Runtime> = LazyLock::new(|| {
-    eprintln!("[Rust] Creating Tokio runtime...");
+    tracing::debug!("Creating Tokio runtime...");
     let rt = tokio::runtime::Builder::new_multi_thread()
         .worker_threads(2)
         .enable_all()
         .build()
         .expect("Failed to create tokio runtime");
-    eprintln!("[Rust] Tokio runtime created successfully");
+    tracing::debug!("Tokio runtime created successfully");
     rt
 });
 
@@ -52,7 +98,11 @@ impl From<anyhow::Error> for TodoTrayError {
 }
 
 /// Application state exposed to Swift
-#[derive(uniffi::Record, Clone, Debug, Default)]
+///
+/// The core owns the canonical copy behind a `tokio::sync::Mutex`; every
+/// accessor and callback hands out a cloned snapshot, so values received in
+/// Swift are safe to read from any thread and never observe partial updates.
+#[derive(uniffi::Record, Clone, Debug, Default, Serialize)]
 pub struct AppState {
     pub overdue_count: u32,
     pub today_count: u32,
@@ -60,13 +110,97 @@ pub struct AppState {
     pub in_progress_count: u32,
     pub github_notification_count: u32,
     pub calendar_event_count: u32,
+    /// Counts in words, e.g. "2 overdue · 5 today · 3 notifications", for tooltips
+    pub summary: String,
+    /// Language of `summary` and the accessibility summary
+    pub language: Language,
+    /// When any source last refreshed successfully (RFC3339)
+    pub last_updated: Option<String>,
     pub tasks: TaskList,
+    /// `tasks` arranged according to `grouping`
+    pub sections: Vec<TaskSection>,
+    pub grouping: GroupingMode,
+    pub menu_layout: MenuLayout,
+    /// Tasks shown per section at most; the rest are counted in `hidden_count`
+    pub max_items_per_section: Option<u32>,
     pub github_notifications: Vec<GithubNotificationSection>,
     pub calendar_events: Vec<CalendarEventSection>,
     pub snooze_durations: Vec<String>,
     pub is_loading: bool,
+    /// Several refreshes in a row reached no source; the data shown is the
+    /// last successful fetch. `source_errors` still lists the failures, for
+    /// the UI to show or fold into one note.
+    pub is_stale: bool,
+    /// First entry of `source_errors`, kept for older UI code.
     pub error_message: Option<String>,
+    pub source_errors: Vec<SourceError>,
     pub autostart_enabled: bool,
+    /// Tasks completed from the tray since local midnight
+    pub completed_today_count: u32,
+    /// Most recent completions first, capped at `RECENT_COMPLETIONS_LIMIT`
+    pub recently_completed: Vec<TodoTask>,
+    /// Only overdue tasks are shown; everything else stays cached but hidden
+    pub focus_mode: bool,
+    /// Actions that would change data are turned off; every task has `can_complete` unset
+    pub read_only: bool,
+    /// Configured menu bar title template, when it parses
+    pub title_format: Option<String>,
+    /// Menu bar title rendered from `title_format`; `None` keeps the default title
+    pub title: Option<String>,
+    /// Earliest overdue task, timed task later today or upcoming meeting
+    pub next_up: Option<NextUpItem>,
+    /// The task, GitHub and calendar blocks in the configured `section_order`
+    pub section_order: Vec<SectionDescriptor>,
+    /// Sources turned off with `set_source_enabled`; their sections stay empty
+    pub disabled_sources: Vec<String>,
+}
+
+/// A failure reported by one integration during the last refresh
+#[derive(uniffi::Record, Clone, Debug, Serialize)]
+pub struct SourceError {
+    /// Source identifier: "todoist", "linear", "github" or "calendar"
+    pub source: String,
+    /// Configured account for multi-account sources (GitHub)
+    pub account_name: Option<String>,
+    pub message: String,
+}
+
+impl SourceError {
+    fn new(source: &str, error: &anyhow::Error) -> Self {
+        Self {
+            source: source.to_string(),
+            account_name: None,
+            message: error.to_string(),
+        }
+    }
+
+    fn for_account(source: &str, account_name: &str, error: &anyhow::Error) -> Self {
+        Self {
+            account_name: Some(account_name.to_string()),
+            ..Self::new(source, error)
+        }
+    }
+}
+
+/// Outcome of `complete_all_today`
+#[derive(uniffi::Record, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BulkCompletion {
+    /// Tasks closed, or held back for the undo window when there is one
+    pub completed: u32,
+    /// Tasks whose close call failed; they stay open
+    pub failed: u32,
+    /// Today tasks left alone because they are Linear issues or read-only
+    pub skipped: u32,
+}
+
+/// How the UI should present an error passed to `EventHandler::on_error`
+#[derive(uniffi::Enum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorSeverity {
+    /// Nothing useful can be shown until the user acts, e.g. a rejected token
+    /// or a first load that reached no source
+    Blocking,
+    /// A background refresh failed; the last data is still shown
+    Transient,
 }
 
 /// Trait implemented by Swift to receive state updates
@@ -75,29 +209,680 @@ pub trait EventHandler: Send + Sync {
     /// Called when the app state changes
     fn on_state_changed(&self, state: AppState);
 
-    /// Called when a task is completed
-    fn on_task_completed(&self, task_name: String);
+    /// Called when a task is completed; pass `undo_token` to `undo_complete`
+    /// to take it back while the undo window lasts
+    fn on_task_completed(&self, task_name: String, undo_token: Option<String>);
 
     /// Called when an error occurs
-    fn on_error(&self, error: String);
+    fn on_error(&self, error: String, severity: ErrorSeverity);
+
+    /// Called with review requests and mentions that arrived since the last refresh
+    fn on_github_alert(&self, notifications: Vec<GithubNotification>);
+
+    /// Called when a refresh finds no overdue tasks where the previous one had some
+    fn on_all_clear(&self);
+
+    /// Called with tasks that became overdue since the last refresh and meet
+    /// `notify_min_priority`
+    fn on_overdue_alert(&self, alert: OverdueAlert);
 }
 
 /// Main Todo Tray core
 #[derive(uniffi::Object)]
 pub struct TodoTrayCore {
     state: Arc<Mutex<AppState>>,
-    todoist_client: Arc<TodoistClient>,
-    linear_client: Option<Arc<LinearClient>>,
-    github_clients: Vec<Arc<GithubClient>>,
-    calendar_clients: Vec<Arc<CalendarClient>>,
-    snooze_durations: Vec<SnoozeDuration>,
+    /// Swapped wholesale when config.toml changes; clone the `Arc` out
+    /// rather than holding the lock across an `.await`.
+    clients: RwLock<Arc<Clients>>,
+    /// Keeps the config file watcher alive
+    config_watcher: std::sync::Mutex<Option<notify::RecommendedWatcher>>,
+    /// Bumped on every config file event, to debounce bursts of writes
+    config_generation: AtomicU64,
+    /// Per-source results a manual refresh shows while fresh data loads
+    fetch_cache: std::sync::Mutex<FetchCache>,
+    /// Held while a full refresh runs, so refreshes never overlap
+    refresh_gate: Mutex<()>,
+    /// Full refreshes asked for so far
+    refresh_requests: AtomicU64,
+    /// Requests answered by the last finished refresh, i.e. those made
+    /// before it started
+    refresh_covered: AtomicU64,
+    /// Spreads out the first fetches of each GitHub account and calendar feed
+    fetch_stagger: std::sync::Mutex<Jitter>,
+    completions: std::sync::Mutex<CompletionLog>,
+    /// Completions still inside their undo window
+    pending_completions: std::sync::Mutex<PendingCompletions>,
+    /// Tasks that close pending completions once their undo window passes
+    undo_timers: std::sync::Mutex<Vec<tokio::task::JoinHandle<()>>>,
+    refresh_failures: std::sync::Mutex<FailureTracker>,
+    github_alerts: std::sync::Mutex<GithubAlertTracker>,
+    overdue_alerts: std::sync::Mutex<OverdueAlertTracker>,
+    overdue: std::sync::Mutex<OverdueTracker>,
+    recurring: std::sync::Mutex<RecurringSuppression>,
+    /// How each source fared in full refreshes, for `diagnostics`
+    health: std::sync::Mutex<HealthLog>,
+    /// Wakes the refresh loop early after the Mac resumes from sleep
+    resumed: Notify,
+    last_resumed: std::sync::Mutex<Option<Instant>>,
+    /// Set once by `shutdown`; background work stops when it flips to true
+    shutdown: watch::Sender<bool>,
+    /// Where refreshed state is persisted; `None` disables the disk cache.
+    state_cache_path: Option<PathBuf>,
+    /// GitHub threads hidden locally until their snooze ends
+    github_snoozes: std::sync::Mutex<GithubSnoozes>,
+    /// Where `github_snoozes` is persisted; `None` keeps them in memory only.
+    github_snooze_path: Option<PathBuf>,
+    /// Sources turned off from the menu, skipped on every refresh
+    disabled_sources: std::sync::Mutex<DisabledSources>,
+    /// Where `disabled_sources` is persisted; `None` keeps them in memory only.
+    disabled_sources_path: Option<PathBuf>,
     event_handler: Arc<dyn EventHandler>,
 }
 
+const RECENT_COMPLETIONS_LIMIT: usize = 10;
+
+#[derive(Clone, Debug)]
+struct CompletedTask {
+    task: TodoTask,
+    completed_at: DateTime<Utc>,
+}
+
+/// A fetch result and when it arrived, so a manual refresh can show it at
+/// once while fresh data loads.
+#[derive(Debug)]
+struct Cached<T> {
+    value: T,
+    fetched_at: Instant,
+}
+
+impl<T> Cached<T> {
+    fn new(value: T) -> Self {
+        Self {
+            value,
+            fetched_at: Instant::now(),
+        }
+    }
+
+    /// The value, unless it is older than `max_age`.
+    fn fresh_within(&self, max_age: Duration) -> Option<&T> {
+        (self.fetched_at.elapsed() < max_age).then_some(&self.value)
+    }
+}
+
+/// The last successful fetch of each source. A failed fetch drops the
+/// source's entry so a manual refresh never hides an error behind older data.
+#[derive(Debug, Default)]
+struct FetchCache {
+    todoist: Option<Cached<Vec<TodoTask>>>,
+    linear: Option<Cached<Vec<TodoTask>>>,
+    /// Keyed by account name
+    github: HashMap<String, Cached<GithubNotificationSection>>,
+    calendar: Option<Cached<Vec<CalendarEventSection>>>,
+}
+
+impl FetchCache {
+    fn store(&mut self, results: &RefreshResults) {
+        fn cached<T: Clone>(result: &anyhow::Result<T>) -> Option<Cached<T>> {
+            result.as_ref().ok().cloned().map(Cached::new)
+        }
+        self.todoist = cached(&results.todoist);
+        self.linear = results.linear.as_ref().and_then(cached);
+        for account in &results.github {
+            match &account.result {
+                Ok(section) => {
+                    self.github
+                        .insert(account.account_name.clone(), Cached::new(section.clone()));
+                }
+                Err(_) => {
+                    self.github.remove(&account.account_name);
+                }
+            }
+        }
+        self.calendar = cached(&results.calendar);
+    }
+
+    /// Results for every source `fetch_and_apply` would fetch, if each was
+    /// fetched within `max_age`.
+    fn fresh_results(&self, core: &TodoTrayCore, max_age: Duration) -> Option<RefreshResults> {
+        let todoist = self.todoist.as_ref()?.fresh_within(max_age)?.clone();
+        let linear = match &core.clients().linear {
+            Some(_) if !core.is_source_disabled("linear") => {
+                Some(Ok(self.linear.as_ref()?.fresh_within(max_age)?.clone()))
+            }
+            _ => None,
+        };
+        let mut github = Vec::new();
+        if !core.is_source_disabled("github") {
+            for client in &core.clients().github {
+                let section = self
+                    .github
+                    .get(client.account_name())?
+                    .fresh_within(max_age)?;
+                github.push(GithubAccountResult {
+                    account_name: client.account_name().to_string(),
+                    result: Ok(section.clone()),
+                });
+            }
+        }
+        let calendar = self.calendar.as_ref()?.fresh_within(max_age)?.clone();
+        Some(RefreshResults {
+            todoist: Ok(todoist),
+            linear,
+            github,
+            calendar: Ok(calendar),
+        })
+    }
+}
+
+/// Counts consecutive full refreshes in which every source failed.
+#[derive(Debug, Default)]
+struct FailureTracker {
+    consecutive: u32,
+}
+
+impl FailureTracker {
+    /// Record one refresh and return whether the data should now be shown as stale.
+    fn record(&mut self, failed: bool, threshold: u32) -> bool {
+        if failed {
+            self.consecutive = self.consecutive.saturating_add(1);
+        } else {
+            self.consecutive = 0;
+        }
+        threshold > 0 && self.consecutive >= threshold
+    }
+}
+
+/// GitHub threads in each account's list as of its last successful fetch, so
+/// only newly-arrived ones raise an alert.
+#[derive(Debug, Default)]
+struct GithubAlertTracker {
+    /// Keyed by account name. An account's first successful fetch only seeds
+    /// its set, so that launching, or recovering from failed fetches, doesn't
+    /// alert on the whole inbox.
+    seen: HashMap<String, HashSet<String>>,
+}
+
+impl GithubAlertTracker {
+    /// Record the notifications of the accounts in `fetched`, which were just
+    /// fetched successfully, and return the actionable ones not seen before.
+    fn new_alerts(
+        &mut self,
+        sections: &[GithubNotificationSection],
+        fetched: &[String],
+    ) -> Vec<GithubNotification> {
+        let mut alerts = Vec::new();
+        for account_name in fetched {
+            let notifications = sections
+                .iter()
+                .filter(|section| section.account_name == *account_name)
+                .flat_map(|section| &section.notifications);
+            if let Some(seen) = self.seen.get(account_name) {
+                alerts.extend(
+                    notifications
+                        .clone()
+                        .filter(|n| n.is_actionable && !seen.contains(&n.thread_id))
+                        .cloned(),
+                );
+            }
+            // Threads that left the list alert again if they come back.
+            let current = notifications.map(|n| n.thread_id.clone()).collect();
+            self.seen.insert(account_name.clone(), current);
+        }
+        alerts
+    }
+}
+
+/// Overdue count as of the last refresh, to notice the list being cleared.
+#[derive(Debug, Default)]
+struct OverdueTracker {
+    /// `None` until the first refresh, so launching with nothing overdue
+    /// doesn't count as clearing the list.
+    previous: Option<u32>,
+}
+
+impl OverdueTracker {
+    /// Record the current overdue count and return whether it just dropped to zero.
+    fn record(&mut self, overdue: u32) -> bool {
+        let cleared = overdue == 0 && self.previous.is_some_and(|previous| previous > 0);
+        self.previous = Some(overdue);
+        cleared
+    }
+}
+
+/// Local record of tasks completed from the tray today.
+#[derive(Debug, Default)]
+struct CompletionLog {
+    entries: Vec<CompletedTask>,
+}
+
+impl CompletionLog {
+    fn record(&mut self, task: TodoTask, completed_at: DateTime<Utc>) {
+        self.entries.push(CompletedTask { task, completed_at });
+    }
+
+    /// Forget completions from before `today` (local date).
+    fn prune(&mut self, today: NaiveDate) {
+        self.entries
+            .retain(|entry| entry.completed_at.with_timezone(&Local).date_naive() == today);
+    }
+
+    /// The task for `task_id` if it's among the completions shown in the menu.
+    fn recent(&self, task_id: &str) -> Option<&TodoTask> {
+        self.entries
+            .iter()
+            .rev()
+            .take(RECENT_COMPLETIONS_LIMIT)
+            .map(|entry| &entry.task)
+            .find(|task| task.id == task_id)
+    }
+
+    fn remove(&mut self, task_id: &str) {
+        self.entries.retain(|entry| entry.task.id != task_id);
+    }
+
+    fn apply_to(&self, state: &mut AppState) {
+        state.completed_today_count = self.entries.len() as u32;
+        state.recently_completed = self
+            .entries
+            .iter()
+            .rev()
+            .take(RECENT_COMPLETIONS_LIMIT)
+            .map(|entry| entry.task.clone())
+            .collect();
+    }
+}
+
+/// Completed tasks whose close calls wait for the undo window to pass.
+/// Bulk completions share one entry so a single undo takes them all back.
+#[derive(Debug)]
+struct PendingCompletion {
+    tasks: Vec<TodoTask>,
+    backend: TaskBackend,
+}
+
+/// Completions that can still be undone, by undo token.
+#[derive(Debug, Default)]
+struct PendingCompletions {
+    next_token: u64,
+    entries: HashMap<String, PendingCompletion>,
+}
+
+impl PendingCompletions {
+    /// Hold back `tasks` and return their undo token.
+    fn add(&mut self, tasks: Vec<TodoTask>, backend: TaskBackend) -> String {
+        self.next_token += 1;
+        let token = self.next_token.to_string();
+        self.entries
+            .insert(token.clone(), PendingCompletion { tasks, backend });
+        token
+    }
+
+    /// Remove the completion for `token`; `None` once it was undone or closed.
+    fn take(&mut self, token: &str) -> Option<PendingCompletion> {
+        self.entries.remove(token)
+    }
+
+    fn contains(&self, task: &TodoTask) -> bool {
+        self.entries
+            .values()
+            .flat_map(|pending| pending.tasks.iter())
+            .any(|pending| pending.id == task.id && pending.source == task.source)
+    }
+}
+
+/// Recurring tasks completed since the last full refresh, whose next
+/// instance stays hidden until then.
+#[derive(Debug, Default)]
+struct RecurringSuppression {
+    entries: Vec<SuppressedRecurring>,
+}
+
+#[derive(Debug)]
+struct SuppressedRecurring {
+    task_id: String,
+    completed_at: Instant,
+    /// Due date of the next instance, once a refresh has seen it
+    next_due: Option<String>,
+}
+
+impl RecurringSuppression {
+    fn add(&mut self, task_id: &str, completed_at: Instant) {
+        self.entries.retain(|entry| entry.task_id != task_id);
+        self.entries.push(SuppressedRecurring {
+            task_id: task_id.to_string(),
+            completed_at,
+            next_due: None,
+        });
+    }
+
+    /// Stop hiding tasks completed before a full refresh that started at `started`.
+    fn expire_before(&mut self, started: Instant) {
+        self.entries.retain(|entry| entry.completed_at >= started);
+    }
+
+    /// Leave out next instances of suppressed Todoist tasks. A task whose due
+    /// date moved again since, e.g. rescheduled by hand, shows up again.
+    fn hide(&mut self, tasks: &mut Vec<TodoTask>) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let mut changed = HashSet::new();
+        tasks.retain(|task| {
+            let Some(entry) = self
+                .entries
+                .iter_mut()
+                .find(|entry| task.source == "todoist" && entry.task_id == task.id)
+            else {
+                return true;
+            };
+            match &entry.next_due {
+                None => {
+                    entry.next_due = task.due_datetime.clone();
+                    false
+                }
+                Some(due) if Some(due) == task.due_datetime.as_ref() => false,
+                Some(_) => {
+                    changed.insert(task.id.clone());
+                    true
+                }
+            }
+        });
+        self.entries
+            .retain(|entry| !changed.contains(&entry.task_id));
+    }
+}
+
 #[derive(Clone, Debug)]
-struct SnoozeDuration {
+struct SnoozeOption {
     label: String,
-    duration: chrono::Duration,
+    target: SnoozeTarget,
+}
+
+/// API clients and client-side settings built from one config
+struct Clients {
+    todoist: Arc<TodoistClient>,
+    linear: Option<Arc<LinearClient>>,
+    linear_complete_state: String,
+    github: Vec<Arc<GithubClient>>,
+    calendar: Vec<Arc<CalendarClient>>,
+    snooze_options: Vec<SnoozeOption>,
+    section_order: Vec<MenuSectionKind>,
+    /// Day snoozes that land on a weekend move on to Monday
+    skip_weekends: bool,
+    label_filter: LabelFilter,
+    read_only: bool,
+    title_cleaning: TitleCleaning,
+    show_tomorrow_after_hour: Option<u8>,
+    all_day_tasks: AllDayPosition,
+    collapse_completed_recurring: bool,
+    notify_min_priority: Option<u8>,
+    /// Badge calendar events by those still to come rather than all of today's
+    count_remaining_events: bool,
+    stale_after_failures: u32,
+    undo_window: Duration,
+    /// Oldest refreshed state a manual refresh shows before fresh data arrives
+    max_stale: Duration,
+    /// Clock of the meeting time in the accessibility summary
+    time_format: TimeFormat,
+}
+
+impl Clients {
+    /// Refuse actions that change data while in read-only mode.
+    fn ensure_writable(&self) -> Result<(), TodoTrayError> {
+        if self.read_only {
+            return Err(TodoTrayError::Unexpected {
+                message: "Todo Tray is in read-only mode; turn off read_only in config.toml to make changes.".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Every source a full refresh fetches, with the account for per-account ones
+    fn sources(&self) -> Vec<(&'static str, Option<String>)> {
+        let mut sources = vec![("todoist", None)];
+        if self.linear.is_some() {
+            sources.push(("linear", None));
+        }
+        sources.extend(
+            self.github
+                .iter()
+                .map(|client| ("github", Some(client.account_name().to_string()))),
+        );
+        if !self.calendar.is_empty() {
+            sources.push(("calendar", None));
+        }
+        sources
+    }
+
+    /// Target of the snooze option labelled `label`
+    fn snooze_target(&self, label: &str) -> Result<SnoozeTarget, TodoTrayError> {
+        self.snooze_options
+            .iter()
+            .find(|entry| entry.label == label)
+            .map(|entry| entry.target)
+            .ok_or_else(|| TodoTrayError::Unexpected {
+                message: format!("Unknown snooze duration: {}", label),
+            })
+    }
+
+    /// Tidy freshly fetched Todoist titles for the menu, if configured.
+    fn clean_titles(&self, tasks: &mut [TodoTask]) {
+        if self.title_cleaning != TitleCleaning::default() {
+            for task in tasks {
+                task.clean_content(self.title_cleaning);
+            }
+        }
+    }
+}
+
+/// Build every client from a config, failing on invalid snooze options.
+fn build_clients(config: &Config) -> Result<Clients, TodoTrayError> {
+    let proxy = ProxySettings {
+        http_proxy: config.http_proxy.clone(),
+        https_proxy: config.https_proxy.clone(),
+        no_proxy: config.no_proxy.clone(),
+    };
+    let client =
+        build_http_client(&proxy, &TlsSettings::default()).map_err(|e| TodoTrayError::Config {
+            message: format!("{:#}", e),
+        })?;
+    let http: Arc<dyn HttpTransport> = Arc::new(ReqwestTransport::new(client));
+    // Feeds and accounts with their own certificates get a client of their own.
+    let transport_for = |tls: &TlsConfig| -> Result<Arc<dyn HttpTransport>, String> {
+        let tls = tls.settings()?;
+        if tls.is_empty() {
+            return Ok(http.clone());
+        }
+        let client = build_http_client(&proxy, &tls).map_err(|e| format!("{:#}", e))?;
+        Ok(Arc::new(ReqwestTransport::new(client)))
+    };
+
+    let todoist = TodoistClient::new(
+        config.todoist_api_token.clone(),
+        config.time_format,
+        chrono::Duration::minutes(config.due_soon_minutes.into()),
+        config.task_filter_query.clone(),
+        config.todoist_filter.clone(),
+        config.todoist_incremental_sync,
+        http.clone(),
+    );
+    let todoist = match config.todoist_api_base.as_deref() {
+        Some(base_url) => todoist.with_base_url(base_url),
+        None => todoist,
+    };
+    let todoist = Arc::new(
+        todoist
+            .with_reminders(config.show_reminders)
+            .with_countdown_times(config.countdown_times)
+            .with_deadline_urgency(config.deadline_urgency)
+            .with_language(config.language),
+    );
+    let linear = config
+        .linear_api_token
+        .as_deref()
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            Arc::new(
+                LinearClient::new(
+                    token.to_string(),
+                    config.linear_states.clone(),
+                    http.clone(),
+                )
+                .with_language(config.language),
+            )
+        });
+    let github = config
+        .github_accounts
+        .iter()
+        .map(|account| {
+            let http = transport_for(&account.tls).map_err(|message| TodoTrayError::Config {
+                message: format!("GitHub account '{}': {}", account.name.trim(), message),
+            })?;
+            Ok(Arc::new(
+                GithubClient::new(
+                    account.name.trim().to_string(),
+                    account.token.trim().to_string(),
+                    config.time_format,
+                    account.page_limits(),
+                    http,
+                )
+                .with_display_name(account.display_name().map(str::to_string))
+                .with_language(config.language),
+            ))
+        })
+        .collect::<Result<Vec<_>, TodoTrayError>>()?;
+    let calendar = config
+        .calendar_feeds
+        .iter()
+        .map(|feed| {
+            let (working_hours, http) = feed
+                .working_hours
+                .as_ref()
+                .map(|(start, end)| WorkingHours::parse(start, end))
+                .transpose()
+                .and_then(|working_hours| Ok((working_hours, transport_for(&feed.tls)?)))
+                .map_err(|message| TodoTrayError::Config {
+                    message: format!("Calendar feed '{}': {}", feed.name.trim(), message),
+                })?;
+            Ok(Arc::new(
+                CalendarClient::new(
+                    feed.name.trim().to_string(),
+                    feed.ical_url.trim().to_string(),
+                    config.time_format,
+                    feed.email
+                        .as_deref()
+                        .map(str::trim)
+                        .filter(|email| !email.is_empty())
+                        .map(str::to_string),
+                    feed.hide_declined,
+                    http,
+                )
+                .with_display_name(feed.display_name().map(str::to_string))
+                .with_language(config.language)
+                .with_hide_cancelled(feed.hide_cancelled)
+                .with_working_hours(working_hours, feed.hide_all_day),
+            ))
+        })
+        .collect::<Result<Vec<_>, TodoTrayError>>()?;
+    let raw_snooze = if config.snooze_durations.is_empty() {
+        default_snooze_durations()
+    } else {
+        config.snooze_durations.clone()
+    };
+    let snooze_options = raw_snooze
+        .into_iter()
+        .map(|raw| {
+            let label = raw.trim().to_string();
+            SnoozeTarget::parse(&label).map(|target| SnoozeOption { label, target })
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|message| TodoTrayError::Config { message })?;
+    let section_order = section_order::parse(&config.section_order)
+        .map_err(|message| TodoTrayError::Config { message })?;
+
+    Ok(Clients {
+        todoist,
+        linear,
+        linear_complete_state: config.linear_complete_state.trim().to_string(),
+        github,
+        calendar,
+        snooze_options,
+        section_order,
+        skip_weekends: config.skip_weekends,
+        label_filter: LabelFilter::new(&config.include_labels, &config.exclude_labels)
+            .with_excluded_projects(&config.exclude_projects),
+        read_only: config.read_only,
+        title_cleaning: TitleCleaning {
+            strip_markdown: config.clean_task_titles,
+            strip_leading_emoji: config.clean_task_titles && config.strip_title_emoji,
+        },
+        show_tomorrow_after_hour: config.show_tomorrow_after_hour,
+        all_day_tasks: config.all_day_tasks,
+        collapse_completed_recurring: config.collapse_completed_recurring,
+        notify_min_priority: config.notify_min_priority,
+        count_remaining_events: config.count_remaining_events,
+        stale_after_failures: config.stale_after_failures,
+        undo_window: Duration::from_secs(config.undo_window_seconds),
+        max_stale: Duration::from_secs(config.max_stale_seconds),
+        time_format: config.time_format,
+    })
+}
+
+/// Copy the display settings from `config` into `state`.
+fn apply_config_to_state(state: &mut AppState, config: &Config, clients: &Clients) {
+    state.grouping = config.grouping;
+    state.language = config.language;
+    state.summary = summarize_counts(state);
+    state.read_only = config.read_only;
+    state.menu_layout = config.menu_layout;
+    state.section_order = clients
+        .section_order
+        .iter()
+        .map(|&kind| SectionDescriptor::new(kind, 0))
+        .collect();
+    state.max_items_per_section = config
+        .max_items_per_section
+        .map(|max| u32::try_from(max).unwrap_or(u32::MAX));
+    state.snooze_durations = clients
+        .snooze_options
+        .iter()
+        .map(|entry| entry.label.clone())
+        .collect();
+    let mut tasks = std::mem::take(&mut state.tasks);
+    state.sections = build_task_sections(
+        &mut tasks,
+        state.grouping,
+        state.menu_layout,
+        state.max_items_per_section.map(|max| max as usize),
+    );
+    state.tasks = tasks;
+    state.title_format = config.title_format.clone().filter(|format| {
+        match title::render(format, &TitleCounts::default()) {
+            Ok(_) => true,
+            Err(e) => {
+                tracing::warn!(error = %e, "Invalid title_format; using the default title");
+                false
+            }
+        }
+    });
+}
+
+/// Write a commented starting config.toml for a first run and return its
+/// path. Works before a `TodoTrayCore` can be created; an existing config is
+/// never overwritten.
+#[uniffi::export]
+pub fn write_default_config() -> Result<String, TodoTrayError> {
+    let write = || -> anyhow::Result<PathBuf> {
+        let path = Config::config_path()?;
+        Config::write_template(&path)?;
+        Ok(path)
+    };
+    write()
+        .map(|path| path.display().to_string())
+        .map_err(|e| TodoTrayError::Config {
+            message: format!("{:#}", e),
+        })
 }
 
 #[uniffi::export]
@@ -105,61 +890,21 @@ impl TodoTrayCore {
     /// Create a new TodoTrayCore instance (synchronous)
     #[uniffi::constructor]
     pub fn new(event_handler: Arc<dyn EventHandler>) -> Result<Arc<Self>, TodoTrayError> {
-        eprintln!("[Rust] TodoTrayCore::new() called");
+        logging::init();
+        tracing::debug!("TodoTrayCore::new() called");
 
         // Force runtime initialization
         let _runtime = &*TOKIO_RUNTIME;
-        eprintln!("[Rust] Runtime initialized");
+        tracing::debug!("Runtime initialized");
 
         // Load config
         let config = Config::load().map_err(|e| {
-            eprintln!("[Rust] Config load error: {}", e);
+            tracing::error!(error = %e, "Config load error");
             TodoTrayError::Config {
                 message: e.to_string(),
             }
         })?;
-        eprintln!("[Rust] Config loaded successfully");
-
-        let todoist_client = Arc::new(TodoistClient::new(config.todoist_api_token));
-        let linear_client = config
-            .linear_api_token
-            .as_deref()
-            .map(str::trim)
-            .filter(|token| !token.is_empty())
-            .map(|token| Arc::new(LinearClient::new(token.to_string())));
-        let github_clients = config
-            .github_accounts
-            .iter()
-            .map(|account| {
-                Arc::new(GithubClient::new(
-                    account.name.trim().to_string(),
-                    account.token.trim().to_string(),
-                ))
-            })
-            .collect::<Vec<_>>();
-        let calendar_clients = config
-            .calendar_feeds
-            .iter()
-            .map(|feed| {
-                Arc::new(CalendarClient::new(
-                    feed.name.trim().to_string(),
-                    feed.ical_url.trim().to_string(),
-                ))
-            })
-            .collect::<Vec<_>>();
-        let raw_snooze = if config.snooze_durations.is_empty() {
-            default_snooze_durations()
-        } else {
-            config.snooze_durations.clone()
-        };
-        let snooze_durations = raw_snooze
-            .into_iter()
-            .map(|raw| {
-                let label = raw.trim().to_string();
-                parse_snooze_duration(&label).map(|duration| SnoozeDuration { label, duration })
-            })
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|message| TodoTrayError::Config { message })?;
+        tracing::info!("Config loaded successfully");
 
         let autostart_enabled = autostart::is_enabled();
 
@@ -170,63 +915,108 @@ impl TodoTrayCore {
             let _ = autostart::disable();
         }
 
-        let core = Arc::new(Self {
-            state: Arc::new(Mutex::new(AppState {
-                autostart_enabled: autostart::is_enabled(),
-                is_loading: true,
-                snooze_durations: snooze_durations
-                    .iter()
-                    .map(|entry| entry.label.clone())
-                    .collect(),
-                ..Default::default()
-            })),
-            todoist_client,
-            linear_client,
-            github_clients,
-            calendar_clients,
-            snooze_durations,
-            event_handler,
-        });
-
-        // Start background refresh loop
-        let core_clone = core.clone();
-        std::thread::spawn(move || {
-            eprintln!("[Rust] Background thread started, entering tokio runtime...");
-            // Run async code in the tokio runtime
-            TOKIO_RUNTIME.block_on(async move {
-                eprintln!("[Rust] Inside tokio runtime, starting background task...");
+        let mut core = Self::from_config(config, event_handler)?;
+        if let Some(core) = Arc::get_mut(&mut core) {
+            core.state_cache_path = cache::cache_path().ok();
+            core.github_snooze_path = github_snooze::store_path().ok();
+            if let Some(path) = &core.github_snooze_path {
+                *core.github_snoozes.get_mut().unwrap() = json_store::load_or_default(path);
+            }
+            core.disabled_sources_path = source_toggles::store_path().ok();
+            if let Some(path) = &core.disabled_sources_path {
+                let disabled = source_toggles::load(path);
+                core.state.blocking_lock().disabled_sources = disabled.names();
+                *core.disabled_sources.get_mut().unwrap() = disabled;
+            }
+        }
+        core.seed_from_cache();
+        core.watch_config();
+        core.spawn_refresh_loop();
 
-                // Initial refresh
-                eprintln!("[Rust] About to call refresh_tasks()...");
-                if let Err(e) = refresh_tasks(&core_clone).await {
-                    eprintln!("[Rust] Initial refresh failed: {}", e);
-                }
-                eprintln!("[Rust] Initial refresh complete");
+        tracing::debug!("TodoTrayCore::new() returning...");
 
-                // Refresh every 5 minutes
-                let mut interval = tokio::time::interval(Duration::from_secs(300));
-                loop {
-                    interval.tick().await;
-                    if let Err(e) = refresh_tasks(&core_clone).await {
-                        eprintln!("[Rust] Refresh failed: {}", e);
-                    }
-                }
-            });
-        });
+        Ok(core)
+    }
 
-        eprintln!("[Rust] TodoTrayCore::new() returning...");
+    /// Refresh everything now and restart the refresh interval, e.g. after
+    /// the Mac wakes from sleep. Repeated calls within a short window are ignored.
+    pub fn notify_resumed(&self) {
+        let now = Instant::now();
+        let mut last_resumed = self.last_resumed.lock().unwrap();
+        if last_resumed.is_some_and(|at| now.duration_since(at) < RESUME_DEBOUNCE) {
+            return;
+        }
+        *last_resumed = Some(now);
+        self.resumed.notify_one();
+    }
 
-        Ok(core)
+    /// Stop the background refresh loop and config watching, cancelling any
+    /// refresh in flight. Call before the app exits; there is no way to restart.
+    ///
+    /// Completions still inside their undo window are closed before this
+    /// returns, waiting up to `SHUTDOWN_CLOSE_TIMEOUT`.
+    pub fn shutdown(&self) {
+        self.shutdown.send_replace(true);
+        *self.config_watcher.lock().unwrap() = None;
+        let closed = TOKIO_RUNTIME.block_on(async {
+            tokio::time::timeout(SHUTDOWN_CLOSE_TIMEOUT, wait_for_undo_timers(self)).await
+        });
+        if closed.is_err() {
+            tracing::warn!("Gave up waiting for pending completions to close");
+        }
     }
 
     /// Refresh tasks from Todoist and Linear (synchronous wrapper)
+    ///
+    /// Emits the current state first if it's recent enough, then the fresh one.
     pub fn refresh(&self) -> Result<(), TodoTrayError> {
-        TOKIO_RUNTIME.block_on(async { refresh_tasks(self).await })
+        TOKIO_RUNTIME.block_on(async { serve_then_refresh(self).await })
     }
 
     /// Complete a task (synchronous wrapper)
-    pub fn complete(&self, task_id: String) -> Result<(), TodoTrayError> {
-        TOKIO_RUNTIME.block_on(async { complete_task(self, task_id).await })
+    pub fn complete(self: Arc<Self>, task_id: String) -> Result<(), TodoTrayError> {
+        TOKIO_RUNTIME.block_on(async { complete_task(&self, task_id).await })
+    }
+
+    /// Reopen a Todoist task from the recently completed list.
+    pub fn reopen_task(&self, task_id: String) -> Result<(), TodoTrayError> {
+        TOKIO_RUNTIME.block_on(async { reopen_task(self, task_id).await })
+    }
+
+    /// Delete a Todoist task for good.
+    ///
+    /// Unlike `complete` there is no undo window, and the task doesn't show up
+    /// under completed tasks or in `reopen_task`; confirm with the user first.
+    pub fn delete_task(&self, task_id: String) -> Result<(), TodoTrayError> {
+        TOKIO_RUNTIME.block_on(async { delete_task(self, task_id).await })
+    }
+
+    /// Todoist projects a task can be moved to, fetched fresh each call so a
+    /// picker shows projects added since the last refresh.
+    pub fn list_projects(&self) -> Result<Vec<TodoistProject>, TodoTrayError> {
+        TOKIO_RUNTIME
+            .block_on(async { self.clients().todoist.list_projects().await })
+            .map_err(api_error)
+    }
+
+    /// Move a Todoist task to the project `project_id` from `list_projects`,
+    /// then refresh the tasks.
+    pub fn move_task(&self, task_id: String, project_id: String) -> Result<(), TodoTrayError> {
+        TOKIO_RUNTIME.block_on(async { move_task(self, task_id, project_id).await })
+    }
+
+    /// Take back a completion whose undo window hasn't passed yet.
+    pub fn undo_complete(&self, undo_token: String) -> Result<(), TodoTrayError> {
+        let pending = self
+            .pending_completions
+            .lock()
+            .unwrap()
+            .take(&undo_token)
+            .ok_or_else(|| TodoTrayError::NotFound {
+                message: "The task was already completed and can no longer be undone".to_string(),
+            })?;
+        TOKIO_RUNTIME.block_on(async { restore_tasks(self, pending.tasks).await });
+        Ok(())
     }
 
     /// Snooze a Todoist task by the provided duration label (e.g. "30m", "1d").
@@ -238,6 +1028,34 @@ impl TodoTrayCore {
         TOKIO_RUNTIME.block_on(async { snooze_task(self, task_id, duration_label).await })
     }
 
+    /// Complete every Todoist task due today, skipping Linear issues and
+    /// read-only tasks.
+    ///
+    /// The tasks close one at a time and refresh once at the end; a summary
+    /// goes to `on_task_completed`. With an undo window they are held back
+    /// together under one undo token and failures arrive via `on_error`
+    /// later. Confirm with the user first.
+    pub fn complete_all_today(self: Arc<Self>) -> Result<BulkCompletion, TodoTrayError> {
+        TOKIO_RUNTIME.block_on(async { complete_all_today(&self).await })
+    }
+
+    /// Add a Todoist task from a line in quick-add syntax, e.g. "buy milk
+    /// tomorrow #Shopping", returning how Todoist read the date, project and
+    /// labels so the UI can show it.
+    pub fn quick_add_task(&self, text: String) -> Result<QuickAddResult, TodoTrayError> {
+        TOKIO_RUNTIME.block_on(async { quick_add_task(self, text).await })
+    }
+
+    /// Move every overdue Todoist task to today, returning how many moved.
+    /// Timed tasks keep their time of day. Recurring tasks are left alone,
+    /// since moving one by date would drop its recurrence.
+    ///
+    /// Tasks that fail to update are reported as a Todoist source error
+    /// instead of stopping the rest; the tasks are refreshed once at the end.
+    pub fn reschedule_all_overdue_to_today(&self) -> Result<u32, TodoTrayError> {
+        TOKIO_RUNTIME.block_on(async { reschedule_overdue_to_today(self).await })
+    }
+
     /// Resolve a GitHub notification thread for one configured account.
     pub fn resolve_github_notification(
         &self,
@@ -249,32 +1067,135 @@ impl TodoTrayCore {
         })
     }
 
+    /// Hide a GitHub thread in Todo Tray for a snooze duration label (e.g.
+    /// "30m", "1d"). The thread stays unread on GitHub and shows up again on
+    /// the first refresh after the snooze ends.
+    pub fn snooze_github_notification(
+        &self,
+        account_name: String,
+        thread_id: String,
+        duration_label: String,
+    ) -> Result<(), TodoTrayError> {
+        TOKIO_RUNTIME.block_on(async {
+            snooze_github_notification_internal(self, account_name, thread_id, duration_label).await
+        })
+    }
+
+    /// Stop notifications for a GitHub thread, marking it read as well.
+    pub fn unsubscribe_github_notification(
+        &self,
+        account_name: String,
+        thread_id: String,
+    ) -> Result<(), TodoTrayError> {
+        TOKIO_RUNTIME.block_on(async {
+            unsubscribe_github_notification_internal(self, account_name, thread_id).await
+        })
+    }
+
     /// Get the current app state
     pub fn get_state(&self) -> AppState {
-        TOKIO_RUNTIME.block_on(async { self.state.lock().await.clone() })
+        TOKIO_RUNTIME.block_on(async { visible_state(&*self.state.lock().await) })
     }
 
-    /// Toggle autostart
-    pub fn toggle_autostart(&self) -> Result<bool, TodoTrayError> {
-        let enabled = if autostart::is_enabled() {
-            autostart::disable().map_err(|e| TodoTrayError::Unexpected {
-                message: e.to_string(),
-            })?;
-            false
-        } else {
-            autostart::enable().map_err(|e| TodoTrayError::Unexpected {
-                message: e.to_string(),
-            })?;
-            true
-        };
+    /// `state` as one spoken sentence for VoiceOver in its language, e.g.
+    /// "You have 2 overdue tasks, 5 tasks today, and 1 meeting at 14:00."
+    /// Pass the state being rendered so the label matches it.
+    pub fn accessibility_summary(&self, state: AppState) -> String {
+        describe_state(&state, self.clients().time_format, Utc::now())
+    }
 
-        // Update state
-        let state = self.state.clone();
+    /// The current state as JSON, for scripts and widgets. Like `get_state`
+    /// it holds no tokens and makes no network calls.
+    pub fn export_state_json(&self) -> String {
+        let state = self.get_state();
+        serde_json::to_string_pretty(&state).unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Failed to serialize state");
+            "{}".to_string()
+        })
+    }
+
+    /// Rename old keys in config.toml, backing the original up to
+    /// config.toml.bak. The file watcher then reloads it; returns whether
+    /// anything changed.
+    pub fn migrate_config_file(&self) -> Result<bool, TodoTrayError> {
+        Config::migrate_file().map_err(|e| TodoTrayError::Config {
+            message: e.to_string(),
+        })
+    }
+
+    /// What's configured and when each source last refreshed, without secrets.
+    pub fn diagnostics(&self) -> Diagnostics {
+        let clients = self.clients();
+        let sources = self.health.lock().unwrap().for_sources(&clients.sources());
+        Diagnostics::new(
+            Config::config_path()
+                .ok()
+                .map(|path| path.display().to_string()),
+            u32::from(clients.linear.is_some()),
+            clients.github.len() as u32,
+            clients.calendar.len() as u32,
+            clients.read_only,
+            sources,
+        )
+    }
+
+    /// Filter the cached tasks by a case-insensitive substring, without a network call.
+    /// An empty query returns every task.
+    pub fn filter_tasks(&self, query: String) -> TaskList {
+        let tasks =
+            TOKIO_RUNTIME.block_on(async { visible_state(&*self.state.lock().await).tasks });
+        filter_task_list(&tasks, &query)
+    }
+
+    /// Overdue and today's tasks, plus tomorrow's while they're shown, as one
+    /// list ordered like the menu sections. Built from cached state without a
+    /// network call; `get_state` still has them grouped.
+    pub fn flat_today_list(&self) -> Vec<TodoTask> {
+        let tasks =
+            TOKIO_RUNTIME.block_on(async { visible_state(&*self.state.lock().await).tasks });
+        flat_today_list(&tasks)
+    }
+
+    /// Turn "todoist", "linear", "github" or "calendar" off or back on
+    /// without touching config.toml. A disabled source isn't fetched and its
+    /// section is emptied right away; turning it on refreshes everything.
+    /// The choice is remembered across restarts.
+    pub fn set_source_enabled(&self, source: String, enabled: bool) -> Result<(), TodoTrayError> {
+        TOKIO_RUNTIME.block_on(async { set_source_enabled(self, source, enabled).await })
+    }
+
+    /// Show only overdue tasks, or everything again. Hidden data stays cached,
+    /// so turning focus mode off needs no network call.
+    pub fn set_focus_mode(&self, enabled: bool) {
+        let state_copy = TOKIO_RUNTIME.block_on(async {
+            let mut state = self.state.lock().await;
+            state.focus_mode = enabled;
+            visible_state(&state)
+        });
+        self.event_handler.on_state_changed(state_copy);
+    }
+
+    /// Toggle autostart
+    pub fn toggle_autostart(&self) -> Result<bool, TodoTrayError> {
+        let enabled = if autostart::is_enabled() {
+            autostart::disable().map_err(|e| TodoTrayError::Unexpected {
+                message: e.to_string(),
+            })?;
+            false
+        } else {
+            autostart::enable().map_err(|e| TodoTrayError::Unexpected {
+                message: e.to_string(),
+            })?;
+            true
+        };
+
+        // Update state
+        let state = self.state.clone();
         let event_handler = self.event_handler.clone();
         TOKIO_RUNTIME.spawn(async move {
             let mut s = state.lock().await;
             s.autostart_enabled = enabled;
-            let state_copy = s.clone();
+            let state_copy = visible_state(&s);
             drop(s);
             event_handler.on_state_changed(state_copy);
         });
@@ -288,308 +1209,3750 @@ impl TodoTrayCore {
     }
 }
 
-// Internal async implementations
+/// Non-blocking variants of the FFI methods.
+///
+/// These run on the shared tokio runtime and are awaited from Swift, so the
+/// calling thread is never parked while a network request is in flight.
+#[uniffi::export(async_runtime = "tokio")]
+impl TodoTrayCore {
+    /// Refresh all sources without blocking the caller, emitting recent
+    /// state first like [`Self::refresh`]
+    pub async fn refresh_async(self: Arc<Self>) -> Result<(), TodoTrayError> {
+        run_on_runtime(async move { serve_then_refresh(&self).await }).await
+    }
 
-async fn refresh_tasks(core: &TodoTrayCore) -> Result<(), TodoTrayError> {
-    let todoist = core.todoist_client.get_tasks();
-    let linear = async {
-        match &core.linear_client {
-            Some(client) => client.get_in_progress_issues().await.map(Some),
-            None => Ok(None),
-        }
-    };
-    let (mut tasks, linear_tasks) =
-        tokio::try_join!(todoist, linear).map_err(|e| TodoTrayError::Network {
-            message: e.to_string(),
-        })?;
-    let github_sections = fetch_github_notifications(core).await?;
-    let calendar_sections = fetch_calendar_events(core).await?;
+    /// Complete a task without blocking the caller
+    pub async fn complete_async(self: Arc<Self>, task_id: String) -> Result<(), TodoTrayError> {
+        run_on_runtime(async move { complete_task(&self, task_id).await }).await
+    }
 
-    if let Some(mut linear_tasks) = linear_tasks {
-        tasks.append(&mut linear_tasks);
+    /// Reopen a recently completed task without blocking the caller
+    pub async fn reopen_task_async(self: Arc<Self>, task_id: String) -> Result<(), TodoTrayError> {
+        run_on_runtime(async move { reopen_task(&self, task_id).await }).await
     }
 
-    let grouped = group_tasks(tasks);
+    /// Delete a Todoist task without blocking the caller; see `delete_task`
+    pub async fn delete_task_async(self: Arc<Self>, task_id: String) -> Result<(), TodoTrayError> {
+        run_on_runtime(async move { delete_task(&self, task_id).await }).await
+    }
 
-    let mut state = core.state.lock().await;
-    apply_grouped_tasks_to_state(&mut state, grouped);
-    state.github_notification_count = github_sections
-        .iter()
-        .map(|section| section.notifications.len() as u32)
-        .sum();
-    state.calendar_event_count = calendar_sections
-        .iter()
-        .map(|section| section.events.len() as u32)
-        .sum();
-    state.github_notifications = github_sections;
-    state.calendar_events = calendar_sections;
+    /// Fetch Todoist projects without blocking the caller; see `list_projects`
+    pub async fn list_projects_async(
+        self: Arc<Self>,
+    ) -> Result<Vec<TodoistProject>, TodoTrayError> {
+        run_on_runtime(async move {
+            self.clients()
+                .todoist
+                .list_projects()
+                .await
+                .map_err(api_error)
+        })
+        .await
+    }
 
-    let state_copy = state.clone();
-    drop(state);
+    /// Move a Todoist task to another project without blocking the caller
+    pub async fn move_task_async(
+        self: Arc<Self>,
+        task_id: String,
+        project_id: String,
+    ) -> Result<(), TodoTrayError> {
+        run_on_runtime(async move { move_task(&self, task_id, project_id).await }).await
+    }
 
-    core.event_handler.on_state_changed(state_copy);
+    /// Snooze a Todoist task without blocking the caller
+    pub async fn snooze_task_async(
+        self: Arc<Self>,
+        task_id: String,
+        duration_label: String,
+    ) -> Result<(), TodoTrayError> {
+        run_on_runtime(async move { snooze_task(&self, task_id, duration_label).await }).await
+    }
 
-    Ok(())
+    /// Complete every Todoist task due today without blocking the caller
+    pub async fn complete_all_today_async(
+        self: Arc<Self>,
+    ) -> Result<BulkCompletion, TodoTrayError> {
+        run_on_runtime(async move { complete_all_today(&self).await }).await
+    }
+
+    /// Add a Todoist task from quick-add syntax without blocking the caller
+    pub async fn quick_add_task_async(
+        self: Arc<Self>,
+        text: String,
+    ) -> Result<QuickAddResult, TodoTrayError> {
+        run_on_runtime(async move { quick_add_task(&self, text).await }).await
+    }
+
+    /// Move every overdue Todoist task to today without blocking the caller
+    pub async fn reschedule_all_overdue_to_today_async(
+        self: Arc<Self>,
+    ) -> Result<u32, TodoTrayError> {
+        run_on_runtime(async move { reschedule_overdue_to_today(&self).await }).await
+    }
+
+    /// Resolve a GitHub notification thread without blocking the caller
+    pub async fn resolve_github_notification_async(
+        self: Arc<Self>,
+        account_name: String,
+        thread_id: String,
+    ) -> Result<(), TodoTrayError> {
+        run_on_runtime(async move {
+            resolve_github_notification_internal(&self, account_name, thread_id).await
+        })
+        .await
+    }
+
+    /// Snooze a GitHub notification thread without blocking the caller
+    pub async fn snooze_github_notification_async(
+        self: Arc<Self>,
+        account_name: String,
+        thread_id: String,
+        duration_label: String,
+    ) -> Result<(), TodoTrayError> {
+        run_on_runtime(async move {
+            snooze_github_notification_internal(&self, account_name, thread_id, duration_label)
+                .await
+        })
+        .await
+    }
+
+    /// Unsubscribe from a GitHub notification thread without blocking the caller
+    pub async fn unsubscribe_github_notification_async(
+        self: Arc<Self>,
+        account_name: String,
+        thread_id: String,
+    ) -> Result<(), TodoTrayError> {
+        run_on_runtime(async move {
+            unsubscribe_github_notification_internal(&self, account_name, thread_id).await
+        })
+        .await
+    }
 }
 
-async fn complete_task(core: &TodoTrayCore, task_id: String) -> Result<(), TodoTrayError> {
-    // Lookup the task first so we can block completion for non-Todoist sources.
-    let selected_task = {
-        let state = core.state.lock().await;
-        state
-            .tasks
-            .overdue
-            .iter()
-            .chain(state.tasks.today.iter())
-            .chain(state.tasks.tomorrow.iter())
-            .chain(state.tasks.in_progress.iter())
-            .find(|t| t.id == task_id)
-            .map(|t| (t.content.clone(), t.can_complete))
-    };
+impl TodoTrayCore {
+    /// Build a core from an already-loaded config without starting the refresh loop.
+    fn from_config(
+        config: Config,
+        event_handler: Arc<dyn EventHandler>,
+    ) -> Result<Arc<Self>, TodoTrayError> {
+        let clients = build_clients(&config)?;
+        let mut state = AppState {
+            autostart_enabled: autostart::is_enabled(),
+            is_loading: true,
+            ..Default::default()
+        };
+        apply_config_to_state(&mut state, &config, &clients);
 
-    let (task_name, can_complete) = selected_task.ok_or_else(|| TodoTrayError::NotFound {
-        message: format!("Task not found: {}", task_id),
-    })?;
+        Ok(Arc::new(Self {
+            state: Arc::new(Mutex::new(state)),
+            fetch_cache: std::sync::Mutex::new(FetchCache::default()),
+            clients: RwLock::new(Arc::new(clients)),
+            config_watcher: std::sync::Mutex::new(None),
+            config_generation: AtomicU64::new(0),
+            refresh_gate: Mutex::new(()),
+            refresh_requests: AtomicU64::new(0),
+            refresh_covered: AtomicU64::new(0),
+            fetch_stagger: std::sync::Mutex::new(Jitter::new(fastrand::Rng::new())),
+            completions: std::sync::Mutex::new(CompletionLog::default()),
+            pending_completions: std::sync::Mutex::new(PendingCompletions::default()),
+            undo_timers: std::sync::Mutex::new(Vec::new()),
+            refresh_failures: std::sync::Mutex::new(FailureTracker::default()),
+            github_alerts: std::sync::Mutex::new(GithubAlertTracker::default()),
+            overdue_alerts: std::sync::Mutex::new(OverdueAlertTracker::default()),
+            overdue: std::sync::Mutex::new(OverdueTracker::default()),
+            recurring: std::sync::Mutex::new(RecurringSuppression::default()),
+            health: std::sync::Mutex::new(HealthLog::default()),
+            resumed: Notify::new(),
+            last_resumed: std::sync::Mutex::new(None),
+            shutdown: watch::Sender::new(false),
+            state_cache_path: None,
+            github_snoozes: std::sync::Mutex::new(GithubSnoozes::default()),
+            github_snooze_path: None,
+            disabled_sources: std::sync::Mutex::new(DisabledSources::default()),
+            disabled_sources_path: None,
+            event_handler,
+        }))
+    }
 
-    if !can_complete {
-        return Err(TodoTrayError::Unexpected {
-            message: "This task is read-only and cannot be completed from Todo Tray.".to_string(),
+    /// Resolves once `shutdown` has been called.
+    async fn shut_down(&self) {
+        let mut stopped = self.shutdown.subscribe();
+        let _ = stopped.wait_for(|stopped| *stopped).await;
+    }
+
+    fn is_source_disabled(&self, source: &str) -> bool {
+        self.disabled_sources.lock().unwrap().contains(source)
+    }
+
+    /// Leave out tasks whose completion can still be undone.
+    fn hide_pending_completions(&self, tasks: &mut Vec<TodoTask>) {
+        let pending = self.pending_completions.lock().unwrap();
+        tasks.retain(|task| !pending.contains(task));
+    }
+
+    /// Leave out threads snoozed in Todo Tray; expired snoozes are forgotten.
+    fn hide_snoozed_github(&self, section: &mut GithubNotificationSection) {
+        let now = Utc::now();
+        let mut snoozes = self.github_snoozes.lock().unwrap();
+        if snoozes.prune(now) {
+            self.save_github_snoozes(&snoozes);
+        }
+        snoozes.hide(section, now);
+    }
+
+    fn save_github_snoozes(&self, snoozes: &GithubSnoozes) {
+        if let Some(path) = &self.github_snooze_path {
+            if let Err(e) = json_store::save(path, snoozes) {
+                tracing::warn!(error = %e, "Failed to write GitHub snoozes");
+            }
+        }
+    }
+
+    /// Snapshot of the current clients.
+    fn clients(&self) -> Arc<Clients> {
+        self.clients.read().unwrap().clone()
+    }
+
+    /// Swap in clients and settings from a freshly loaded config.
+    async fn apply_config(&self, config: Config) -> Result<(), TodoTrayError> {
+        let clients = build_clients(&config)?;
+        let mut state = self.state.lock().await;
+        apply_config_to_state(&mut state, &config, &clients);
+        *self.clients.write().unwrap() = Arc::new(clients);
+        // Cached results may come from other accounts or filters.
+        *self.fetch_cache.lock().unwrap() = FetchCache::default();
+        let state_copy = visible_state(&state);
+        drop(state);
+
+        self.event_handler.on_state_changed(state_copy);
+        Ok(())
+    }
+
+    /// Reload config.toml, keeping the previous config if it doesn't validate.
+    async fn reload_config(&self) {
+        let result = match Config::load() {
+            Ok(config) => self.apply_config(config).await,
+            Err(e) => Err(TodoTrayError::Config {
+                message: e.to_string(),
+            }),
+        };
+        match result {
+            Ok(()) => {
+                tracing::info!("Config reloaded");
+                if let Err(e) = refresh_tasks(self).await {
+                    tracing::warn!(error = %e, "Refresh after config reload failed");
+                }
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Config reload failed");
+                self.event_handler
+                    .on_error(e.to_string(), ErrorSeverity::Blocking);
+            }
+        }
+    }
+
+    /// Watch config.toml and reload it shortly after it stops changing.
+    fn watch_config(self: &Arc<Self>) {
+        let Ok(config_path) = Config::config_path() else {
+            return;
+        };
+        let core = Arc::downgrade(self);
+        let watcher = crate::config::watch(&config_path, move || {
+            let Some(core) = core.upgrade() else {
+                return;
+            };
+            let generation = core.config_generation.fetch_add(1, Ordering::SeqCst) + 1;
+            TOKIO_RUNTIME.spawn(async move {
+                tokio::time::sleep(CONFIG_RELOAD_DEBOUNCE).await;
+                if core.config_generation.load(Ordering::SeqCst) == generation {
+                    core.reload_config().await;
+                }
+            });
         });
+        match watcher {
+            Ok(watcher) => *self.config_watcher.lock().unwrap() = Some(watcher),
+            Err(e) => tracing::warn!(error = %e, "Failed to watch config file"),
+        }
     }
 
-    core.todoist_client
-        .complete_task(&task_id)
-        .await
-        .map_err(|e| TodoTrayError::Network {
-            message: e.to_string(),
-        })?;
+    /// Seed state from the on-disk cache so the menu isn't empty while the
+    /// first refresh is in flight.
+    fn seed_from_cache(&self) {
+        let Some(snapshot) = self.state_cache_path.as_deref().and_then(cache::load) else {
+            return;
+        };
+        let mut state = self.state.blocking_lock();
+        snapshot.apply_to(&mut state);
+        let state_copy = visible_state(&state);
+        drop(state);
+        self.event_handler.on_state_changed(state_copy);
+    }
 
-    // Notify
-    core.event_handler.on_task_completed(task_name);
+    /// Start the background refresh loop on a dedicated thread.
+    fn spawn_refresh_loop(self: &Arc<Self>) {
+        let core_clone = self.clone();
+        std::thread::spawn(move || {
+            tracing::debug!("Background thread started, entering tokio runtime...");
+            // Run async code in the tokio runtime
+            TOKIO_RUNTIME.block_on(async move {
+                tracing::debug!("Inside tokio runtime, starting background task...");
 
-    // Refresh only Todoist-backed task sections; other sources refresh on interval.
-    refresh_todoist_tasks(core).await?;
+                tokio::select! {
+                    _ = core_clone.shut_down() => {}
+                    _ = initial_refresh(&core_clone) => {}
+                }
 
-    Ok(())
+                let jitter = Jitter::new(fastrand::Rng::new());
+                run_refresh_loop(&core_clone, REFRESH_INTERVAL, jitter, || async {
+                    if let Err(e) = refresh_tasks(&core_clone).await {
+                        tracing::warn!(error = %e, "Refresh failed");
+                    }
+                })
+                .await;
+            });
+        });
+    }
 }
 
-async fn snooze_task(
-    core: &TodoTrayCore,
-    task_id: String,
-    duration_label: String,
-) -> Result<(), TodoTrayError> {
-    let duration = core
-        .snooze_durations
-        .iter()
-        .find(|entry| entry.label == duration_label)
-        .map(|entry| entry.duration)
-        .ok_or_else(|| TodoTrayError::Unexpected {
-            message: format!("Unknown snooze duration: {}", duration_label),
-        })?;
+/// Check the Todoist token, then load every source. A rejected token is
+/// reported but doesn't hold back the other sources; it stays listed as the
+/// Todoist source error until a refresh gets through.
+async fn initial_refresh(core: &TodoTrayCore) {
+    let rejected = match verify_todoist_token(core).await {
+        Err(e @ TodoTrayError::Config { .. }) => {
+            tracing::error!(error = %e, "Todoist token check failed");
+            core.event_handler
+                .on_error(e.to_string(), ErrorSeverity::Blocking);
+            Some(e)
+        }
+        _ => None,
+    };
 
-    let current_due = {
-        let state = core.state.lock().await;
-        state
-            .tasks
-            .overdue
-            .iter()
-            .chain(state.tasks.today.iter())
-            .chain(state.tasks.tomorrow.iter())
-            .find(|t| t.id == task_id && t.source == "todoist")
-            .and_then(|t| t.due_datetime.clone())
+    tracing::debug!("About to call refresh_tasks()...");
+    if let Err(e) = refresh_tasks(core).await {
+        tracing::warn!(error = %e, "Initial refresh failed");
     }
-    .ok_or_else(|| TodoTrayError::NotFound {
-        message: "Todoist task with due date not found".to_string(),
-    })?;
+    tracing::info!("Initial refresh complete");
 
-    let due = DateTime::parse_from_rfc3339(&current_due)
-        .map(|dt| dt.with_timezone(&Utc))
-        .map_err(|e| TodoTrayError::Unexpected {
-            message: format!("Invalid due datetime on task: {}", e),
-        })?;
-    let new_due = due + duration;
-    let due_datetime = new_due.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    if let Some(e) = rejected {
+        let mut state = core.state.lock().await;
+        clear_source_error(&mut state, "todoist", None);
+        state.source_errors.insert(
+            0,
+            SourceError::new("todoist", &anyhow::anyhow!(e.to_string())),
+        );
+        sync_error_message(&mut state);
+        let state_copy = visible_state(&state);
+        drop(state);
+        core.event_handler.on_state_changed(state_copy);
+    }
+}
 
-    core.todoist_client
-        .update_task_due_datetime(&task_id, &due_datetime)
-        .await
-        .map_err(|e| TodoTrayError::Network {
-            message: e.to_string(),
-        })?;
+/// Randomly stretches or shrinks refresh periods by up to `REFRESH_JITTER`.
+struct Jitter {
+    rng: fastrand::Rng,
+}
 
-    // Refresh only Todoist-backed task sections; other sources refresh on interval.
-    refresh_todoist_tasks(core).await
+impl Jitter {
+    /// Seed `rng` for a repeatable sequence of delays.
+    fn new(rng: fastrand::Rng) -> Self {
+        Self { rng }
+    }
+
+    /// `period` moved by a random amount within ±`REFRESH_JITTER` of itself.
+    fn delay(&mut self, period: Duration) -> Duration {
+        let offset = (self.rng.f64() * 2.0 - 1.0) * REFRESH_JITTER;
+        period.mul_f64(1.0 + offset)
+    }
+
+    /// A random pause of at most `max`.
+    fn stagger(&mut self, max: Duration) -> Duration {
+        max.mul_f64(self.rng.f64())
+    }
 }
 
-async fn resolve_github_notification_internal(
+/// Call `refresh` about every `period`, or straight away when `notify_resumed`
+/// fires, which also restarts the period. Returns once the core shuts down,
+/// dropping any refresh still in flight.
+async fn run_refresh_loop<F, Fut>(
     core: &TodoTrayCore,
-    account_name: String,
-    thread_id: String,
-) -> Result<(), TodoTrayError> {
-    let client = core
-        .github_clients
-        .iter()
-        .find(|client| client.account_name() == account_name)
-        .cloned()
-        .ok_or_else(|| TodoTrayError::NotFound {
-            message: format!("GitHub account not found: {}", account_name),
-        })?;
+    period: Duration,
+    mut jitter: Jitter,
+    mut refresh: F,
+) where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = ()>,
+{
+    loop {
+        let delay = jitter.delay(period);
+        let next = async {
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = core.resumed.notified() => {
+                    tracing::info!("Resumed from sleep, refreshing");
+                }
+            }
+            refresh().await;
+        };
+        tokio::select! {
+            biased;
+            _ = core.shut_down() => break,
+            _ = next => {}
+        }
+    }
+    tracing::info!("Refresh loop stopped");
+}
 
-    client
-        .mark_notification_as_read(&thread_id)
+/// Run a future on the shared runtime and await its result from any executor.
+async fn run_on_runtime<T, F>(future: F) -> Result<T, TodoTrayError>
+where
+    T: Send + 'static,
+    F: std::future::Future<Output = Result<T, TodoTrayError>> + Send + 'static,
+{
+    TOKIO_RUNTIME
+        .spawn(future)
         .await
-        .map_err(|e| TodoTrayError::Network {
-            message: e.to_string(),
-        })?;
-
-    // Refresh only this account's GitHub notifications; other sources refresh on interval.
-    refresh_single_github_account(core, &account_name).await
+        .map_err(|e| TodoTrayError::Unexpected {
+            message: format!("Background task failed: {}", e),
+        })?
 }
 
-async fn refresh_todoist_tasks(core: &TodoTrayCore) -> Result<(), TodoTrayError> {
-    let mut todoist_tasks = core
-        .todoist_client
-        .get_tasks()
+// Internal async implementations
+
+/// Check the Todoist token, reporting a rejected token as a config error.
+async fn verify_todoist_token(core: &TodoTrayCore) -> Result<String, TodoTrayError> {
+    core.clients()
+        .todoist
+        .verify_token()
         .await
-        .map_err(|e| TodoTrayError::Network {
+        .map_err(api_error)
+}
+
+/// Report a rejected token as a config error and anything else as a network error.
+fn api_error(e: anyhow::Error) -> TodoTrayError {
+    if e.downcast_ref::<InvalidTokenError>().is_some() {
+        TodoTrayError::Config {
             message: e.to_string(),
-        })?;
+        }
+    } else {
+        TodoTrayError::Network {
+            message: e.to_string(),
+        }
+    }
+}
 
-    // Keep currently-cached Linear tasks; they will be refreshed on the regular interval.
-    let cached_linear = {
-        let state = core.state.lock().await;
-        state.tasks.in_progress.clone()
-    };
-    todoist_tasks.extend(cached_linear);
+/// Refresh every source, waiting for one already in flight. Requests that
+/// pile up behind it share a single refresh instead of each fetching again.
+async fn refresh_tasks(core: &TodoTrayCore) -> Result<(), TodoTrayError> {
+    let request = core.refresh_requests.fetch_add(1, Ordering::SeqCst) + 1;
+    let _gate = core.refresh_gate.lock().await;
+    if core.refresh_covered.load(Ordering::SeqCst) >= request {
+        tracing::debug!("Refresh already done by a later request");
+        return Ok(());
+    }
+    let covers = core.refresh_requests.load(Ordering::SeqCst);
+    let result = fetch_and_apply(core).await;
+    core.refresh_covered.store(covers, Ordering::SeqCst);
+    result
+}
 
-    let grouped = group_tasks(todoist_tasks);
+#[tracing::instrument(
+    name = "refresh",
+    skip_all,
+    fields(task_count, github_count, calendar_count, all_failed)
+)]
+async fn fetch_and_apply(core: &TodoTrayCore) -> Result<(), TodoTrayError> {
+    let started = Instant::now();
+    let clients = core.clients();
+    let todoist = async {
+        if core.is_source_disabled("todoist") {
+            return Ok(Vec::new());
+        }
+        clients.todoist.get_tasks().await
+    };
+    let linear = async {
+        match &clients.linear {
+            Some(_) if core.is_source_disabled("linear") => None,
+            Some(client) => Some(client.get_in_progress_issues().await),
+            None => None,
+        }
+    };
+    let (mut todoist, mut linear) = tokio::join!(todoist, linear);
+    if let Ok(tasks) = &mut todoist {
+        core.hide_pending_completions(tasks);
+        let mut recurring = core.recurring.lock().unwrap();
+        recurring.expire_before(started);
+        recurring.hide(tasks);
+        drop(recurring);
+        clients.clean_titles(tasks);
+    }
+    if let Some(Ok(tasks)) = &mut linear {
+        core.hide_pending_completions(tasks);
+    }
+    let github = fetch_github_notifications(core).await;
+    let calendar = fetch_calendar_events(core).await;
+    let results = RefreshResults {
+        todoist,
+        linear,
+        github,
+        calendar,
+    };
+    let all_failed = results.all_failed(!clients.calendar.is_empty());
+    let github_fetched: Vec<String> = results
+        .github
+        .iter()
+        .filter(|account| account.result.is_ok())
+        .map(|account| account.account_name.clone())
+        .collect();
+    core.fetch_cache.lock().unwrap().store(&results);
 
     let mut state = core.state.lock().await;
-    apply_grouped_tasks_to_state(&mut state, grouped);
+    let initial_load = state.is_loading;
+    let previous_errors = state.source_errors.clone();
+    apply_refresh_results(
+        &mut state,
+        results,
+        &clients.label_filter,
+        clients.show_tomorrow_after_hour,
+        clients.all_day_tasks,
+        clients.count_remaining_events,
+    );
+    if !all_failed {
+        mark_updated(&mut state);
+    }
+    {
+        let now = Utc::now();
+        let mut health = core.health.lock().unwrap();
+        for (source, account_name) in clients.sources() {
+            if core.is_source_disabled(source) {
+                continue;
+            }
+            let error = state
+                .source_errors
+                .iter()
+                .find(|error| error.source == source && error.account_name == account_name);
+            health.record(
+                source,
+                account_name.as_deref(),
+                error.map(|error| error.message.as_str()),
+                now,
+            );
+        }
+    }
+    let task_count = state.tasks.todoist_tasks().count() + state.tasks.in_progress.len();
+    tracing::Span::current()
+        .record("task_count", task_count)
+        .record("github_count", state.github_notification_count)
+        .record("calendar_count", state.calendar_event_count)
+        .record("all_failed", all_failed);
+    let error_report = refresh_error_report(
+        &previous_errors,
+        &state.source_errors,
+        initial_load && all_failed,
+    );
+    state.is_stale = core
+        .refresh_failures
+        .lock()
+        .unwrap()
+        .record(all_failed, clients.stale_after_failures);
+    {
+        // Completions reset at local midnight.
+        let mut completions = core.completions.lock().unwrap();
+        completions.prune(Local::now().date_naive());
+        completions.apply_to(&mut state);
+    }
+    let alerts = core
+        .github_alerts
+        .lock()
+        .unwrap()
+        .new_alerts(&state.github_notifications, &github_fetched);
+    let all_clear = core.overdue.lock().unwrap().record(state.overdue_count);
+    let overdue_alert = core
+        .overdue_alerts
+        .lock()
+        .unwrap()
+        .new_alert(&state.tasks.overdue, clients.notify_min_priority);
+
     let state_copy = state.clone();
     drop(state);
 
-    core.event_handler.on_state_changed(state_copy);
-    Ok(())
-}
+    if let Some(path) = &core.state_cache_path {
+        if let Err(e) = json_store::save(path, &StateSnapshot::from_state(&state_copy)) {
+            tracing::warn!(error = %e, "Failed to write state cache");
+        }
+    }
 
-async fn refresh_single_github_account(
-    core: &TodoTrayCore,
-    account_name: &str,
-) -> Result<(), TodoTrayError> {
-    let client = core
-        .github_clients
-        .iter()
-        .find(|client| client.account_name() == account_name)
-        .cloned()
-        .ok_or_else(|| TodoTrayError::NotFound {
-            message: format!("GitHub account not found: {}", account_name),
-        })?;
+    core.event_handler
+        .on_state_changed(visible_state(&state_copy));
+    if !alerts.is_empty() && !state_copy.focus_mode {
+        core.event_handler.on_github_alert(alerts);
+    }
+    if all_clear && !state_copy.focus_mode {
+        core.event_handler.on_all_clear();
+    }
+    if let Some(alert) = overdue_alert {
+        core.event_handler.on_overdue_alert(alert);
+    }
+    if let Some((message, severity)) = error_report {
+        core.event_handler.on_error(message, severity);
+    }
 
-    let section = client
-        .get_notifications()
-        .await
-        .map_err(|e| TodoTrayError::Network {
-            message: e.to_string(),
-        })?;
+    Ok(())
+}
 
-    let mut state = core.state.lock().await;
-    let existing_index = state
-        .github_notifications
-        .iter()
-        .position(|s| s.account_name == account_name);
-    state
-        .github_notifications
-        .retain(|s| s.account_name != account_name);
-    if !section.notifications.is_empty() {
-        if let Some(index) = existing_index {
-            let index = index.min(state.github_notifications.len());
-            state.github_notifications.insert(index, section);
-        } else {
-            state.github_notifications.push(section);
+/// Emit a state built from the cached fetch results straight away, if every
+/// source was fetched within `max_stale`, then refresh and emit the result.
+async fn serve_then_refresh(core: &TodoTrayCore) -> Result<(), TodoTrayError> {
+    let clients = core.clients();
+    let cached = core
+        .fetch_cache
+        .lock()
+        .unwrap()
+        .fresh_results(core, clients.max_stale);
+    if let Some(mut results) = cached {
+        if let Ok(tasks) = &mut results.todoist {
+            core.hide_pending_completions(tasks);
         }
+        if let Some(Ok(tasks)) = &mut results.linear {
+            core.hide_pending_completions(tasks);
+        }
+        for account in &mut results.github {
+            if let Ok(section) = &mut account.result {
+                core.hide_snoozed_github(section);
+            }
+        }
+        let mut state = core.state.lock().await.clone();
+        apply_refresh_results(
+            &mut state,
+            results,
+            &clients.label_filter,
+            clients.show_tomorrow_after_hour,
+            clients.all_day_tasks,
+            clients.count_remaining_events,
+        );
+        core.completions.lock().unwrap().apply_to(&mut state);
+        core.event_handler.on_state_changed(visible_state(&state));
     }
-    state.github_notification_count = state
-        .github_notifications
+    refresh_tasks(core).await
+}
+
+/// Error to report after a refresh: blocking when the first load reached
+/// nothing, otherwise transient for sources that just started failing.
+/// Sources that keep failing are only reported once.
+fn refresh_error_report(
+    previous: &[SourceError],
+    current: &[SourceError],
+    initial_load_failed: bool,
+) -> Option<(String, ErrorSeverity)> {
+    if initial_load_failed {
+        let message = current.first()?.message.clone();
+        return Some((message, ErrorSeverity::Blocking));
+    }
+    let is_new = |error: &&SourceError| {
+        !previous
+            .iter()
+            .any(|old| old.source == error.source && old.account_name == error.account_name)
+    };
+    let message = current
         .iter()
-        .map(|section| section.notifications.len() as u32)
-        .sum();
-    state.is_loading = false;
-    state.error_message = None;
-    let state_copy = state.clone();
-    drop(state);
+        .filter(is_new)
+        .map(|error| error.message.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    (!message.is_empty()).then_some((message, ErrorSeverity::Transient))
+}
 
-    core.event_handler.on_state_changed(state_copy);
+/// Backend that owns a task, derived from `TodoTask::source`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TaskBackend {
+    Todoist,
+    Linear,
+}
+
+impl TaskBackend {
+    fn for_source(source: &str) -> Option<Self> {
+        match source {
+            "todoist" => Some(Self::Todoist),
+            "linear" => Some(Self::Linear),
+            _ => None,
+        }
+    }
+}
+
+async fn complete_task(core: &Arc<TodoTrayCore>, task_id: String) -> Result<(), TodoTrayError> {
+    core.clients().ensure_writable()?;
+
+    // Lookup the task first so we can route completion to the owning source.
+    let selected_task = {
+        let state = core.state.lock().await;
+        let task = state
+            .tasks
+            .todoist_tasks()
+            .chain(state.tasks.in_progress.iter())
+            .find(|t| t.id == task_id)
+            .cloned();
+        task
+    };
+
+    let task = selected_task.ok_or_else(|| TodoTrayError::NotFound {
+        message: format!("Task not found: {}", task_id),
+    })?;
+
+    let backend = TaskBackend::for_source(&task.source).filter(|_| task.can_complete);
+    let Some(backend) = backend else {
+        return Err(TodoTrayError::Unexpected {
+            message: "This task is read-only and cannot be completed from Todo Tray.".to_string(),
+        });
+    };
+
+    let clients = core.clients();
+    if backend == TaskBackend::Linear && clients.linear.is_none() {
+        return Err(TodoTrayError::Config {
+            message: "Linear is not configured".to_string(),
+        });
+    }
+
+    if clients.undo_window.is_zero() {
+        close_task(core, &task, backend).await?;
+        let task_name = task.content.clone();
+        record_completion(core, task).await;
+        core.event_handler.on_task_completed(task_name, None);
+        return refresh_task_source(core, backend).await;
+    }
+
+    // Hide the task right away and close it once the undo window has passed.
+    let task_name = task.content.clone();
+    let undo_token = core
+        .pending_completions
+        .lock()
+        .unwrap()
+        .add(vec![task], backend);
+    {
+        let mut state = core.state.lock().await;
+        let mut tasks = state
+            .tasks
+            .todoist_tasks()
+            .chain(state.tasks.in_progress.iter())
+            .cloned()
+            .collect();
+        core.hide_pending_completions(&mut tasks);
+        regroup_tasks(&mut state, tasks, &clients);
+        let state_copy = visible_state(&state);
+        drop(state);
+        core.event_handler.on_state_changed(state_copy);
+    }
+    core.event_handler
+        .on_task_completed(task_name, Some(undo_token.clone()));
+    close_after_undo_window(core, clients.undo_window, undo_token);
     Ok(())
 }
 
-fn apply_grouped_tasks_to_state(state: &mut AppState, grouped: TaskList) {
-    state.overdue_count = grouped.overdue.len() as u32;
-    state.today_count = grouped.today.len() as u32;
-    state.tomorrow_count = grouped.tomorrow.len() as u32;
-    state.in_progress_count = grouped.in_progress.len() as u32;
-    state.tasks = grouped;
-    state.is_loading = false;
-    state.error_message = None;
+/// Close the completion for `undo_token` once `undo_window` has passed.
+fn close_after_undo_window(core: &Arc<TodoTrayCore>, undo_window: Duration, undo_token: String) {
+    let timer = TOKIO_RUNTIME.spawn({
+        let core = Arc::clone(core);
+        async move {
+            // Quitting doesn't undo a completion; close it right away instead.
+            // `shutdown` waits for this before returning.
+            tokio::select! {
+                _ = tokio::time::sleep(undo_window) => {}
+                _ = core.shut_down() => {}
+            }
+            finish_pending_completion(&core, &undo_token).await;
+        }
+    });
+    let mut timers = core.undo_timers.lock().unwrap();
+    timers.retain(|timer| !timer.is_finished());
+    timers.push(timer);
+}
+
+/// Wait until every undo window started so far has passed and its
+/// completion was closed, or undone.
+async fn wait_for_undo_timers(core: &TodoTrayCore) {
+    loop {
+        let timers = std::mem::take(&mut *core.undo_timers.lock().unwrap());
+        if timers.is_empty() {
+            return;
+        }
+        for timer in timers {
+            let _ = timer.await;
+        }
+    }
+}
+
+/// Close the completion for `undo_token` unless it was undone meanwhile.
+async fn finish_pending_completion(core: &TodoTrayCore, undo_token: &str) {
+    let Some(pending) = core.pending_completions.lock().unwrap().take(undo_token) else {
+        return;
+    };
+    let total = pending.tasks.len();
+    let closed = close_tasks(core, pending.tasks, pending.backend).await;
+    if !closed.still_open.is_empty() {
+        // Those tasks are still open, so show them again.
+        restore_tasks(core, closed.still_open).await;
+    }
+    let mut result = match closed.completed {
+        0 => Ok(()),
+        _ => refresh_task_source(core, pending.backend).await,
+    };
+    if let Some(e) = closed.first_error {
+        result = Err(e);
+    }
+    if let Err(e) = result {
+        let message = match total {
+            1 => e.to_string(),
+            _ => format!(
+                "Couldn't complete {} of {} tasks: {}",
+                total - closed.completed as usize,
+                total,
+                e
+            ),
+        };
+        core.event_handler
+            .on_error(message, ErrorSeverity::Transient);
+    }
 }
 
-async fn fetch_github_notifications(
+/// Tasks closed by `close_tasks` and those that stayed open
+struct ClosedTasks {
+    completed: u32,
+    still_open: Vec<TodoTask>,
+    first_error: Option<TodoTrayError>,
+}
+
+/// Close `tasks` one at a time, recording each completion and carrying on
+/// past failures.
+async fn close_tasks(
     core: &TodoTrayCore,
-) -> Result<Vec<GithubNotificationSection>, TodoTrayError> {
-    let mut sections = Vec::new();
-    for client in &core.github_clients {
-        let section = client
-            .get_notifications()
-            .await
-            .map_err(|e| TodoTrayError::Network {
-                message: e.to_string(),
-            })?;
-        if !section.notifications.is_empty() {
-            sections.push(section);
+    tasks: Vec<TodoTask>,
+    backend: TaskBackend,
+) -> ClosedTasks {
+    let mut closed = ClosedTasks {
+        completed: 0,
+        still_open: Vec::new(),
+        first_error: None,
+    };
+    for task in tasks {
+        match close_task(core, &task, backend).await {
+            Ok(()) => {
+                record_completion(core, task).await;
+                closed.completed += 1;
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, task_id = %task.id, "Failed to complete task");
+                closed.first_error.get_or_insert(e);
+                closed.still_open.push(task);
+            }
         }
     }
-    Ok(sections)
+    closed
+}
+
+async fn complete_all_today(core: &Arc<TodoTrayCore>) -> Result<BulkCompletion, TodoTrayError> {
+    let clients = core.clients();
+    clients.ensure_writable()?;
+    let (tasks, skipped): (Vec<TodoTask>, Vec<TodoTask>) = {
+        let state = core.state.lock().await;
+        state
+            .tasks
+            .today
+            .iter()
+            .cloned()
+            .partition(|task| task.source == "todoist" && task.can_complete)
+    };
+    let mut outcome = BulkCompletion {
+        skipped: skipped.len() as u32,
+        ..Default::default()
+    };
+    if tasks.is_empty() {
+        return Ok(outcome);
+    }
+    let summary = match tasks.as_slice() {
+        [task] => task.content.clone(),
+        _ => format!("{} tasks", tasks.len()),
+    };
+
+    if !clients.undo_window.is_zero() {
+        // Hide them right away; one undo brings the whole batch back.
+        outcome.completed = tasks.len() as u32;
+        let undo_token = core
+            .pending_completions
+            .lock()
+            .unwrap()
+            .add(tasks, TaskBackend::Todoist);
+        {
+            let mut state = core.state.lock().await;
+            let mut tasks = state
+                .tasks
+                .todoist_tasks()
+                .chain(state.tasks.in_progress.iter())
+                .cloned()
+                .collect();
+            core.hide_pending_completions(&mut tasks);
+            regroup_tasks(&mut state, tasks, &clients);
+            let state_copy = visible_state(&state);
+            drop(state);
+            core.event_handler.on_state_changed(state_copy);
+        }
+        core.event_handler
+            .on_task_completed(summary, Some(undo_token.clone()));
+        close_after_undo_window(core, clients.undo_window, undo_token);
+        return Ok(outcome);
+    }
+
+    let total = tasks.len();
+    let closed = close_tasks(core, tasks, TaskBackend::Todoist).await;
+    outcome.completed = closed.completed;
+    outcome.failed = closed.still_open.len() as u32;
+    let failure = closed.first_error.map(|e| {
+        SourceError::new(
+            "todoist",
+            &anyhow::anyhow!(
+                "Couldn't complete {} of {} tasks: {}",
+                outcome.failed,
+                total,
+                e
+            ),
+        )
+    });
+    refresh_todoist_tasks_reporting(core, failure).await?;
+    if outcome.completed > 0 {
+        core.event_handler.on_task_completed(summary, None);
+    }
+    Ok(outcome)
 }
 
-async fn fetch_calendar_events(
+/// Close `task` in the backend that owns it.
+async fn close_task(
     core: &TodoTrayCore,
-) -> Result<Vec<CalendarEventSection>, TodoTrayError> {
-    let mut sections = Vec::new();
-    for client in &core.calendar_clients {
-        let section = client
-            .get_today_events()
+    task: &TodoTask,
+    backend: TaskBackend,
+) -> Result<(), TodoTrayError> {
+    let clients = core.clients();
+    match backend {
+        TaskBackend::Todoist => clients
+            .todoist
+            .complete_task(&task.id)
             .await
-            .map_err(|e| TodoTrayError::Network {
-                message: e.to_string(),
-            })?;
-        if !section.events.is_empty() {
-            sections.push(section);
+            .map_err(api_error),
+        TaskBackend::Linear => {
+            let client = clients
+                .linear
+                .as_ref()
+                .ok_or_else(|| TodoTrayError::Config {
+                    message: "Linear is not configured".to_string(),
+                })?;
+            client
+                .complete_issue(&task.id, &clients.linear_complete_state)
+                .await
+                .map_err(api_error)
         }
     }
-    Ok(sections)
 }
 
-fn parse_snooze_duration(input: &str) -> Result<chrono::Duration, String> {
-    let value = input.trim().to_lowercase();
-    if value.len() < 2 {
-        return Err(format!("Invalid snooze duration '{}'", input));
+async fn record_completion(core: &TodoTrayCore, task: TodoTask) {
+    if task.is_recurring && core.clients().collapse_completed_recurring {
+        core.recurring.lock().unwrap().add(&task.id, Instant::now());
+    }
+    let mut state = core.state.lock().await;
+    let mut completions = core.completions.lock().unwrap();
+    completions.prune(Local::now().date_naive());
+    completions.record(task, Utc::now());
+    completions.apply_to(&mut state);
+}
+
+/// Refresh only the owning source's tasks; other sources refresh on interval.
+async fn refresh_task_source(
+    core: &TodoTrayCore,
+    backend: TaskBackend,
+) -> Result<(), TodoTrayError> {
+    match backend {
+        TaskBackend::Todoist => refresh_todoist_tasks(core).await,
+        TaskBackend::Linear => refresh_linear_tasks(core).await,
+    }
+}
+
+/// Show tasks hidden by a pending completion again.
+async fn restore_tasks(core: &TodoTrayCore, restored: Vec<TodoTask>) {
+    let clients = core.clients();
+    let mut state = core.state.lock().await;
+    let mut tasks: Vec<TodoTask> = state
+        .tasks
+        .todoist_tasks()
+        .chain(state.tasks.in_progress.iter())
+        .cloned()
+        .collect();
+    tasks.extend(restored);
+    regroup_tasks(&mut state, tasks, &clients);
+    let state_copy = visible_state(&state);
+    drop(state);
+
+    core.event_handler.on_state_changed(state_copy);
+}
+
+async fn reopen_task(core: &TodoTrayCore, task_id: String) -> Result<(), TodoTrayError> {
+    core.clients().ensure_writable()?;
+    let source = {
+        let completions = core.completions.lock().unwrap();
+        completions.recent(&task_id).map(|task| task.source.clone())
+    }
+    .ok_or_else(|| TodoTrayError::NotFound {
+        message: format!("Task was not recently completed: {}", task_id),
+    })?;
+    if TaskBackend::for_source(&source) != Some(TaskBackend::Todoist) {
+        return Err(TodoTrayError::Unexpected {
+            message: "Only Todoist tasks can be reopened from Todo Tray.".to_string(),
+        });
+    }
+
+    core.clients()
+        .todoist
+        .reopen_task(&task_id)
+        .await
+        .map_err(api_error)?;
+
+    {
+        let mut state = core.state.lock().await;
+        let mut completions = core.completions.lock().unwrap();
+        completions.remove(&task_id);
+        completions.apply_to(&mut state);
+    }
+    refresh_todoist_tasks(core).await
+}
+
+/// The cached Todoist task `task_id`, or an error saying only Todoist tasks
+/// can be `what`, e.g. "deleted".
+async fn cached_todoist_task(
+    core: &TodoTrayCore,
+    task_id: &str,
+    what: &str,
+) -> Result<TodoTask, TodoTrayError> {
+    let task = {
+        let state = core.state.lock().await;
+        let task = state
+            .tasks
+            .todoist_tasks()
+            .chain(state.tasks.in_progress.iter())
+            .find(|t| t.id == task_id)
+            .cloned();
+        task
+    }
+    .ok_or_else(|| TodoTrayError::NotFound {
+        message: format!("Task not found: {}", task_id),
+    })?;
+    if TaskBackend::for_source(&task.source) != Some(TaskBackend::Todoist) || !task.can_complete {
+        return Err(TodoTrayError::Unexpected {
+            message: format!("Only Todoist tasks can be {} from Todo Tray.", what),
+        });
+    }
+    Ok(task)
+}
+
+async fn delete_task(core: &TodoTrayCore, task_id: String) -> Result<(), TodoTrayError> {
+    core.clients().ensure_writable()?;
+    cached_todoist_task(core, &task_id, "deleted").await?;
+
+    core.clients()
+        .todoist
+        .delete_task(&task_id)
+        .await
+        .map_err(api_error)?;
+    refresh_todoist_tasks(core).await
+}
+
+async fn move_task(
+    core: &TodoTrayCore,
+    task_id: String,
+    project_id: String,
+) -> Result<(), TodoTrayError> {
+    let clients = core.clients();
+    clients.ensure_writable()?;
+    cached_todoist_task(core, &task_id, "moved").await?;
+
+    clients
+        .todoist
+        .move_task(&task_id, &project_id)
+        .await
+        .map_err(api_error)?;
+    refresh_todoist_tasks(core).await
+}
+
+async fn snooze_task(
+    core: &TodoTrayCore,
+    task_id: String,
+    duration_label: String,
+) -> Result<(), TodoTrayError> {
+    let clients = core.clients();
+    clients.ensure_writable()?;
+    let target = clients.snooze_target(&duration_label)?;
+
+    let current_due = {
+        let state = core.state.lock().await;
+        let due = state
+            .tasks
+            .todoist_tasks()
+            .find(|t| t.id == task_id && t.source == "todoist")
+            .map(|t| (t.due_datetime.clone(), t.all_day));
+        due
+    }
+    .ok_or_else(|| TodoTrayError::NotFound {
+        message: "Todoist task not found".to_string(),
+    })?;
+
+    let (due_datetime, all_day) = current_due;
+    let due = due_datetime
+        .map(|due| {
+            DateTime::parse_from_rfc3339(&due)
+                .map(|dt| {
+                    if all_day {
+                        Due::Date(dt.with_timezone(&Local).date_naive())
+                    } else {
+                        Due::DateTime(dt.with_timezone(&Utc))
+                    }
+                })
+                .map_err(|e| TodoTrayError::Unexpected {
+                    message: format!("Invalid due datetime on task: {}", e),
+                })
+        })
+        .transpose()?;
+    let new_due = target
+        .resolve(due, Local::now(), clients.skip_weekends)
+        .ok_or_else(|| TodoTrayError::NotFound {
+            message: "Todoist task has no due date to snooze from".to_string(),
+        })?;
+
+    let update = match new_due {
+        Due::Date(date) => {
+            let due_date = date.format("%Y-%m-%d").to_string();
+            clients
+                .todoist
+                .update_task_due_date(&task_id, &due_date)
+                .await
+        }
+        Due::DateTime(dt) => {
+            let due_datetime = dt.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+            clients
+                .todoist
+                .update_task_due_datetime(&task_id, &due_datetime)
+                .await
+        }
+    };
+    update.map_err(api_error)?;
+
+    // Refresh only Todoist-backed task sections; other sources refresh on interval.
+    refresh_todoist_tasks(core).await
+}
+
+async fn quick_add_task(
+    core: &TodoTrayCore,
+    text: String,
+) -> Result<QuickAddResult, TodoTrayError> {
+    let clients = core.clients();
+    clients.ensure_writable()?;
+    if text.trim().is_empty() {
+        return Err(TodoTrayError::Unexpected {
+            message: "Type a task to add".to_string(),
+        });
+    }
+    let added = clients.todoist.quick_add(&text).await.map_err(api_error)?;
+    refresh_todoist_tasks(core).await?;
+    Ok(added)
+}
+
+async fn set_source_enabled(
+    core: &TodoTrayCore,
+    source: String,
+    enabled: bool,
+) -> Result<(), TodoTrayError> {
+    let source = source.trim().to_lowercase();
+    if !source_toggles::SOURCES.contains(&source.as_str()) {
+        return Err(TodoTrayError::NotFound {
+            message: format!("Unknown source: {}", source),
+        });
+    }
+    let names = {
+        let mut disabled = core.disabled_sources.lock().unwrap();
+        if !disabled.set(&source, enabled) {
+            return Ok(());
+        }
+        if let Some(path) = &core.disabled_sources_path {
+            if let Err(e) = json_store::save(path, &*disabled) {
+                tracing::warn!(error = %e, "Failed to write disabled sources");
+            }
+        }
+        disabled.names()
+    };
+
+    let clients = core.clients();
+    let mut state = core.state.lock().await;
+    state.disabled_sources = names;
+    if enabled {
+        drop(state);
+        return refresh_tasks(core).await;
+    }
+    match source.as_str() {
+        "todoist" | "linear" => {
+            let tasks = state
+                .tasks
+                .todoist_tasks()
+                .chain(state.tasks.in_progress.iter())
+                .filter(|task| match source.as_str() {
+                    "linear" => task.source != "linear",
+                    _ => task.source == "linear",
+                })
+                .cloned()
+                .collect();
+            regroup_tasks(&mut state, tasks, &clients);
+        }
+        "github" => {
+            state.github_notifications.clear();
+            state.github_notification_count = 0;
+        }
+        _ => {
+            state.calendar_events.clear();
+            state.calendar_event_count = 0;
+        }
+    }
+    state.summary = summarize_counts(&state);
+    clear_source_error(&mut state, &source, None);
+    let state_copy = visible_state(&state);
+    drop(state);
+    core.event_handler.on_state_changed(state_copy);
+    Ok(())
+}
+
+async fn reschedule_overdue_to_today(core: &TodoTrayCore) -> Result<u32, TodoTrayError> {
+    let clients = core.clients();
+    clients.ensure_writable()?;
+    let tasks: Vec<(String, Option<String>)> = {
+        let state = core.state.lock().await;
+        state
+            .tasks
+            .overdue
+            .iter()
+            .filter(|task| task.source == "todoist" && task.can_complete && !task.is_recurring)
+            .map(|task| {
+                let due_datetime = task.due_datetime.clone().filter(|_| !task.all_day);
+                (task.id.clone(), due_datetime)
+            })
+            .collect()
+    };
+    if tasks.is_empty() {
+        return Ok(0);
     }
 
-    let (number_part, unit_part) = value.split_at(value.len() - 1);
-    let amount: i64 = number_part
-        .parse()
-        .map_err(|_| format!("Invalid snooze duration '{}'", input))?;
-    if amount <= 0 {
-        return Err(format!("Snooze duration must be positive: '{}'", input));
+    let now = Local::now();
+    let mut rescheduled = 0;
+    let mut first_error = None;
+    for (task_id, due_datetime) in &tasks {
+        let time_of_day = due_datetime
+            .as_deref()
+            .and_then(|due| DateTime::parse_from_rfc3339(due).ok())
+            .map(|due| due.with_timezone(&Local).time());
+        let update = match time_of_day.and_then(|time| {
+            now.date_naive()
+                .and_time(time)
+                .and_local_timezone(Local)
+                .earliest()
+        }) {
+            Some(due) => {
+                let due_datetime = due.to_utc().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+                clients
+                    .todoist
+                    .update_task_due_datetime(task_id, &due_datetime)
+                    .await
+            }
+            None => {
+                let due_date = now.format("%Y-%m-%d").to_string();
+                clients
+                    .todoist
+                    .update_task_due_date(task_id, &due_date)
+                    .await
+            }
+        };
+        match update {
+            Ok(()) => rescheduled += 1,
+            Err(e) => {
+                tracing::warn!(error = %e, task_id, "Failed to reschedule overdue task");
+                first_error.get_or_insert(e);
+            }
+        }
     }
 
-    match unit_part {
-        "m" => Ok(chrono::Duration::minutes(amount)),
-        "h" => Ok(chrono::Duration::hours(amount)),
-        "d" => Ok(chrono::Duration::days(amount)),
-        _ => Err(format!(
-            "Unsupported snooze duration unit in '{}'. Use m, h, or d.",
-            input
-        )),
+    let failure = first_error.map(|e| {
+        let failed = tasks.len() as u32 - rescheduled;
+        SourceError::new(
+            "todoist",
+            &anyhow::anyhow!(
+                "Couldn't reschedule {} of {} overdue tasks: {}",
+                failed,
+                tasks.len(),
+                e
+            ),
+        )
+    });
+    refresh_todoist_tasks_reporting(core, failure).await?;
+    Ok(rescheduled)
+}
+
+fn github_client(
+    core: &TodoTrayCore,
+    account_name: &str,
+) -> Result<Arc<GithubClient>, TodoTrayError> {
+    core.clients()
+        .github
+        .iter()
+        .find(|client| client.account_name() == account_name)
+        .cloned()
+        .ok_or_else(|| TodoTrayError::NotFound {
+            message: format!("GitHub account not found: {}", account_name),
+        })
+}
+
+async fn resolve_github_notification_internal(
+    core: &TodoTrayCore,
+    account_name: String,
+    thread_id: String,
+) -> Result<(), TodoTrayError> {
+    core.clients().ensure_writable()?;
+    let client = github_client(core, &account_name)?;
+
+    client
+        .mark_notification_as_read(&thread_id)
+        .await
+        .map_err(api_error)?;
+
+    // Refresh only this account's GitHub notifications; other sources refresh on interval.
+    refresh_single_github_account(core, &account_name).await
+}
+
+async fn snooze_github_notification_internal(
+    core: &TodoTrayCore,
+    account_name: String,
+    thread_id: String,
+    duration_label: String,
+) -> Result<(), TodoTrayError> {
+    let clients = core.clients();
+    github_client(core, &account_name)?;
+    let target = clients.snooze_target(&duration_label)?;
+    let now = Local::now();
+    let Some(Due::DateTime(until)) = target.resolve(
+        Some(Due::DateTime(now.with_timezone(&Utc))),
+        now,
+        clients.skip_weekends,
+    ) else {
+        return Err(TodoTrayError::Unexpected {
+            message: format!("Can't snooze until: {}", duration_label),
+        });
+    };
+
+    {
+        let mut snoozes = core.github_snoozes.lock().unwrap();
+        snoozes.prune(Utc::now());
+        snoozes.snooze(&account_name, &thread_id, until);
+        core.save_github_snoozes(&snoozes);
+    }
+
+    let mut state = core.state.lock().await;
+    for section in &mut state.github_notifications {
+        core.hide_snoozed_github(section);
+    }
+    state
+        .github_notifications
+        .retain(|section| !section.notifications.is_empty());
+    state.github_notification_count = state
+        .github_notifications
+        .iter()
+        .map(|section| section.notifications.len() as u32)
+        .sum();
+    state.summary = summarize_counts(&state);
+    let state_copy = visible_state(&state);
+    drop(state);
+
+    core.event_handler.on_state_changed(state_copy);
+    Ok(())
+}
+
+async fn unsubscribe_github_notification_internal(
+    core: &TodoTrayCore,
+    account_name: String,
+    thread_id: String,
+) -> Result<(), TodoTrayError> {
+    core.clients().ensure_writable()?;
+    let client = github_client(core, &account_name)?;
+
+    client
+        .unsubscribe_thread(&thread_id)
+        .await
+        .map_err(api_error)?;
+
+    // Unsubscribing leaves the thread unread, so resolve it too.
+    resolve_github_notification_internal(core, account_name, thread_id).await
+}
+
+async fn refresh_todoist_tasks(core: &TodoTrayCore) -> Result<(), TodoTrayError> {
+    refresh_todoist_tasks_reporting(core, None).await
+}
+
+/// Refresh Todoist tasks, recording `failure` from the action that led to the
+/// refresh in place of any earlier Todoist error.
+async fn refresh_todoist_tasks_reporting(
+    core: &TodoTrayCore,
+    failure: Option<SourceError>,
+) -> Result<(), TodoTrayError> {
+    if core.is_source_disabled("todoist") {
+        return Ok(());
+    }
+    let clients = core.clients();
+    let mut todoist_tasks = clients.todoist.get_tasks().await.map_err(api_error)?;
+    core.hide_pending_completions(&mut todoist_tasks);
+    core.recurring.lock().unwrap().hide(&mut todoist_tasks);
+    clients.clean_titles(&mut todoist_tasks);
+
+    // Keep currently-cached Linear tasks; they will be refreshed on the regular interval.
+    let cached_linear = {
+        let state = core.state.lock().await;
+        state.tasks.in_progress.clone()
+    };
+    todoist_tasks.extend(cached_linear);
+
+    let grouped = group_visible_tasks(
+        todoist_tasks,
+        &clients.label_filter,
+        clients.show_tomorrow_after_hour,
+        clients.all_day_tasks,
+    );
+
+    let mut state = core.state.lock().await;
+    apply_grouped_tasks_to_state(&mut state, grouped);
+    mark_updated(&mut state);
+    clear_source_error(&mut state, "todoist", None);
+    if let Some(failure) = failure {
+        state.source_errors.push(failure);
+        sync_error_message(&mut state);
+    }
+    let state_copy = visible_state(&state);
+    drop(state);
+
+    core.event_handler.on_state_changed(state_copy);
+    Ok(())
+}
+
+async fn refresh_linear_tasks(core: &TodoTrayCore) -> Result<(), TodoTrayError> {
+    let clients = core.clients();
+    let Some(client) = clients
+        .linear
+        .as_ref()
+        .filter(|_| !core.is_source_disabled("linear"))
+    else {
+        return Ok(());
+    };
+    let mut linear_tasks = client.get_in_progress_issues().await.map_err(api_error)?;
+    core.hide_pending_completions(&mut linear_tasks);
+
+    // Keep currently-cached Todoist tasks; they will be refreshed on the regular interval.
+    let mut state = core.state.lock().await;
+    linear_tasks.extend(state.tasks.todoist_tasks().cloned());
+    let grouped = group_visible_tasks(
+        linear_tasks,
+        &clients.label_filter,
+        clients.show_tomorrow_after_hour,
+        clients.all_day_tasks,
+    );
+    apply_grouped_tasks_to_state(&mut state, grouped);
+    mark_updated(&mut state);
+    clear_source_error(&mut state, "linear", None);
+    let state_copy = visible_state(&state);
+    drop(state);
+
+    core.event_handler.on_state_changed(state_copy);
+    Ok(())
+}
+
+async fn refresh_single_github_account(
+    core: &TodoTrayCore,
+    account_name: &str,
+) -> Result<(), TodoTrayError> {
+    let client = github_client(core, account_name)?;
+    if core.is_source_disabled("github") {
+        return Ok(());
+    }
+
+    let mut section = client.get_notifications().await.map_err(api_error)?;
+    core.hide_snoozed_github(&mut section);
+
+    let mut state = core.state.lock().await;
+    let existing_index = state
+        .github_notifications
+        .iter()
+        .position(|s| s.account_name == account_name);
+    state
+        .github_notifications
+        .retain(|s| s.account_name != account_name);
+    if !section.notifications.is_empty() {
+        if let Some(index) = existing_index {
+            let index = index.min(state.github_notifications.len());
+            state.github_notifications.insert(index, section);
+        } else {
+            state.github_notifications.push(section);
+        }
+    }
+    state.github_notification_count = state
+        .github_notifications
+        .iter()
+        .map(|section| section.notifications.len() as u32)
+        .sum();
+    state.summary = summarize_counts(&state);
+    state.is_loading = false;
+    mark_updated(&mut state);
+    clear_source_error(&mut state, "github", Some(account_name));
+    let alerts = core
+        .github_alerts
+        .lock()
+        .unwrap()
+        .new_alerts(&state.github_notifications, &[account_name.to_string()]);
+    let state_copy = visible_state(&state);
+    drop(state);
+
+    let focus_mode = state_copy.focus_mode;
+    core.event_handler.on_state_changed(state_copy);
+    if !alerts.is_empty() && !focus_mode {
+        core.event_handler.on_github_alert(alerts);
+    }
+    Ok(())
+}
+
+fn mark_updated(state: &mut AppState) {
+    // Whole seconds, which Foundation's ISO 8601 parser accepts by default
+    state.last_updated = Some(Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
+}
+
+/// Group tasks, leaving out tomorrow's until `show_tomorrow_after_hour`.
+fn group_visible_tasks(
+    tasks: Vec<TodoTask>,
+    labels: &LabelFilter,
+    show_tomorrow_after_hour: Option<u8>,
+    all_day: AllDayPosition,
+) -> TaskList {
+    let mut grouped = group_tasks(tasks, labels, all_day);
+    if !shows_tomorrow(show_tomorrow_after_hour, Local::now().hour()) {
+        grouped.tomorrow.clear();
+    }
+    grouped
+}
+
+/// Regroup the shown tasks after adding or removing one locally.
+fn regroup_tasks(state: &mut AppState, tasks: Vec<TodoTask>, clients: &Clients) {
+    let grouped = group_visible_tasks(
+        tasks,
+        &clients.label_filter,
+        clients.show_tomorrow_after_hour,
+        clients.all_day_tasks,
+    );
+    apply_grouped_tasks_to_state(state, grouped);
+}
+
+fn apply_grouped_tasks_to_state(state: &mut AppState, mut grouped: TaskList) {
+    state.overdue_count = grouped.overdue.len() as u32;
+    state.today_count = grouped.today.len() as u32;
+    state.tomorrow_count = grouped.tomorrow.len() as u32;
+    state.in_progress_count = grouped.in_progress.len() as u32;
+    state.sections = build_task_sections(
+        &mut grouped,
+        state.grouping,
+        state.menu_layout,
+        state.max_items_per_section.map(|max| max as usize),
+    );
+    state.tasks = grouped;
+    state.summary = summarize_counts(state);
+    state.is_loading = false;
+}
+
+/// The snapshot handed to the UI: in focus mode, everything but overdue
+/// tasks is left out.
+fn visible_state(state: &AppState) -> AppState {
+    let mut visible = state.clone();
+    if visible.read_only {
+        let lists = [
+            &mut visible.tasks.overdue,
+            &mut visible.tasks.today,
+            &mut visible.tasks.tomorrow,
+            &mut visible.tasks.other,
+            &mut visible.tasks.in_progress,
+        ];
+        let sections = visible
+            .sections
+            .iter_mut()
+            .map(|section| &mut section.tasks);
+        for task in lists.into_iter().chain(sections).flatten() {
+            task.can_complete = false;
+        }
+    }
+    if visible.focus_mode {
+        let mut tasks = TaskList {
+            overdue: std::mem::take(&mut visible.tasks.overdue),
+            ..Default::default()
+        };
+        visible.sections = build_task_sections(
+            &mut tasks,
+            visible.grouping,
+            visible.menu_layout,
+            visible.max_items_per_section.map(|max| max as usize),
+        );
+        visible.tasks = tasks;
+        visible.today_count = 0;
+        visible.tomorrow_count = 0;
+        visible.in_progress_count = 0;
+        visible.github_notifications.clear();
+        visible.github_notification_count = 0;
+        visible.calendar_events.clear();
+        visible.calendar_event_count = 0;
+        visible.summary = summarize_counts(&visible);
+    }
+    visible.title = visible.title_format.as_deref().and_then(|format| {
+        let counts = TitleCounts {
+            overdue: visible.overdue_count,
+            today: visible.today_count,
+            tomorrow: visible.tomorrow_count,
+            linear: visible.in_progress_count,
+            github: visible.github_notification_count,
+            calendar: visible.calendar_event_count,
+        };
+        title::render(format, &counts).ok()
+    });
+    visible.next_up = next_up::pick(&visible.tasks, &visible.calendar_events, Utc::now());
+    let task_count = visible.tasks.todoist_tasks().count() + visible.tasks.in_progress.len();
+    for section in &mut visible.section_order {
+        section.count = match section.kind {
+            MenuSectionKind::Tasks => task_count as u32,
+            MenuSectionKind::Github => visible.github_notification_count,
+            MenuSectionKind::Calendar => visible.calendar_event_count,
+        };
+    }
+    visible
+}
+
+/// Describe the non-zero counts in `state` in its language, most urgent first.
+pub(crate) fn summarize_counts(state: &AppState) -> String {
+    let (meetings, other_events) = calendar_counts(state);
+    let counts = [
+        (state.overdue_count, Text::OverdueCount, Text::OverdueCount),
+        (state.today_count, Text::TodayCount, Text::TodayCount),
+        (
+            state.in_progress_count,
+            Text::InProgressCount,
+            Text::InProgressCount,
+        ),
+        (
+            state.github_notification_count,
+            Text::Notification,
+            Text::Notifications,
+        ),
+        (meetings, Text::Meeting, Text::Meetings),
+        (other_events, Text::Event, Text::Events),
+    ];
+    let parts: Vec<String> = counts
+        .into_iter()
+        .filter(|(count, _, _)| *count > 0)
+        .map(|(count, singular, plural)| {
+            let text = if count == 1 { singular } else { plural };
+            state.language.format(text, count.into())
+        })
+        .collect();
+
+    if parts.is_empty() {
+        state.language.template(Text::NothingDue).to_string()
+    } else {
+        parts.join(" · ")
+    }
+}
+
+/// `calendar_event_count` split into meetings and all-day or free events,
+/// which don't take up time.
+fn calendar_counts(state: &AppState) -> (u32, u32) {
+    let other_events = state
+        .calendar_events
+        .iter()
+        .flat_map(|section| &section.events)
+        .filter(|event| event.is_all_day || event.is_free)
+        .count() as u32;
+    let other_events = other_events.min(state.calendar_event_count);
+    (state.calendar_event_count - other_events, other_events)
+}
+
+/// `summarize_counts` as a full sentence, naming when the next meeting after
+/// `now` starts in `time_format`.
+fn describe_state(state: &AppState, time_format: TimeFormat, now: DateTime<Utc>) -> String {
+    let language = state.language;
+    let count = |n: u32, singular: Text, plural: Text| {
+        language.format(if n == 1 { singular } else { plural }, n.into())
+    };
+    let mut parts = Vec::new();
+    if state.overdue_count > 0 {
+        parts.push(count(
+            state.overdue_count,
+            Text::OverdueTask,
+            Text::OverdueTasks,
+        ));
+    }
+    if state.today_count > 0 {
+        parts.push(count(state.today_count, Text::TaskToday, Text::TasksToday));
+    }
+    if state.in_progress_count > 0 {
+        parts.push(count(
+            state.in_progress_count,
+            Text::IssueInProgress,
+            Text::IssuesInProgress,
+        ));
+    }
+    if state.github_notification_count > 0 {
+        parts.push(count(
+            state.github_notification_count,
+            Text::GithubNotification,
+            Text::GithubNotifications,
+        ));
+    }
+    let (meetings, other_events) = calendar_counts(state);
+    if meetings > 0 {
+        let next = next_up::pick(&TaskList::default(), &state.calendar_events, now);
+        let start = next
+            .and_then(|item| DateTime::parse_from_rfc3339(&item.when).ok())
+            .map(|start| time_format.format_time(&start.with_timezone(&Local)));
+        let at = match start {
+            Some(_) if meetings == 1 => language.template(Text::MeetingAt),
+            Some(_) => language.template(Text::NextMeetingAt),
+            None => "",
+        };
+        parts.push(
+            count(meetings, Text::Meeting, Text::Meetings)
+                + &at.replace("{time}", &start.unwrap_or_default()),
+        );
+    }
+    if other_events > 0 {
+        parts.push(count(other_events, Text::Event, Text::Events));
+    }
+
+    let items = match parts.as_slice() {
+        [] => return language.template(Text::AllCaughtUp).to_string(),
+        [only] => only.clone(),
+        [first, second] => format!("{first}{}{second}", language.template(Text::And)),
+        [rest @ .., last] => format!(
+            "{}{}{last}",
+            rest.join(", "),
+            language.template(Text::ListAnd)
+        ),
+    };
+    language.template(Text::YouHave).replace("{items}", &items)
+}
+
+/// Per-source outcomes of one full refresh.
+struct RefreshResults {
+    todoist: anyhow::Result<Vec<TodoTask>>,
+    /// `None` when Linear is not configured.
+    linear: Option<anyhow::Result<Vec<TodoTask>>>,
+    github: Vec<GithubAccountResult>,
+    calendar: anyhow::Result<Vec<CalendarEventSection>>,
+}
+
+impl RefreshResults {
+    /// Whether no configured source could be reached.
+    fn all_failed(&self, has_calendar: bool) -> bool {
+        self.todoist.is_err()
+            && self.linear.as_ref().is_none_or(|linear| linear.is_err())
+            && self.github.iter().all(|account| account.result.is_err())
+            && (!has_calendar || self.calendar.is_err())
+    }
+}
+
+/// Merge refresh results into state, keeping cached data for sources that failed.
+fn apply_refresh_results(
+    state: &mut AppState,
+    results: RefreshResults,
+    labels: &LabelFilter,
+    show_tomorrow_after_hour: Option<u8>,
+    all_day: AllDayPosition,
+    count_remaining_events: bool,
+) {
+    let mut source_errors = Vec::new();
+
+    let mut tasks = match results.todoist {
+        Ok(tasks) => tasks,
+        Err(e) => {
+            source_errors.push(SourceError::new("todoist", &e));
+            state.tasks.todoist_tasks().cloned().collect()
+        }
+    };
+    match results.linear {
+        Some(Ok(mut linear_tasks)) => tasks.append(&mut linear_tasks),
+        Some(Err(e)) => {
+            source_errors.push(SourceError::new("linear", &e));
+            tasks.extend(state.tasks.in_progress.iter().cloned());
+        }
+        None => {}
+    }
+    apply_grouped_tasks_to_state(
+        state,
+        group_visible_tasks(tasks, labels, show_tomorrow_after_hour, all_day),
+    );
+
+    state.github_notifications = merge_github_results(
+        &state.github_notifications,
+        results.github,
+        &mut source_errors,
+    );
+    state.github_notification_count = state
+        .github_notifications
+        .iter()
+        .map(|section| section.notifications.len() as u32)
+        .sum();
+
+    match results.calendar {
+        Ok(sections) => state.calendar_events = sections,
+        Err(e) => source_errors.push(SourceError::new("calendar", &e)),
+    }
+    state.calendar_event_count = state
+        .calendar_events
+        .iter()
+        .map(|section| {
+            if count_remaining_events {
+                section.remaining_count
+            } else {
+                section.events.len() as u32
+            }
+        })
+        .sum();
+    state.summary = summarize_counts(state);
+
+    state.source_errors = source_errors;
+    sync_error_message(state);
+}
+
+/// Outcome of fetching notifications for one GitHub account.
+struct GithubAccountResult {
+    account_name: String,
+    result: anyhow::Result<GithubNotificationSection>,
+}
+
+/// Build the new GitHub sections in account order. Accounts that failed keep
+/// their previously cached section so one bad token doesn't hide the rest.
+fn merge_github_results(
+    cached: &[GithubNotificationSection],
+    results: Vec<GithubAccountResult>,
+    source_errors: &mut Vec<SourceError>,
+) -> Vec<GithubNotificationSection> {
+    let mut sections = Vec::new();
+    for GithubAccountResult {
+        account_name,
+        result,
+    } in results
+    {
+        let section = match result {
+            Ok(section) => Some(section),
+            Err(e) => {
+                source_errors.push(SourceError::for_account("github", &account_name, &e));
+                cached
+                    .iter()
+                    .find(|section| section.account_name == account_name)
+                    .cloned()
+            }
+        };
+        if let Some(section) = section.filter(|s| !s.notifications.is_empty()) {
+            sections.push(section);
+        }
+    }
+    sections
+}
+
+/// Drop any recorded error for `source` (optionally one account) after it
+/// refreshed successfully.
+fn clear_source_error(state: &mut AppState, source: &str, account_name: Option<&str>) {
+    state.source_errors.retain(|error| {
+        error.source != source
+            || (account_name.is_some() && error.account_name.as_deref() != account_name)
+    });
+    sync_error_message(state);
+}
+
+fn sync_error_message(state: &mut AppState) {
+    state.error_message = state
+        .source_errors
+        .first()
+        .map(|error| error.message.clone());
+}
+
+/// Before the first refresh has finished, wait a random moment before every
+/// account or feed after the first, so the first requests of clients started
+/// together don't line up.
+async fn stagger_initial_fetch(core: &TodoTrayCore, index: usize) {
+    if index == 0 || core.refresh_covered.load(Ordering::SeqCst) > 0 {
+        return;
+    }
+    let delay = core
+        .fetch_stagger
+        .lock()
+        .unwrap()
+        .stagger(INITIAL_FETCH_STAGGER);
+    tokio::time::sleep(delay).await;
+}
+
+/// Accounts are fetched one after another, so their requests never burst
+/// together, and staggered on the first refresh.
+async fn fetch_github_notifications(core: &TodoTrayCore) -> Vec<GithubAccountResult> {
+    let mut results = Vec::new();
+    if core.is_source_disabled("github") {
+        return results;
+    }
+    for (index, client) in core.clients().github.iter().enumerate() {
+        stagger_initial_fetch(core, index).await;
+        results.push(GithubAccountResult {
+            account_name: client.account_name().to_string(),
+            result: client.get_notifications().await.map(|mut section| {
+                core.hide_snoozed_github(&mut section);
+                section
+            }),
+        });
+    }
+    results
+}
+
+async fn fetch_calendar_events(core: &TodoTrayCore) -> anyhow::Result<Vec<CalendarEventSection>> {
+    let mut sections = Vec::new();
+    if core.is_source_disabled("calendar") {
+        return Ok(sections);
+    }
+    let now = Utc::now();
+    for (index, client) in core.clients().calendar.iter().enumerate() {
+        stagger_initial_fetch(core, index).await;
+        let mut section = client.get_today_events().await?;
+        section.count_remaining(now);
+        if !section.events.is_empty() {
+            sections.push(section);
+        }
+    }
+    Ok(sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calendar::CalendarEvent;
+    use crate::github::{GithubNotification, GithubNotificationKind};
+    use crate::http::MockTransport;
+    use crate::task::TodoistTask;
+
+    #[derive(Default)]
+    struct RecordingHandler {
+        states: std::sync::Mutex<Vec<AppState>>,
+        /// Task names with their undo tokens
+        completed: std::sync::Mutex<Vec<(String, Option<String>)>>,
+        errors: std::sync::Mutex<Vec<(String, ErrorSeverity)>>,
+    }
+
+    impl EventHandler for RecordingHandler {
+        fn on_state_changed(&self, state: AppState) {
+            self.states.lock().unwrap().push(state);
+        }
+
+        fn on_task_completed(&self, task_name: String, undo_token: Option<String>) {
+            self.completed.lock().unwrap().push((task_name, undo_token));
+        }
+
+        fn on_error(&self, error: String, severity: ErrorSeverity) {
+            self.errors.lock().unwrap().push((error, severity));
+        }
+
+        fn on_github_alert(&self, _notifications: Vec<GithubNotification>) {}
+
+        fn on_all_clear(&self) {}
+
+        fn on_overdue_alert(&self, _alert: OverdueAlert) {}
+    }
+
+    fn test_core(extra_config: &str) -> (Arc<TodoTrayCore>, Arc<RecordingHandler>) {
+        let handler = Arc::new(RecordingHandler::default());
+        let core = TodoTrayCore::from_config(test_config(extra_config), handler.clone())
+            .expect("core builds");
+        (core, handler)
+    }
+
+    fn test_config(extra_config: &str) -> Config {
+        toml::from_str(&format!("todoist_api_token = \"test\"\n{}", extra_config))
+            .expect("valid test config")
+    }
+
+    #[test]
+    fn build_clients_creates_one_client_per_configured_source() {
+        let config = test_config(
+            r#"
+            linear_api_token = "lin"
+            snooze_durations = ["15m", "tomorrow-9am"]
+
+            [[github_accounts]]
+            name = "work"
+            token = "a"
+
+            [[github_accounts]]
+            name = " personal "
+            token = "b"
+            display_name = "Side projects"
+
+            [[calendar_feeds]]
+            name = "Team"
+            url = "https://example.com/team.ics"
+            "#,
+        );
+
+        let clients = build_clients(&config).unwrap();
+
+        assert!(clients.linear.is_some());
+        let accounts: Vec<_> = clients.github.iter().map(|c| c.account_name()).collect();
+        assert_eq!(accounts, vec!["work", "personal"]);
+        assert_eq!(clients.calendar.len(), 1);
+        let labels: Vec<_> = clients
+            .snooze_options
+            .iter()
+            .map(|o| o.label.as_str())
+            .collect();
+        assert_eq!(labels, vec!["15m", "tomorrow-9am"]);
+    }
+
+    #[test]
+    fn build_clients_skips_blank_linear_token_and_rejects_bad_snooze() {
+        let clients = build_clients(&test_config("linear_api_token = \"  \"")).unwrap();
+        assert!(clients.linear.is_none());
+        assert!(clients.github.is_empty());
+
+        let result = build_clients(&test_config("snooze_durations = [\"soon\"]"));
+        assert!(matches!(result, Err(TodoTrayError::Config { .. })));
+    }
+
+    #[test]
+    fn build_clients_names_the_feed_with_bad_certificates() {
+        let feed = |extra: &str| {
+            test_config(&format!(
+                "[[calendar_feeds]]\nname = \"Corp\"\nical_url = \"https://cal.corp/feed.ics\"\n{}",
+                extra
+            ))
+        };
+
+        let missing_ca = build_clients(&feed("ca_cert_path = \"/nonexistent/ca.pem\""));
+        assert!(matches!(
+            missing_ca,
+            Err(TodoTrayError::Config { message })
+                if message.starts_with("Calendar feed 'Corp': Failed to read CA certificate")
+        ));
+
+        let half_identity = build_clients(&feed("client_cert_path = \"client.pem\""));
+        assert!(matches!(
+            half_identity,
+            Err(TodoTrayError::Config { message }) if message.contains("must be set together")
+        ));
+    }
+
+    #[tokio::test]
+    async fn applying_a_new_config_swaps_clients_and_emits_state() {
+        let (core, handler) = test_core("snooze_durations = [\"15m\"]");
+
+        core.apply_config(test_config(
+            "snooze_durations = [\"1h\"]\n[[github_accounts]]\nname = \"work\"\ntoken = \"a\"",
+        ))
+        .await
+        .unwrap();
+
+        assert_eq!(core.clients().github.len(), 1);
+        let states = handler.states.lock().unwrap();
+        assert_eq!(states.last().unwrap().snooze_durations, vec!["1h"]);
+    }
+
+    #[tokio::test]
+    async fn invalid_config_keeps_the_previous_clients() {
+        let (core, handler) = test_core("snooze_durations = [\"15m\"]");
+
+        let result = core
+            .apply_config(test_config("snooze_durations = [\"soon\"]"))
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(core.clients().snooze_options[0].label, "15m");
+        assert!(handler.states.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn complete_async_runs_to_completion_without_blocking() {
+        let (core, handler) = test_core("");
+
+        let result = core.complete_async("missing".to_string()).await;
+
+        assert!(matches!(result, Err(TodoTrayError::NotFound { .. })));
+        assert!(handler.completed.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn snooze_task_async_rejects_unknown_duration() {
+        let (core, _handler) = test_core("snooze_durations = [\"15m\"]");
+
+        let result = core
+            .snooze_task_async("task".to_string(), "2h".to_string())
+            .await;
+
+        assert!(matches!(result, Err(TodoTrayError::Unexpected { .. })));
+    }
+
+    fn github_section(account_name: &str, thread_ids: &[&str]) -> GithubNotificationSection {
+        GithubNotificationSection {
+            account_name: account_name.to_string(),
+            display_name: None,
+            notifications: thread_ids
+                .iter()
+                .map(|id| GithubNotification {
+                    thread_id: id.to_string(),
+                    title: format!("Thread {}", id),
+                    repository: "octo-org/octo-repo".to_string(),
+                    reason: "Subscribed".to_string(),
+                    kind: GithubNotificationKind::PullRequest,
+                    is_actionable: false,
+                    web_url: format!("https://github.com/notifications?query=thread%3A{}", id),
+                    updated_at: None,
+                    display_time: "recent".to_string(),
+                })
+                .collect(),
+            has_more: false,
+        }
+    }
+
+    /// A section where the listed ids are review requests and the rest subscriptions
+    fn github_section_with_actionable(
+        thread_ids: &[&str],
+        actionable: &[&str],
+    ) -> GithubNotificationSection {
+        let mut section = github_section("work", thread_ids);
+        for notification in &mut section.notifications {
+            notification.is_actionable = actionable.contains(&notification.thread_id.as_str());
+        }
+        section
+    }
+
+    fn alert_ids(alerts: &[GithubNotification]) -> Vec<&str> {
+        alerts.iter().map(|n| n.thread_id.as_str()).collect()
+    }
+
+    fn seeded_jitter() -> Jitter {
+        Jitter::new(fastrand::Rng::with_seed(7))
+    }
+
+    #[test]
+    fn refresh_delays_stay_within_the_jitter_range() {
+        let period = Duration::from_secs(300);
+        let mut jitter = seeded_jitter();
+
+        let delays: Vec<_> = (0..1000).map(|_| jitter.delay(period)).collect();
+
+        assert!(delays
+            .iter()
+            .all(|delay| (Duration::from_secs(270)..=Duration::from_secs(330)).contains(delay)));
+        assert!(delays.iter().any(|delay| *delay < Duration::from_secs(285)));
+        assert!(delays.iter().any(|delay| *delay > Duration::from_secs(315)));
+        let mut again = seeded_jitter();
+        assert_eq!(again.delay(period), delays[0]);
+    }
+
+    #[test]
+    fn initial_fetch_staggers_stay_within_their_limit() {
+        let mut jitter = seeded_jitter();
+
+        let staggers: Vec<_> = (0..1000)
+            .map(|_| jitter.stagger(INITIAL_FETCH_STAGGER))
+            .collect();
+
+        assert!(staggers.iter().all(|delay| *delay <= INITIAL_FETCH_STAGGER));
+        assert!(staggers
+            .iter()
+            .any(|delay| *delay < Duration::from_millis(500)));
+        assert!(staggers
+            .iter()
+            .any(|delay| *delay > Duration::from_millis(1500)));
+        assert_eq!(seeded_jitter().stagger(INITIAL_FETCH_STAGGER), staggers[0]);
+    }
+
+    #[tokio::test]
+    async fn notify_resumed_triggers_a_debounced_refresh() {
+        let (core, _handler) = test_core("");
+        let refreshes = Arc::new(AtomicU64::new(0));
+        let loop_task = tokio::spawn({
+            let core = core.clone();
+            let refreshes = refreshes.clone();
+            async move {
+                run_refresh_loop(&core, Duration::from_secs(3600), seeded_jitter(), || {
+                    refreshes.fetch_add(1, Ordering::SeqCst);
+                    async {}
+                })
+                .await
+            }
+        });
+
+        core.notify_resumed();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while refreshes.load(Ordering::SeqCst) == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("resume triggers a refresh");
+
+        core.notify_resumed();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+
+        loop_task.abort();
+    }
+
+    #[tokio::test]
+    async fn shutdown_stops_the_refresh_loop() {
+        let (core, _handler) = test_core("");
+        let refreshes = Arc::new(AtomicU64::new(0));
+        let loop_task = tokio::spawn({
+            let core = core.clone();
+            let refreshes = refreshes.clone();
+            async move {
+                run_refresh_loop(&core, Duration::from_millis(5), seeded_jitter(), || {
+                    refreshes.fetch_add(1, Ordering::SeqCst);
+                    async {}
+                })
+                .await
+            }
+        });
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while refreshes.load(Ordering::SeqCst) < 2 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("loop refreshes on its interval");
+
+        // `shutdown` blocks on the shared runtime, so call it off this one.
+        let shutting_down = core.clone();
+        std::thread::spawn(move || shutting_down.shutdown())
+            .join()
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(5), loop_task)
+            .await
+            .expect("loop exits after shutdown")
+            .unwrap();
+
+        let after_shutdown = refreshes.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(refreshes.load(Ordering::SeqCst), after_shutdown);
+    }
+
+    #[test]
+    fn successful_refresh_sets_last_updated() {
+        let (core, handler, http) = core_with_mock_todoist("", Duration::ZERO);
+        http.respond(200, r#"{"results": [], "next_cursor": null}"#);
+        assert!(core.state.blocking_lock().last_updated.is_none());
+
+        TOKIO_RUNTIME.block_on(refresh_tasks(&core)).unwrap();
+
+        let states = handler.states.lock().unwrap();
+        let last_updated = states.last().unwrap().last_updated.as_deref();
+        assert!(last_updated.is_some_and(|at| DateTime::parse_from_rfc3339(at).is_ok()));
+    }
+
+    #[test]
+    fn refresh_reaching_no_source_keeps_last_updated_unset() {
+        let (core, handler, http) = core_with_mock_todoist("", Duration::ZERO);
+        http.respond(503, "Service Unavailable");
+
+        TOKIO_RUNTIME.block_on(refresh_tasks(&core)).unwrap();
+
+        let states = handler.states.lock().unwrap();
+        assert!(states.last().unwrap().last_updated.is_none());
+    }
+
+    #[test]
+    fn failed_first_load_reports_a_blocking_error_once() {
+        // With no other sources configured, an unreachable Todoist means the
+        // first refresh reaches nothing at all.
+        let (core, handler, http) = core_with_mock_todoist("", Duration::ZERO);
+        http.respond(503, "Service Unavailable");
+        http.respond(503, "Service Unavailable");
+
+        TOKIO_RUNTIME.block_on(refresh_tasks(&core)).unwrap();
+        TOKIO_RUNTIME.block_on(refresh_tasks(&core)).unwrap();
+
+        let errors = handler.errors.lock().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].1, ErrorSeverity::Blocking);
+    }
+
+    #[test]
+    fn rejected_token_still_runs_the_first_refresh_and_records_the_error() {
+        let (core, handler, http) = core_with_mock_todoist("", Duration::ZERO);
+        http.respond(401, "Unauthorized");
+        http.respond(401, "Unauthorized");
+
+        TOKIO_RUNTIME.block_on(initial_refresh(&core));
+
+        let paths: Vec<_> = http
+            .take_requests()
+            .iter()
+            .map(|r| r.url().path().to_string())
+            .collect();
+        assert_eq!(paths, vec!["/api/v1/user", "/api/v1/tasks/filter"]);
+        let state = core.get_state();
+        assert!(!state.is_loading);
+        assert_eq!(state.source_errors.len(), 1);
+        assert_eq!(state.source_errors[0].source, "todoist");
+        assert_eq!(handler.errors.lock().unwrap()[0].1, ErrorSeverity::Blocking);
+    }
+
+    #[test]
+    fn background_failures_are_transient_and_reported_once() {
+        let todoist = SourceError::new("todoist", &anyhow::anyhow!("todoist down"));
+        let github = SourceError::for_account("github", "work", &anyhow::anyhow!("github down"));
+
+        assert_eq!(
+            refresh_error_report(&[], std::slice::from_ref(&todoist), false),
+            Some(("todoist down".to_string(), ErrorSeverity::Transient))
+        );
+        assert_eq!(
+            refresh_error_report(
+                std::slice::from_ref(&todoist),
+                &[todoist.clone(), github.clone()],
+                false
+            ),
+            Some(("github down".to_string(), ErrorSeverity::Transient))
+        );
+        assert_eq!(
+            refresh_error_report(
+                std::slice::from_ref(&github),
+                std::slice::from_ref(&github),
+                false
+            ),
+            None
+        );
+        assert_eq!(refresh_error_report(&[], &[], false), None);
+    }
+
+    #[test]
+    fn all_clear_fires_when_overdue_drops_to_zero() {
+        let mut tracker = OverdueTracker::default();
+        assert!(!tracker.record(0), "initial load");
+        assert!(!tracker.record(0));
+        assert!(!tracker.record(3));
+        assert!(!tracker.record(1));
+        assert!(tracker.record(0));
+        assert!(!tracker.record(0));
+
+        let mut tracker = OverdueTracker::default();
+        tracker.record(2);
+        assert!(tracker.record(0));
+    }
+
+    #[test]
+    fn first_github_refresh_only_seeds_alerts() {
+        let mut tracker = GithubAlertTracker::default();
+        let work = ["work".to_string()];
+
+        let alerts = tracker.new_alerts(
+            &[github_section_with_actionable(&["1", "2"], &["1"])],
+            &work,
+        );
+
+        assert!(alerts.is_empty());
+    }
+
+    #[test]
+    fn failed_github_fetches_leave_the_first_success_to_seed_alerts() {
+        let mut tracker = GithubAlertTracker::default();
+        let current = [github_section_with_actionable(&["1", "2"], &["1", "2"])];
+
+        tracker.new_alerts(&[], &[]);
+        tracker.new_alerts(&current, &[]);
+
+        assert!(tracker
+            .new_alerts(&current, &["work".to_string()])
+            .is_empty());
+    }
+
+    #[test]
+    fn alerts_fire_once_for_new_actionable_threads() {
+        let mut tracker = GithubAlertTracker::default();
+        let work = ["work".to_string()];
+        tracker.new_alerts(&[github_section_with_actionable(&["1"], &["1"])], &work);
+
+        let current = [github_section_with_actionable(
+            &["1", "2", "3", "4"],
+            &["1", "2", "4"],
+        )];
+        assert_eq!(
+            alert_ids(&tracker.new_alerts(&current, &work)),
+            vec!["2", "4"]
+        );
+        assert!(tracker.new_alerts(&current, &work).is_empty());
+
+        // Only the current threads are kept; one that comes back alerts again.
+        tracker.new_alerts(&[], &work);
+        assert!(tracker.seen["work"].is_empty());
+        assert_eq!(
+            alert_ids(&tracker.new_alerts(&current, &work)),
+            vec!["1", "2", "4"]
+        );
+    }
+
+    fn calendar_section(account_name: &str, titles: &[&str]) -> CalendarEventSection {
+        CalendarEventSection {
+            account_name: account_name.to_string(),
+            events: titles
+                .iter()
+                .map(|title| CalendarEvent {
+                    event_id: title.to_string(),
+                    title: title.to_string(),
+                    start_at: None,
+                    end_at: None,
+                    display_time: "All day".to_string(),
+                    is_all_day: true,
+                    location: None,
+                    my_response: None,
+                    is_free: false,
+                    open_url: None,
+                })
+                .collect(),
+            remaining_count: 0,
+        }
+    }
+
+    fn linear_task(id: &str) -> TodoTask {
+        TodoTask::from_linear(
+            id.to_string(),
+            id.to_uppercase(),
+            "Issue".to_string(),
+            None,
+            "In Progress".to_string(),
+            0,
+            None,
+            Language::English,
+        )
+    }
+
+    /// Todoist task as the client builds it, due on `due` ("2020-01-01" for
+    /// an overdue one)
+    fn todoist_task(id: &str, content: &str, due: Option<&str>) -> TodoTask {
+        todoist_task_from(serde_json::json!({
+            "id": id,
+            "content": content,
+            "due": due.map(|date| serde_json::json!({"date": date})),
+        }))
+    }
+
+    /// Todoist task as the client builds it from the API's `json`
+    fn todoist_task_from(json: serde_json::Value) -> TodoTask {
+        let todoist: TodoistTask = serde_json::from_value(json).unwrap();
+        TodoTask::from_todoist(
+            todoist,
+            TimeFormat::default(),
+            chrono::Duration::minutes(60),
+            false,
+            Language::English,
+        )
+    }
+
+    /// Today's date as Todoist writes due dates
+    fn today() -> String {
+        Local::now().date_naive().to_string()
+    }
+
+    #[test]
+    fn summary_lists_non_zero_counts_in_order() {
+        let summary = |overdue, today, in_progress, github, calendar| {
+            summarize_counts(&AppState {
+                overdue_count: overdue,
+                today_count: today,
+                in_progress_count: in_progress,
+                github_notification_count: github,
+                calendar_event_count: calendar,
+                ..Default::default()
+            })
+        };
+
+        assert_eq!(
+            summary(2, 5, 0, 3, 1),
+            "2 overdue · 5 today · 3 notifications · 1 meeting"
+        );
+        assert_eq!(
+            summary(0, 1, 2, 1, 0),
+            "1 today · 2 in progress · 1 notification"
+        );
+        assert_eq!(summary(0, 0, 0, 0, 4), "4 meetings");
+        assert_eq!(summary(0, 0, 0, 0, 0), "Nothing due");
+
+        let swedish = |overdue, today| {
+            summarize_counts(&AppState {
+                overdue_count: overdue,
+                today_count: today,
+                language: Language::Swedish,
+                ..Default::default()
+            })
+        };
+        assert_eq!(swedish(2, 5), "2 försenade · 5 idag");
+        assert_eq!(swedish(0, 0), "Inget att göra");
+    }
+
+    #[test]
+    fn github_error_keeps_cached_sections_and_other_sources() {
+        let mut state = AppState {
+            github_notifications: vec![github_section("work", &["1"])],
+            calendar_events: vec![calendar_section("Work", &["Standup"])],
+            ..Default::default()
+        };
+
+        apply_refresh_results(
+            &mut state,
+            RefreshResults {
+                todoist: Ok(Vec::new()),
+                linear: Some(Ok(vec![linear_task("abc")])),
+                github: vec![GithubAccountResult {
+                    account_name: "work".to_string(),
+                    result: Err(anyhow::anyhow!("GitHub API error for account 'work' (401)")),
+                }],
+                calendar: Ok(vec![calendar_section("Work", &["Standup", "Review"])]),
+            },
+            &LabelFilter::default(),
+            None,
+            AllDayPosition::default(),
+            false,
+        );
+
+        assert_eq!(state.github_notification_count, 1);
+        assert_eq!(
+            state.github_notifications[0].notifications[0].thread_id,
+            "1"
+        );
+        assert_eq!(state.calendar_event_count, 2);
+        assert_eq!(state.in_progress_count, 1);
+        assert_eq!(state.source_errors.len(), 1);
+        assert_eq!(state.source_errors[0].source, "github");
+        assert_eq!(
+            state.error_message.as_deref(),
+            Some("GitHub API error for account 'work' (401)")
+        );
+    }
+
+    #[test]
+    fn section_order_follows_the_config_with_counts() {
+        let (core, _handler) = test_core(r#"section_order = ["calendar", "tasks"]"#);
+        {
+            let mut state = core.state.blocking_lock();
+            state.tasks.in_progress = vec![linear_task("eng-1")];
+            state.calendar_events = vec![calendar_section("Work", &["Standup", "Review"])];
+            state.calendar_event_count = 2;
+        }
+
+        let order: Vec<_> = core
+            .get_state()
+            .section_order
+            .into_iter()
+            .map(|section| (section.kind, section.count))
+            .collect();
+
+        assert_eq!(
+            order,
+            vec![
+                (MenuSectionKind::Calendar, 2),
+                (MenuSectionKind::Tasks, 1),
+                (MenuSectionKind::Github, 0),
+            ]
+        );
+        assert!(build_clients(&test_config(r#"section_order = ["email"]"#)).is_err());
+    }
+
+    #[test]
+    fn counts_leave_out_excluded_projects() {
+        let (core, _handler) = test_core(r#"exclude_projects = ["Someday"]"#);
+        let in_project = |id: &str, project: &str| {
+            let mut task = todoist_task(id, "Task", Some(&today()));
+            task.project_name = Some(project.to_string());
+            task
+        };
+        let tasks = vec![
+            in_project("a", "Work"),
+            in_project("b", "someday"),
+            linear_task("eng-1"),
+        ];
+
+        let clients = core.clients();
+        let mut state = core.state.blocking_lock();
+        regroup_tasks(&mut state, tasks, &clients);
+
+        assert_eq!(state.today_count, 1);
+        assert_eq!(state.in_progress_count, 1);
+    }
+
+    /// Today's events from a feed of `events`, parsed the way a real feed is.
+    fn parsed_calendar(events: &[String]) -> CalendarEventSection {
+        let http = Arc::new(MockTransport::default());
+        http.respond(
+            200,
+            &format!("BEGIN:VCALENDAR\r\n{}END:VCALENDAR\r\n", events.concat()),
+        );
+        let client = CalendarClient::new(
+            "work".to_string(),
+            "https://example.com/feed.ics".to_string(),
+            TimeFormat::default(),
+            None,
+            false,
+            http,
+        );
+        TOKIO_RUNTIME
+            .block_on(async { client.get_today_events().await })
+            .unwrap()
+    }
+
+    /// Local `hour` o'clock `days` from today
+    fn local_hour(days: i64, hour: u32) -> DateTime<Utc> {
+        (Local::now().date_naive() + chrono::Duration::days(days))
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+            .and_local_timezone(Local)
+            .earliest()
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn timed_vevent(uid: &str, start: DateTime<Utc>, end: DateTime<Utc>, extra: &str) -> String {
+        format!(
+            "BEGIN:VEVENT\r\nUID:{uid}\r\nSUMMARY:{uid}\r\nDTSTART:{}\r\nDTEND:{}\r\n{extra}END:VEVENT\r\n",
+            start.format("%Y%m%dT%H%M%SZ"),
+            end.format("%Y%m%dT%H%M%SZ"),
+        )
+    }
+
+    #[test]
+    fn accessibility_summary_is_a_sentence_naming_the_next_meeting_in_the_language() {
+        let now = local_hour(0, 8);
+        let carried_over = timed_vevent("Offsite", local_hour(-1, 16), local_hour(0, 9), "");
+        let standup = timed_vevent("Standup", local_hour(0, 10), local_hour(0, 11), "");
+        let review = timed_vevent("Review", local_hour(0, 12), local_hour(0, 13), "");
+        let focus = timed_vevent(
+            "Focus",
+            local_hour(0, 14),
+            local_hour(0, 15),
+            "TRANSP:TRANSPARENT\r\n",
+        );
+        let holiday = format!(
+            "BEGIN:VEVENT\r\nUID:Holiday\r\nSUMMARY:Holiday\r\nDTSTART;VALUE=DATE:{}\r\nEND:VEVENT\r\n",
+            Local::now().format("%Y%m%d")
+        );
+        let state = |overdue, today, github, calendar_events: Vec<CalendarEventSection>| AppState {
+            overdue_count: overdue,
+            today_count: today,
+            github_notification_count: github,
+            calendar_event_count: calendar_events.iter().map(|s| s.events.len() as u32).sum(),
+            calendar_events,
+            ..Default::default()
+        };
+        let describe = |state: &AppState| describe_state(state, TimeFormat::default(), now);
+
+        assert_eq!(describe(&state(0, 0, 0, vec![])), "All caught up.");
+        assert_eq!(describe(&state(0, 1, 0, vec![])), "You have 1 task today.");
+        assert_eq!(
+            describe(&state(2, 5, 0, vec![])),
+            "You have 2 overdue tasks and 5 tasks today."
+        );
+        let busy_day = parsed_calendar(&[
+            carried_over.clone(),
+            standup.clone(),
+            review,
+            focus,
+            holiday,
+        ]);
+        assert_eq!(busy_day.events[0].display_time, "…-09:00");
+        assert_eq!(
+            describe(&state(2, 5, 1, vec![busy_day.clone()])),
+            "You have 2 overdue tasks, 5 tasks today, 1 GitHub notification, 3 meetings, the next at 10:00, and 2 events."
+        );
+        assert_eq!(
+            describe_state(
+                &state(0, 0, 0, vec![busy_day.clone()]),
+                TimeFormat::TwelveHour,
+                now
+            ),
+            "You have 3 meetings, the next at 10:00 AM and 2 events."
+        );
+        assert_eq!(
+            describe(&state(0, 0, 0, vec![parsed_calendar(&[standup])])),
+            "You have 1 meeting at 10:00."
+        );
+        assert_eq!(
+            describe(&state(0, 0, 0, vec![parsed_calendar(&[carried_over])])),
+            "You have 1 meeting."
+        );
+        let swedish = AppState {
+            language: Language::Swedish,
+            ..state(2, 5, 0, vec![])
+        };
+        assert_eq!(
+            describe(&swedish),
+            "Du har 2 försenade uppgifter och 5 uppgifter idag."
+        );
+        assert_eq!(
+            summarize_counts(&state(0, 0, 0, vec![busy_day])),
+            "3 meetings · 2 events"
+        );
+    }
+
+    #[test]
+    fn capped_sections_keep_the_true_counts() {
+        let (core, _handler) = test_core("max_items_per_section = 5");
+        let overdue = (1..=20).map(|i| linear_task(&i.to_string())).collect();
+
+        let mut state = core.state.blocking_lock();
+        apply_grouped_tasks_to_state(
+            &mut state,
+            TaskList {
+                overdue,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(state.overdue_count, 20);
+        assert_eq!(state.sections[0].tasks.len(), 5);
+        assert_eq!(state.sections[0].hidden_count, 15);
+    }
+
+    #[test]
+    fn calendar_count_can_leave_out_ended_events() {
+        let mut section = calendar_section("Work", &["Standup", "Review"]);
+        section.remaining_count = 1;
+        let results = || RefreshResults {
+            todoist: Ok(Vec::new()),
+            linear: None,
+            github: Vec::new(),
+            calendar: Ok(vec![section.clone()]),
+        };
+
+        let mut state = AppState::default();
+        apply_refresh_results(
+            &mut state,
+            results(),
+            &LabelFilter::default(),
+            None,
+            AllDayPosition::default(),
+            true,
+        );
+        assert_eq!(state.calendar_event_count, 1);
+        assert_eq!(state.calendar_events[0].events.len(), 2);
+
+        apply_refresh_results(
+            &mut state,
+            results(),
+            &LabelFilter::default(),
+            None,
+            AllDayPosition::default(),
+            false,
+        );
+        assert_eq!(state.calendar_event_count, 2);
+    }
+
+    #[test]
+    fn calendar_survives_github_failure_when_calendar_also_fails() {
+        let mut state = AppState {
+            calendar_events: vec![calendar_section("Work", &["Standup"])],
+            ..Default::default()
+        };
+
+        apply_refresh_results(
+            &mut state,
+            RefreshResults {
+                todoist: Ok(Vec::new()),
+                linear: None,
+                github: vec![GithubAccountResult {
+                    account_name: "work".to_string(),
+                    result: Err(anyhow::anyhow!("github down")),
+                }],
+                calendar: Err(anyhow::anyhow!("calendar down")),
+            },
+            &LabelFilter::default(),
+            None,
+            AllDayPosition::default(),
+            false,
+        );
+
+        assert_eq!(state.calendar_event_count, 1);
+        assert_eq!(state.calendar_events[0].events[0].title, "Standup");
+        let sources = state
+            .source_errors
+            .iter()
+            .map(|error| error.source.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(sources, vec!["github", "calendar"]);
+        assert_eq!(state.error_message.as_deref(), Some("github down"));
+    }
+
+    #[test]
+    fn successful_refresh_clears_previous_errors() {
+        let mut state = AppState {
+            source_errors: vec![SourceError {
+                source: "todoist".to_string(),
+                account_name: None,
+                message: "offline".to_string(),
+            }],
+            error_message: Some("offline".to_string()),
+            ..Default::default()
+        };
+
+        apply_refresh_results(
+            &mut state,
+            RefreshResults {
+                todoist: Ok(Vec::new()),
+                linear: None,
+                github: Vec::new(),
+                calendar: Ok(Vec::new()),
+            },
+            &LabelFilter::default(),
+            None,
+            AllDayPosition::default(),
+            false,
+        );
+
+        assert!(state.source_errors.is_empty());
+        assert_eq!(state.error_message, None);
+    }
+
+    #[test]
+    fn failing_github_account_does_not_hide_other_accounts() {
+        let cached = vec![github_section("work", &["1", "2"])];
+        let mut source_errors = Vec::new();
+
+        let sections = merge_github_results(
+            &cached,
+            vec![
+                GithubAccountResult {
+                    account_name: "work".to_string(),
+                    result: Err(anyhow::anyhow!("bad token")),
+                },
+                GithubAccountResult {
+                    account_name: "personal".to_string(),
+                    result: Ok(github_section("personal", &["3"])),
+                },
+            ],
+            &mut source_errors,
+        );
+
+        let accounts = sections
+            .iter()
+            .map(|section| section.account_name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(accounts, vec!["work", "personal"]);
+        assert_eq!(sections[0].notifications.len(), 2);
+        assert_eq!(sections[1].notifications[0].thread_id, "3");
+        assert_eq!(source_errors.len(), 1);
+        assert_eq!(source_errors[0].account_name.as_deref(), Some("work"));
+    }
+
+    #[test]
+    fn clearing_one_github_account_keeps_other_account_errors() {
+        let error = anyhow::anyhow!("bad token");
+        let mut state = AppState {
+            source_errors: vec![
+                SourceError::for_account("github", "work", &error),
+                SourceError::for_account("github", "personal", &error),
+            ],
+            ..Default::default()
+        };
+
+        clear_source_error(&mut state, "github", Some("work"));
+
+        assert_eq!(state.source_errors.len(), 1);
+        assert_eq!(
+            state.source_errors[0].account_name.as_deref(),
+            Some("personal")
+        );
+        assert_eq!(state.error_message.as_deref(), Some("bad token"));
+    }
+
+    #[test]
+    fn task_backend_is_derived_from_source() {
+        assert_eq!(
+            TaskBackend::for_source("todoist"),
+            Some(TaskBackend::Todoist)
+        );
+        assert_eq!(TaskBackend::for_source("linear"), Some(TaskBackend::Linear));
+        assert_eq!(TaskBackend::for_source("github"), None);
+    }
+
+    #[tokio::test]
+    async fn completing_linear_task_routes_to_linear_client() {
+        let (core, handler) = test_core("");
+        core.state.lock().await.tasks.in_progress = vec![linear_task("abc")];
+
+        let result = core.complete_async("abc".to_string()).await;
+
+        // No Linear token is configured, so dispatch fails before any Todoist call.
+        assert!(matches!(result, Err(TodoTrayError::Config { .. })));
+        assert!(handler.completed.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn completing_a_task_pushes_it_to_recent_completions() {
+        let mut log = CompletionLog::default();
+        let mut state = AppState::default();
+
+        log.record(linear_task("first"), Utc::now());
+        log.record(linear_task("second"), Utc::now());
+        log.apply_to(&mut state);
+
+        assert_eq!(state.completed_today_count, 2);
+        let ids = state
+            .recently_completed
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["second", "first"]);
+    }
+
+    #[test]
+    fn completions_reset_at_local_midnight() {
+        let mut log = CompletionLog::default();
+        let yesterday_evening = Local::now()
+            .date_naive()
+            .pred_opt()
+            .unwrap()
+            .and_hms_opt(23, 30, 0)
+            .unwrap()
+            .and_local_timezone(Local)
+            .earliest()
+            .unwrap()
+            .with_timezone(&Utc);
+        log.record(linear_task("old"), yesterday_evening);
+        log.record(linear_task("new"), Utc::now());
+
+        log.prune(Local::now().date_naive());
+        let mut state = AppState::default();
+        log.apply_to(&mut state);
+
+        assert_eq!(state.completed_today_count, 1);
+        assert_eq!(state.recently_completed[0].id, "new");
+    }
+
+    #[test]
+    fn rejected_tokens_map_to_config_errors() {
+        for credential in [
+            "Todoist token",
+            "GitHub token for account 'work'",
+            "Linear API key",
+        ] {
+            let error = api_error(
+                InvalidTokenError {
+                    credential: credential.to_string(),
+                }
+                .into(),
+            );
+            assert!(matches!(error, TodoTrayError::Config { .. }));
+            assert_eq!(
+                error.to_string(),
+                format!("Configuration error: {} is invalid or expired", credential)
+            );
+        }
+
+        let error = api_error(anyhow::anyhow!("connection reset"));
+        assert!(matches!(error, TodoTrayError::Network { .. }));
+    }
+
+    #[test]
+    fn stale_flag_turns_on_at_the_failure_threshold_and_clears_on_recovery() {
+        let mut tracker = FailureTracker::default();
+
+        assert!(!tracker.record(true, 3));
+        assert!(!tracker.record(true, 3));
+        assert!(tracker.record(true, 3));
+        assert!(tracker.record(true, 3));
+        assert!(!tracker.record(false, 3));
+        assert!(!tracker.record(true, 3));
+    }
+
+    #[test]
+    fn zero_threshold_never_marks_stale() {
+        let mut tracker = FailureTracker::default();
+
+        for _ in 0..5 {
+            assert!(!tracker.record(true, 0));
+        }
+    }
+
+    #[test]
+    fn refresh_counts_as_failed_only_when_every_configured_source_failed() {
+        let results = |todoist_ok: bool, github_ok: bool| RefreshResults {
+            todoist: if todoist_ok {
+                Ok(Vec::new())
+            } else {
+                Err(anyhow::anyhow!("offline"))
+            },
+            linear: None,
+            github: vec![GithubAccountResult {
+                account_name: "work".to_string(),
+                result: if github_ok {
+                    Ok(github_section("work", &[]))
+                } else {
+                    Err(anyhow::anyhow!("offline"))
+                },
+            }],
+            calendar: Ok(Vec::new()),
+        };
+
+        assert!(results(false, false).all_failed(false));
+        assert!(!results(false, false).all_failed(true));
+        assert!(!results(true, false).all_failed(false));
+        assert!(!results(false, true).all_failed(false));
+    }
+
+    #[test]
+    fn focus_mode_hides_everything_but_overdue_tasks_until_turned_off() {
+        let (core, handler) = test_core("");
+        {
+            let mut state = core.state.blocking_lock();
+            state.tasks.overdue = vec![linear_task("late")];
+            state.tasks.today = vec![linear_task("now")];
+            state.overdue_count = 1;
+            state.today_count = 1;
+            state.github_notifications = vec![github_section("work", &["1", "2"])];
+            state.github_notification_count = 2;
+        }
+        let last_state = || handler.states.lock().unwrap().last().cloned().unwrap();
+
+        core.set_focus_mode(true);
+        let focused = last_state();
+        assert!(focused.focus_mode);
+        assert_eq!(focused.overdue_count, 1);
+        assert_eq!(focused.tasks.overdue[0].id, "late");
+        assert_eq!(focused.today_count, 0);
+        assert!(focused.tasks.today.is_empty());
+        assert_eq!(focused.github_notification_count, 0);
+        assert!(focused.github_notifications.is_empty());
+        assert_eq!(focused.summary, "1 overdue");
+        assert_eq!(core.get_state().today_count, 0);
+
+        core.set_focus_mode(false);
+        let restored = last_state();
+        assert_eq!(restored.today_count, 1);
+        assert_eq!(restored.tasks.today[0].id, "now");
+        assert_eq!(restored.github_notification_count, 2);
+    }
+
+    #[test]
+    fn title_is_rendered_from_a_valid_title_format() {
+        let (core, _handler) = test_core(r#"title_format = "! {overdue} · {linear} · {prs}""#);
+        {
+            let mut state = core.state.blocking_lock();
+            state.overdue_count = 2;
+            state.in_progress_count = 1;
+            state.github_notification_count = 4;
+        }
+        assert_eq!(core.get_state().title.as_deref(), Some("! 2 · 1 · 4"));
+
+        let (core, _handler) = test_core(r#"title_format = "{nope}""#);
+        assert_eq!(core.get_state().title, None);
+    }
+
+    /// A core whose Todoist requests go to a mock and whose undo window is `undo_window`.
+    fn core_with_mock_todoist(
+        extra_config: &str,
+        undo_window: Duration,
+    ) -> (Arc<TodoTrayCore>, Arc<RecordingHandler>, Arc<MockTransport>) {
+        let (core, handler) = test_core(extra_config);
+        let http = Arc::new(MockTransport::default());
+        let mut clients = build_clients(&test_config(extra_config)).unwrap();
+        clients.todoist = Arc::new(TodoistClient::new(
+            "test".to_string(),
+            TimeFormat::default(),
+            chrono::Duration::minutes(60),
+            None,
+            None,
+            false,
+            http.clone(),
+        ));
+        clients.undo_window = undo_window;
+        *core.clients.write().unwrap() = Arc::new(clients);
+        let task = todoist_task("1", "Write report", Some("2020-01-01"));
+        core.state.blocking_lock().tasks.overdue = vec![task];
+        (core, handler, http)
+    }
+
+    fn undo_token(handler: &RecordingHandler) -> String {
+        let completed = handler.completed.lock().unwrap();
+        let (name, token) = completed.last().cloned().unwrap();
+        assert_eq!(name, "Write report");
+        token.expect("completion can be undone")
+    }
+
+    #[test]
+    fn undoing_a_completion_cancels_the_close() {
+        let (core, handler, http) = core_with_mock_todoist("", Duration::from_millis(100));
+
+        core.clone().complete("1".to_string()).unwrap();
+        assert!(core.get_state().tasks.overdue.is_empty());
+
+        core.undo_complete(undo_token(&handler)).unwrap();
+        assert_eq!(core.get_state().tasks.overdue[0].id, "1");
+
+        TOKIO_RUNTIME.block_on(wait_for_undo_timers(&core));
+        assert!(http.take_requests().is_empty());
+        assert_eq!(core.get_state().completed_today_count, 0);
+        assert!(matches!(
+            core.undo_complete("1".to_string()),
+            Err(TodoTrayError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn shutdown_closes_completions_still_in_their_undo_window() {
+        let (core, handler, http) = core_with_mock_todoist("", Duration::from_secs(3600));
+        http.respond(204, "");
+        http.respond(200, r#"{"results": [], "next_cursor": null}"#);
+
+        core.clone().complete("1".to_string()).unwrap();
+        let token = undo_token(&handler);
+        core.shutdown();
+
+        assert_eq!(
+            http.take_requests()[0].url().path(),
+            "/api/v1/tasks/1/close"
+        );
+        assert_eq!(core.get_state().completed_today_count, 1);
+        assert!(core.undo_complete(token).is_err());
+    }
+
+    #[test]
+    fn completion_is_closed_once_the_undo_window_passes() {
+        let (core, handler, http) = core_with_mock_todoist("", Duration::from_millis(20));
+        http.respond(204, "");
+        http.respond(200, r#"{"results": [], "next_cursor": null}"#);
+
+        core.clone().complete("1".to_string()).unwrap();
+        let token = undo_token(&handler);
+        TOKIO_RUNTIME.block_on(wait_for_undo_timers(&core));
+
+        let requests = http.take_requests();
+        assert_eq!(requests[0].url().path(), "/api/v1/tasks/1/close");
+        assert_eq!(core.get_state().completed_today_count, 1);
+        assert!(core.get_state().tasks.overdue.is_empty());
+        assert!(core.undo_complete(token).is_err());
+        assert!(handler.errors.lock().unwrap().is_empty());
+    }
+
+    /// Collects the values recorded on `refresh` spans
+    #[derive(Clone, Default)]
+    struct RefreshSpanFields(Arc<std::sync::Mutex<HashMap<String, String>>>);
+
+    impl tracing::field::Visit for RefreshSpanFields {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+
+    impl<S> tracing_subscriber::Layer<S> for RefreshSpanFields
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_record(
+            &self,
+            id: &tracing::span::Id,
+            values: &tracing::span::Record<'_>,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            if ctx
+                .metadata(id)
+                .is_some_and(|meta| meta.name() == "refresh")
+            {
+                values.record(&mut self.clone());
+            }
+        }
+    }
+
+    #[test]
+    fn refresh_span_records_the_task_count() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let (core, _handler, http) = core_with_mock_todoist("", Duration::ZERO);
+        http.respond(
+            200,
+            r#"{"results": [{"id": "1", "content": "First", "due": null},
+                            {"id": "2", "content": "Second", "due": null}],
+                "next_cursor": null}"#,
+        );
+        http.respond(200, r#"{"results": [], "next_cursor": null}"#);
+        let fields = RefreshSpanFields::default();
+        let subscriber = tracing_subscriber::registry().with(fields.clone());
+
+        tracing::subscriber::with_default(subscriber, || core.refresh()).unwrap();
+
+        let fields = fields.0.lock().unwrap();
+        assert_eq!(fields.get("task_count").map(String::as_str), Some("2"));
+        assert_eq!(fields.get("all_failed").map(String::as_str), Some("false"));
+    }
+
+    #[test]
+    fn only_recently_completed_todoist_tasks_can_be_reopened() {
+        let (core, _handler, http) = core_with_mock_todoist("", Duration::ZERO);
+        core.completions
+            .lock()
+            .unwrap()
+            .record(linear_task("lin"), Utc::now());
+
+        assert!(matches!(
+            core.reopen_task("1".to_string()),
+            Err(TodoTrayError::NotFound { .. })
+        ));
+        assert!(matches!(
+            core.reopen_task("lin".to_string()),
+            Err(TodoTrayError::Unexpected { .. })
+        ));
+        assert!(http.take_requests().is_empty());
+    }
+
+    #[test]
+    fn reopening_removes_the_task_from_recent_completions() {
+        let (core, _handler, http) = core_with_mock_todoist("", Duration::ZERO);
+        let task = todoist_task("7", "Task", None);
+        core.completions.lock().unwrap().record(task, Utc::now());
+        http.respond(204, "");
+        http.respond(200, r#"{"results": [], "next_cursor": null}"#);
+
+        core.reopen_task("7".to_string()).unwrap();
+
+        assert_eq!(
+            http.take_requests()[0].url().path(),
+            "/api/v1/tasks/7/reopen"
+        );
+        let state = core.get_state();
+        assert_eq!(state.completed_today_count, 0);
+        assert!(state.recently_completed.is_empty());
+    }
+
+    #[test]
+    fn read_only_mode_blocks_completion_without_calling_the_api() {
+        let (core, handler, http) = core_with_mock_todoist("read_only = true", Duration::ZERO);
+
+        let result = core.clone().complete("1".to_string());
+
+        assert!(
+            matches!(result, Err(TodoTrayError::Unexpected { message }) if message.contains("read-only"))
+        );
+        assert!(http.take_requests().is_empty());
+        assert!(handler.completed.lock().unwrap().is_empty());
+        assert!(matches!(
+            core.snooze_task("1".to_string(), "30m".to_string()),
+            Err(TodoTrayError::Unexpected { .. })
+        ));
+        assert!(http.take_requests().is_empty());
+    }
+
+    #[test]
+    fn read_only_mode_marks_every_task_as_not_completable() {
+        let (core, _handler) = test_core("read_only = true");
+        {
+            let mut state = core.state.blocking_lock();
+            state.tasks.overdue = vec![linear_task("late")];
+            state.tasks.in_progress = vec![linear_task("wip")];
+        }
+
+        let state = core.get_state();
+
+        assert!(state.read_only);
+        assert!(!state.tasks.overdue[0].can_complete);
+        assert!(!state.tasks.in_progress[0].can_complete);
+    }
+
+    fn overdue_titles(state: &AppState) -> Vec<String> {
+        state
+            .tasks
+            .overdue
+            .iter()
+            .map(|task| task.content.clone())
+            .collect()
+    }
+
+    /// Cache the seeded overdue tasks as if they were just fetched.
+    fn seed_fetch_cache(core: &TodoTrayCore) {
+        let tasks = core.state.blocking_lock().tasks.overdue.clone();
+        let mut cache = core.fetch_cache.lock().unwrap();
+        cache.todoist = Some(Cached::new(tasks));
+        cache.calendar = Some(Cached::new(Vec::new()));
+    }
+
+    #[test]
+    fn manual_refresh_serves_cached_results_then_fresh_state() {
+        let (core, handler, http) = core_with_mock_todoist("", Duration::ZERO);
+        seed_fetch_cache(&core);
+        core.state.blocking_lock().tasks.overdue.clear();
+        http.respond(200, r#"{"results": [], "next_cursor": null}"#);
+
+        core.refresh().unwrap();
+
+        let states = handler.states.lock().unwrap();
+        assert_eq!(states.len(), 2);
+        assert_eq!(overdue_titles(&states[0]), vec!["Write report"]);
+        assert!(overdue_titles(&states[1]).is_empty());
+    }
+
+    #[test]
+    fn results_past_max_stale_or_never_fetched_are_not_served() {
+        for (extra_config, fetched) in [("max_stale_seconds = 0", true), ("", false)] {
+            let (core, handler, http) = core_with_mock_todoist(extra_config, Duration::ZERO);
+            if fetched {
+                seed_fetch_cache(&core);
+            }
+            http.respond(200, r#"{"results": [], "next_cursor": null}"#);
+
+            core.refresh().unwrap();
+
+            let states = handler.states.lock().unwrap();
+            assert_eq!(states.len(), 1, "{}", extra_config);
+            assert!(overdue_titles(&states[0]).is_empty());
+        }
+    }
+
+    #[test]
+    fn failed_fetch_drops_the_cached_results_of_its_source() {
+        let (core, _handler, http) = core_with_mock_todoist("", Duration::ZERO);
+        seed_fetch_cache(&core);
+        http.respond(503, "");
+
+        let _ = TOKIO_RUNTIME.block_on(refresh_tasks(&core));
+
+        let cache = core.fetch_cache.lock().unwrap();
+        assert!(cache.todoist.is_none());
+        assert!(cache.calendar.is_some());
+    }
+
+    #[test]
+    fn deleting_a_todoist_task_sends_a_delete_and_refreshes() {
+        let (core, _handler, http) = core_with_mock_todoist("", Duration::ZERO);
+        http.respond(204, "");
+        http.respond(200, r#"{"results": [], "next_cursor": null}"#);
+
+        core.delete_task("1".to_string()).unwrap();
+
+        let requests = http.take_requests();
+        assert_eq!(requests[0].method(), "DELETE");
+        assert_eq!(requests[0].url().path(), "/api/v1/tasks/1");
+        assert_eq!(requests[1].url().path(), "/api/v1/tasks/filter");
+        assert!(core.get_state().tasks.overdue.is_empty());
+        assert_eq!(core.get_state().completed_today_count, 0);
+    }
+
+    #[test]
+    fn only_todoist_tasks_can_be_deleted() {
+        let (core, _handler, http) = core_with_mock_todoist("", Duration::ZERO);
+        core.state.blocking_lock().tasks.in_progress = vec![linear_task("eng-1")];
+
+        assert!(matches!(
+            core.delete_task("eng-1".to_string()),
+            Err(TodoTrayError::Unexpected { .. })
+        ));
+        assert!(matches!(
+            core.delete_task("missing".to_string()),
+            Err(TodoTrayError::NotFound { .. })
+        ));
+        assert!(http.take_requests().is_empty());
+    }
+
+    fn overdue_todoist_tasks(core: &TodoTrayCore, ids: &[&str]) {
+        core.state.blocking_lock().tasks.overdue = ids
+            .iter()
+            .map(|id| todoist_task(id, "Task", Some("2020-01-01")))
+            .collect();
+    }
+
+    #[test]
+    fn rescheduling_overdue_tasks_updates_each_and_refreshes_once() {
+        let (core, handler, http) = core_with_mock_todoist("", Duration::ZERO);
+        overdue_todoist_tasks(&core, &["1", "2", "3"]);
+        for _ in 0..3 {
+            http.respond(200, "{}");
+        }
+        http.respond(200, r#"{"results": [], "next_cursor": null}"#);
+
+        assert_eq!(core.reschedule_all_overdue_to_today().unwrap(), 3);
+
+        let requests = http.take_requests();
+        let paths: Vec<_> = requests.iter().map(|r| r.url().path()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/api/v1/tasks/1",
+                "/api/v1/tasks/2",
+                "/api/v1/tasks/3",
+                "/api/v1/tasks/filter"
+            ]
+        );
+        let body = std::str::from_utf8(requests[0].body().unwrap().as_bytes().unwrap()).unwrap();
+        let today = Local::now().format("%Y-%m-%d");
+        assert_eq!(body, format!(r#"{{"due_date":"{}"}}"#, today));
+        assert_eq!(handler.states.lock().unwrap().len(), 1);
+        assert!(core.get_state().source_errors.is_empty());
+    }
+
+    #[test]
+    fn rescheduling_keeps_the_time_of_day_and_skips_recurring_tasks() {
+        let (core, _handler, http) = core_with_mock_todoist("", Duration::ZERO);
+        let two_days_ago = Local::now().date_naive() - chrono::Duration::days(2);
+        let due = two_days_ago
+            .and_hms_opt(9, 30, 0)
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap();
+        core.state.blocking_lock().tasks.overdue = vec![
+            todoist_task_from(serde_json::json!({
+                "id": "timed",
+                "content": "Call",
+                "due": {"date": two_days_ago.to_string(), "datetime": due.to_rfc3339()},
+            })),
+            todoist_task_from(serde_json::json!({
+                "id": "weekly",
+                "content": "Review",
+                "due": {"date": two_days_ago.to_string(), "is_recurring": true},
+            })),
+        ];
+        http.respond(200, "{}");
+        http.respond(200, r#"{"results": [], "next_cursor": null}"#);
+
+        assert_eq!(core.reschedule_all_overdue_to_today().unwrap(), 1);
+
+        let requests = http.take_requests();
+        assert_eq!(requests[0].url().path(), "/api/v1/tasks/timed");
+        assert_eq!(requests[1].url().path(), "/api/v1/tasks/filter");
+        let today_at_930 = Local::now()
+            .date_naive()
+            .and_hms_opt(9, 30, 0)
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap()
+            .to_utc()
+            .format("%Y-%m-%dT%H:%M:%SZ");
+        let body = std::str::from_utf8(requests[0].body().unwrap().as_bytes().unwrap()).unwrap();
+        assert_eq!(body, format!(r#"{{"due_datetime":"{}"}}"#, today_at_930));
+    }
+
+    #[test]
+    fn failed_reschedules_are_reported_without_stopping_the_rest() {
+        let (core, _handler, http) = core_with_mock_todoist("", Duration::ZERO);
+        overdue_todoist_tasks(&core, &["1", "2"]);
+        http.respond(500, "boom");
+        http.respond(200, "{}");
+        http.respond(200, r#"{"results": [], "next_cursor": null}"#);
+
+        assert_eq!(core.reschedule_all_overdue_to_today().unwrap(), 1);
+
+        assert_eq!(http.take_requests()[1].url().path(), "/api/v1/tasks/2");
+        let errors = core.get_state().source_errors;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].source, "todoist");
+        assert!(errors[0].message.contains("1 of 2"));
+    }
+
+    #[test]
+    fn refreshes_requested_during_one_share_a_single_fetch() {
+        let (core, _handler, http) = core_with_mock_todoist("", Duration::ZERO);
+        for _ in 0..2 {
+            http.respond(200, r#"{"results": [], "next_cursor": null}"#);
+        }
+
+        // Stand in for a refresh that is still running.
+        let in_flight = core.refresh_gate.blocking_lock();
+        let callers: Vec<_> = (0..2)
+            .map(|_| {
+                let core = core.clone();
+                std::thread::spawn(move || core.refresh())
+            })
+            .collect();
+        while core.refresh_requests.load(Ordering::SeqCst) < 2 {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        drop(in_flight);
+        for caller in callers {
+            caller.join().unwrap().unwrap();
+        }
+
+        let fetches = http
+            .take_requests()
+            .iter()
+            .filter(|r| r.url().path() == "/api/v1/tasks/filter")
+            .count();
+        assert_eq!(fetches, 1);
+    }
+
+    #[test]
+    fn exported_state_is_json_with_the_counts_and_no_tokens() {
+        let (core, _handler) = test_core(
+            r#"
+            [[github_accounts]]
+            name = "work"
+            token = "gh-secret"
+            "#,
+        );
+        {
+            let mut state = core.state.blocking_lock();
+            state.overdue_count = 2;
+            state.today_count = 5;
+            state.tasks.in_progress = vec![linear_task("ENG-1")];
+        }
+
+        let json = core.export_state_json();
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["overdue_count"], 2);
+        assert_eq!(value["today_count"], 5);
+        assert_eq!(value["tasks"]["in_progress"][0]["id"], "ENG-1");
+        assert!(!json.contains("gh-secret"));
+    }
+
+    #[test]
+    fn disabling_github_empties_its_section_and_skips_its_fetch() {
+        let (core, handler, http) = core_with_mock_todoist("", Duration::ZERO);
+        let github_http = Arc::new(MockTransport::default());
+        {
+            let mut clients = build_clients(&test_config("")).unwrap();
+            clients.todoist = core.clients().todoist.clone();
+            clients.github = vec![Arc::new(GithubClient::new(
+                "work".to_string(),
+                "token".to_string(),
+                TimeFormat::default(),
+                crate::github::PageLimits::default(),
+                github_http.clone(),
+            ))];
+            *core.clients.write().unwrap() = Arc::new(clients);
+            let mut state = core.state.blocking_lock();
+            state.github_notifications = vec![github_section("work", &["1", "2"])];
+            state.github_notification_count = 2;
+        }
+
+        core.set_source_enabled("GitHub".to_string(), false)
+            .unwrap();
+
+        let state = handler.states.lock().unwrap().last().cloned().unwrap();
+        assert!(state.github_notifications.is_empty());
+        assert_eq!(state.github_notification_count, 0);
+        assert_eq!(state.disabled_sources, vec!["github"]);
+
+        http.respond(200, r#"{"results": [], "next_cursor": null}"#);
+        core.refresh().unwrap();
+
+        assert!(github_http.take_requests().is_empty());
+        assert!(core.get_state().github_notifications.is_empty());
+        assert!(matches!(
+            core.set_source_enabled("jira".to_string(), false),
+            Err(TodoTrayError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn completing_all_today_closes_only_completable_todoist_tasks() {
+        let (core, handler, http) = core_with_mock_todoist("", Duration::ZERO);
+        {
+            let mut state = core.state.blocking_lock();
+            let todoist = |id: &str, can_complete: bool| {
+                let mut task = todoist_task(id, "Task", Some(&today()));
+                task.can_complete = can_complete;
+                task
+            };
+            state.tasks.today = vec![
+                todoist("2", true),
+                todoist("3", false),
+                linear_task("4"),
+                todoist("5", true),
+            ];
+        }
+        http.respond(204, "");
+        http.respond(204, "");
+        http.respond(200, r#"{"results": [], "next_cursor": null}"#);
+
+        let outcome = core.clone().complete_all_today().unwrap();
+
+        assert_eq!(
+            outcome,
+            BulkCompletion {
+                completed: 2,
+                failed: 0,
+                skipped: 2,
+            }
+        );
+        let requests = http.take_requests();
+        let paths: Vec<_> = requests.iter().map(|r| r.url().path()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/api/v1/tasks/2/close",
+                "/api/v1/tasks/5/close",
+                "/api/v1/tasks/filter"
+            ]
+        );
+        assert_eq!(handler.states.lock().unwrap().len(), 1);
+        assert_eq!(
+            handler.completed.lock().unwrap().as_slice(),
+            &[("2 tasks".to_string(), None)]
+        );
+        assert_eq!(core.get_state().completed_today_count, 2);
+    }
+
+    #[test]
+    fn undoing_complete_all_today_restores_the_whole_batch() {
+        let (core, handler, http) = core_with_mock_todoist("", Duration::from_millis(100));
+        {
+            let mut state = core.state.blocking_lock();
+            state.tasks.today = std::mem::take(&mut state.tasks.overdue);
+            let second = todoist_task("2", "Task", Some(&today()));
+            state.tasks.today.push(second);
+        }
+
+        assert_eq!(core.clone().complete_all_today().unwrap().completed, 2);
+        assert_eq!(core.get_state().tasks.todoist_tasks().count(), 0);
+
+        let token = handler.completed.lock().unwrap()[0].1.clone().unwrap();
+        core.undo_complete(token).unwrap();
+        assert_eq!(core.get_state().tasks.todoist_tasks().count(), 2);
+
+        TOKIO_RUNTIME.block_on(wait_for_undo_timers(&core));
+        assert!(http.take_requests().is_empty());
+    }
+
+    #[test]
+    fn diagnostics_reflect_the_configured_clients_without_secrets() {
+        let (core, _handler) = test_core(
+            r#"
+            linear_api_token = "lin-secret"
+
+            [[github_accounts]]
+            name = "work"
+            token = "gh-secret"
+
+            [[github_accounts]]
+            name = "home"
+            token = "gh-secret-2"
+
+            [[calendar_feeds]]
+            name = "Team"
+            url = "https://example.com/private-feed.ics"
+            "#,
+        );
+        core.health.lock().unwrap().record(
+            "github",
+            Some("home"),
+            Some("Bad credentials"),
+            Utc::now(),
+        );
+
+        let diagnostics = core.diagnostics();
+
+        assert_eq!(diagnostics.linear_accounts, 1);
+        assert_eq!(diagnostics.github_accounts, 2);
+        assert_eq!(diagnostics.calendar_feeds, 1);
+        let sources: Vec<_> = diagnostics
+            .sources
+            .iter()
+            .map(|s| (s.source.as_str(), s.account_name.as_deref()))
+            .collect();
+        assert_eq!(
+            sources,
+            vec![
+                ("todoist", None),
+                ("linear", None),
+                ("github", Some("work")),
+                ("github", Some("home")),
+                ("calendar", None)
+            ]
+        );
+        assert_eq!(
+            diagnostics.sources[3].last_error.as_deref(),
+            Some("Bad credentials")
+        );
+        assert!(diagnostics.report.contains("github (home)"));
+        for secret in ["lin-secret", "gh-secret", "private-feed"] {
+            assert!(!diagnostics.report.contains(secret));
+        }
+    }
+
+    #[test]
+    fn snoozed_github_thread_stays_hidden_across_refreshes_until_it_expires() {
+        let (core, handler) = test_core("[[github_accounts]]\nname = \"work\"\ntoken = \"a\"");
+        {
+            let mut state = core.state.blocking_lock();
+            state.github_notifications = vec![github_section("work", &["1", "2"])];
+            state.github_notification_count = 2;
+        }
+
+        core.snooze_github_notification("work".to_string(), "1".to_string(), "30m".to_string())
+            .unwrap();
+
+        let state = handler.states.lock().unwrap().last().cloned().unwrap();
+        assert_eq!(state.github_notification_count, 1);
+        assert_eq!(
+            state.github_notifications[0].notifications[0].thread_id,
+            "2"
+        );
+        let mut refetched = github_section("work", &["1", "2"]);
+        core.hide_snoozed_github(&mut refetched);
+        assert_eq!(alert_ids(&refetched.notifications), vec!["2"]);
+
+        core.github_snoozes.lock().unwrap().snooze(
+            "work",
+            "1",
+            Utc::now() - chrono::Duration::seconds(1),
+        );
+        let mut refetched = github_section("work", &["1", "2"]);
+        core.hide_snoozed_github(&mut refetched);
+        assert_eq!(alert_ids(&refetched.notifications), vec!["1", "2"]);
+
+        assert!(matches!(
+            core.snooze_github_notification("home".to_string(), "1".to_string(), "30m".to_string()),
+            Err(TodoTrayError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn completed_recurring_task_stays_hidden_until_the_next_full_refresh() {
+        let (core, _handler, http) =
+            core_with_mock_todoist("collapse_completed_recurring = true", Duration::ZERO);
+        core.state.blocking_lock().tasks.overdue[0].is_recurring = true;
+        let tomorrow = (Local::now() + chrono::Duration::days(1)).format("%Y-%m-%d");
+        let next_instance = format!(
+            r#"{{"results": [{{"id": "1", "content": "Water plants",
+                "due": {{"date": "{}", "is_recurring": true}}}}], "next_cursor": null}}"#,
+            tomorrow
+        );
+        http.respond(204, "");
+        http.respond(200, &next_instance);
+
+        core.clone().complete("1".to_string()).unwrap();
+
+        let state = core.get_state();
+        assert!(state.tasks.overdue.is_empty());
+        assert!(state.tasks.tomorrow.is_empty());
+
+        http.respond(200, &next_instance);
+        core.refresh().unwrap();
+
+        assert_eq!(core.get_state().tasks.tomorrow[0].id, "1");
+    }
+
+    #[test]
+    fn recurring_suppression_lets_rescheduled_tasks_through() {
+        let mut suppression = RecurringSuppression::default();
+        suppression.add("1", Instant::now());
+        let task = |due: &str| todoist_task("1", "Task", Some(due));
+
+        let mut tasks = vec![task("2099-01-02")];
+        suppression.hide(&mut tasks);
+        assert!(tasks.is_empty());
+        let mut tasks = vec![task("2099-01-02")];
+        suppression.hide(&mut tasks);
+        assert!(tasks.is_empty());
+
+        let mut tasks = vec![task("2099-01-05")];
+        suppression.hide(&mut tasks);
+        assert_eq!(tasks.len(), 1);
+        assert!(suppression.entries.is_empty());
     }
 }
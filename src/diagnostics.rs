@@ -0,0 +1,182 @@
+//! What's configured and how each source last fared, for bug reports
+
+use chrono::{DateTime, Utc};
+use std::fmt::Write;
+
+/// Snapshot of the setup and source health. Holds no tokens or feed URLs,
+/// so it can be pasted into a bug report as is.
+#[derive(uniffi::Record, Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostics {
+    pub version: String,
+    /// Where config.toml is read from, if the config directory is known
+    pub config_path: Option<String>,
+    pub linear_accounts: u32,
+    pub github_accounts: u32,
+    pub calendar_feeds: u32,
+    pub read_only: bool,
+    /// One entry per configured source, in refresh order
+    pub sources: Vec<SourceHealth>,
+    /// Everything above as plain text
+    pub report: String,
+}
+
+impl Diagnostics {
+    pub fn new(
+        config_path: Option<String>,
+        linear_accounts: u32,
+        github_accounts: u32,
+        calendar_feeds: u32,
+        read_only: bool,
+        sources: Vec<SourceHealth>,
+    ) -> Self {
+        let mut diagnostics = Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            config_path,
+            linear_accounts,
+            github_accounts,
+            calendar_feeds,
+            read_only,
+            sources,
+            report: String::new(),
+        };
+        diagnostics.report = diagnostics.render();
+        diagnostics
+    }
+
+    fn render(&self) -> String {
+        let mut report = format!("Todo Tray {}\n", self.version);
+        let _ = writeln!(
+            report,
+            "Config: {}",
+            self.config_path.as_deref().unwrap_or("(unknown)")
+        );
+        let _ = writeln!(
+            report,
+            "Accounts: Linear {}, GitHub {}, calendar feeds {}{}",
+            self.linear_accounts,
+            self.github_accounts,
+            self.calendar_feeds,
+            if self.read_only { ", read-only" } else { "" }
+        );
+        for source in &self.sources {
+            let name = match &source.account_name {
+                Some(account) => format!("{} ({})", source.source, account),
+                None => source.source.clone(),
+            };
+            let _ = write!(
+                report,
+                "{}: last success {}, last failure {}",
+                name,
+                source.last_success.as_deref().unwrap_or("never"),
+                source.last_failure.as_deref().unwrap_or("never"),
+            );
+            if let Some(error) = &source.last_error {
+                let _ = write!(report, " ({})", error);
+            }
+            report.push('\n');
+        }
+        report
+    }
+}
+
+/// How one source, or one account of it, last refreshed
+#[derive(uniffi::Record, Clone, Debug, PartialEq, Eq)]
+pub struct SourceHealth {
+    /// "todoist", "linear", "github" or "calendar"
+    pub source: String,
+    pub account_name: Option<String>,
+    /// RFC3339
+    pub last_success: Option<String>,
+    /// RFC3339
+    pub last_failure: Option<String>,
+    /// Message of the last failure
+    pub last_error: Option<String>,
+}
+
+/// Health of every source seen in a refresh so far
+#[derive(Debug, Default)]
+pub struct HealthLog {
+    sources: Vec<SourceHealth>,
+}
+
+impl HealthLog {
+    /// Note how `source` fared at `at`; `error` is `None` on success.
+    pub fn record(
+        &mut self,
+        source: &str,
+        account_name: Option<&str>,
+        error: Option<&str>,
+        at: DateTime<Utc>,
+    ) {
+        let index = match self
+            .sources
+            .iter()
+            .position(|s| s.source == source && s.account_name.as_deref() == account_name)
+        {
+            Some(index) => index,
+            None => {
+                self.sources.push(SourceHealth {
+                    source: source.to_string(),
+                    account_name: account_name.map(str::to_string),
+                    last_success: None,
+                    last_failure: None,
+                    last_error: None,
+                });
+                self.sources.len() - 1
+            }
+        };
+        let entry = &mut self.sources[index];
+        match error {
+            Some(message) => {
+                entry.last_failure = Some(at.to_rfc3339());
+                entry.last_error = Some(message.to_string());
+            }
+            None => entry.last_success = Some(at.to_rfc3339()),
+        }
+    }
+
+    /// Entries for `configured` sources in that order, with sources refreshed
+    /// before a config change left out.
+    pub fn for_sources(&self, configured: &[(&str, Option<String>)]) -> Vec<SourceHealth> {
+        configured
+            .iter()
+            .map(|(source, account_name)| {
+                self.sources
+                    .iter()
+                    .find(|s| s.source == *source && s.account_name == *account_name)
+                    .cloned()
+                    .unwrap_or_else(|| SourceHealth {
+                        source: source.to_string(),
+                        account_name: account_name.clone(),
+                        last_success: None,
+                        last_failure: None,
+                        last_error: None,
+                    })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HealthLog;
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn failures_keep_the_last_success() {
+        let mut log = HealthLog::default();
+        let first = Utc::now();
+        let later = first + Duration::minutes(5);
+        log.record("github", Some("work"), None, first);
+        log.record("github", Some("work"), Some("401 Unauthorized"), later);
+        log.record("github", Some("home"), None, later);
+
+        let sources = log.for_sources(&[("todoist", None), ("github", Some("work".to_string()))]);
+
+        assert_eq!(sources[0].source, "todoist");
+        assert_eq!(sources[0].last_success, None);
+        assert_eq!(sources[1].last_success, Some(first.to_rfc3339()));
+        assert_eq!(sources[1].last_failure, Some(later.to_rfc3339()));
+        assert_eq!(sources[1].last_error.as_deref(), Some("401 Unauthorized"));
+    }
+}
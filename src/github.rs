@@ -1,128 +1,237 @@
 //! GitHub notifications API client
 
+use crate::clock::{Clock, SystemClock};
+use crate::http::{status_error, HttpTransport};
+use crate::locale::{Language, Text};
+use crate::time_format::TimeFormat;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Local, Utc};
 use reqwest::Client;
-use serde::Deserialize;
-use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 const GITHUB_API_URL: &str = "https://api.github.com";
 const GITHUB_API_VERSION: &str = "2022-11-28";
 const USER_AGENT: &str = "todo-tray";
-const PAGE_SIZE: usize = 50;
-const MAX_PAGES: usize = 10;
 
-#[derive(uniffi::Record, Clone, Debug)]
+/// Largest page size the notifications endpoint accepts
+pub const MAX_PAGE_SIZE: usize = 50;
+
+#[derive(uniffi::Record, Clone, Debug, Serialize, Deserialize)]
 pub struct GithubNotification {
     pub thread_id: String,
     pub title: String,
     pub repository: String,
     pub reason: String,
+    /// What the thread is about, from the subject type
+    #[serde(default)]
+    pub kind: GithubNotificationKind,
+    /// Asks something of the user directly (review request or mention)
+    #[serde(default)]
+    pub is_actionable: bool,
     pub web_url: String,
     pub updated_at: Option<String>, // RFC3339
     pub display_time: String,
 }
 
-#[derive(uniffi::Record, Clone, Debug, Default)]
+/// What a notification thread is about
+#[derive(uniffi::Enum, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GithubNotificationKind {
+    PullRequest,
+    Issue,
+    Release,
+    Discussion,
+    CheckSuite,
+    /// Commits, security alerts and anything newer
+    #[default]
+    Other,
+}
+
+impl GithubNotificationKind {
+    /// Map a thread's `subject.type`, e.g. "PullRequest".
+    fn from_subject_type(subject_type: &str) -> Self {
+        match subject_type {
+            "PullRequest" => Self::PullRequest,
+            "Issue" => Self::Issue,
+            "Release" => Self::Release,
+            "Discussion" => Self::Discussion,
+            "CheckSuite" => Self::CheckSuite,
+            _ => Self::Other,
+        }
+    }
+}
+
+#[derive(uniffi::Record, Clone, Debug, Default, Serialize, Deserialize)]
 pub struct GithubNotificationSection {
+    /// The configured account `name`, which identifies the account in calls
+    /// like `resolve_github_notification`
     pub account_name: String,
+    /// Menu section title to show instead of `account_name`
+    #[serde(default)]
+    pub display_name: Option<String>,
     pub notifications: Vec<GithubNotification>,
+    /// More unread notifications exist on GitHub than were fetched
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+/// How much of an account's inbox to fetch per refresh
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PageLimits {
+    pub per_page: usize,
+    pub max_pages: usize,
+    /// Stop fetching once this many unread notifications are collected
+    pub max_notifications: Option<usize>,
+}
+
+impl Default for PageLimits {
+    fn default() -> Self {
+        Self {
+            per_page: MAX_PAGE_SIZE,
+            max_pages: 10,
+            max_notifications: None,
+        }
+    }
 }
 
 /// GitHub API client for one account
 pub struct GithubClient {
+    /// Builds requests; `http` sends them
     client: Client,
+    http: Arc<dyn HttpTransport>,
     account_name: String,
+    display_name: Option<String>,
     api_token: String,
+    time_format: TimeFormat,
+    language: Language,
+    limits: PageLimits,
 }
 
 impl GithubClient {
-    pub fn new(account_name: String, api_token: String) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .expect("Failed to create HTTP client");
-
+    /// Requests are sent through `http`, shared with the other clients.
+    pub fn new(
+        account_name: String,
+        api_token: String,
+        time_format: TimeFormat,
+        limits: PageLimits,
+        http: Arc<dyn HttpTransport>,
+    ) -> Self {
+        let client = Client::new();
         Self {
             client,
+            http,
             account_name,
+            display_name: None,
             api_token,
+            time_format,
+            language: Language::default(),
+            limits,
         }
     }
 
+    /// Title the section `display_name` rather than the account name.
+    pub fn with_display_name(mut self, display_name: Option<String>) -> Self {
+        self.display_name = display_name;
+        self
+    }
+
+    /// Write relative times like "3h ago" in `language`.
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
+
     pub fn account_name(&self) -> &str {
         self.account_name.as_str()
     }
 
-    /// Fetch unread notifications for this account.
+    /// Fetch unread notifications for this account, up to the configured limits.
+    #[tracing::instrument(name = "github_fetch", skip_all, fields(account = %self.account_name, notifications))]
     pub async fn get_notifications(&self) -> Result<GithubNotificationSection> {
-        let mut notifications = Vec::new();
-
-        for page in 1..=MAX_PAGES {
-            let url = format!("{}/notifications", GITHUB_API_URL);
-            let response = self
-                .client
-                .get(url)
-                .header("Authorization", format!("Bearer {}", self.api_token))
-                .header("Accept", "application/vnd.github+json")
-                .header("X-GitHub-Api-Version", GITHUB_API_VERSION)
-                .header("User-Agent", USER_AGENT)
-                .query(&[
-                    ("all", "false"),
-                    ("participating", "false"),
-                    ("per_page", &PAGE_SIZE.to_string()),
-                    ("page", &page.to_string()),
-                ])
-                .send()
-                .await
-                .with_context(|| {
-                    format!(
-                        "Failed to connect to GitHub API for account '{}'",
-                        self.account_name
-                    )
-                })?;
-
-            if !response.status().is_success() {
-                let status = response.status();
-                let body = response.text().await.unwrap_or_default();
-                return Err(anyhow::anyhow!(
-                    "GitHub API error for account '{}' ({}): {}",
-                    self.account_name,
-                    status,
-                    body
-                ));
-            }
-
-            let page_items: Vec<GithubThread> = response.json().await.with_context(|| {
-                format!(
-                    "Failed to parse GitHub notifications for account '{}'",
-                    self.account_name
-                )
-            })?;
+        let (notifications, has_more) =
+            collect_notifications(self.limits, |page| self.get_notifications_page(page)).await?;
 
-            let item_count = page_items.len();
-            notifications.extend(page_items.into_iter().filter(|n| n.unread).map(|thread| {
+        let mut notifications = notifications
+            .into_iter()
+            .map(|thread| {
                 let updated = parse_updated_at(&thread.updated_at);
                 let web_url = build_web_url(&thread);
                 GithubNotification {
                     thread_id: thread.id.clone(),
                     title: thread.subject.title,
                     repository: thread.repository.full_name,
+                    kind: GithubNotificationKind::from_subject_type(&thread.subject.kind),
+                    is_actionable: is_actionable_reason(&thread.reason),
                     reason: humanize_reason(&thread.reason),
                     web_url,
                     updated_at: updated.map(|dt| dt.to_rfc3339()),
-                    display_time: format_relative_time(updated),
+                    display_time: format_relative_time(
+                        updated,
+                        self.time_format,
+                        self.language,
+                        &SystemClock,
+                    ),
                 }
-            }));
-
-            if item_count < PAGE_SIZE {
-                break;
-            }
-        }
+            })
+            .collect::<Vec<_>>();
+        sort_actionable_first(&mut notifications);
+        tracing::Span::current().record("notifications", notifications.len());
 
         Ok(GithubNotificationSection {
             account_name: self.account_name.clone(),
+            display_name: self.display_name.clone(),
             notifications,
+            has_more,
+        })
+    }
+
+    async fn get_notifications_page(&self, page: usize) -> Result<Vec<GithubThread>> {
+        let url = format!("{}/notifications", GITHUB_API_URL);
+        let response = self
+            .http
+            .send(
+                self.client
+                    .get(url)
+                    .header("Authorization", format!("Bearer {}", self.api_token))
+                    .header("Accept", "application/vnd.github+json")
+                    .header("X-GitHub-Api-Version", GITHUB_API_VERSION)
+                    .header("User-Agent", USER_AGENT)
+                    .query(&[
+                        ("all", "false"),
+                        ("participating", "false"),
+                        ("per_page", &self.limits.per_page.to_string()),
+                        ("page", &page.to_string()),
+                    ]),
+            )
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to connect to GitHub API for account '{}'",
+                    self.account_name
+                )
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(status_error(
+                status,
+                &body,
+                &format!("GitHub token for account '{}'", self.account_name),
+                anyhow::anyhow!(
+                    "GitHub API error for account '{}' ({}): {}",
+                    self.account_name,
+                    status,
+                    body
+                ),
+            ));
+        }
+
+        response.json().with_context(|| {
+            format!(
+                "Failed to parse GitHub notifications for account '{}'",
+                self.account_name
+            )
         })
     }
 
@@ -130,13 +239,15 @@ impl GithubClient {
     pub async fn mark_notification_as_read(&self, thread_id: &str) -> Result<()> {
         let url = format!("{}/notifications/threads/{}", GITHUB_API_URL, thread_id);
         let response = self
-            .client
-            .patch(url)
-            .header("Authorization", format!("Bearer {}", self.api_token))
-            .header("Accept", "application/vnd.github+json")
-            .header("X-GitHub-Api-Version", GITHUB_API_VERSION)
-            .header("User-Agent", USER_AGENT)
-            .send()
+            .http
+            .send(
+                self.client
+                    .patch(url)
+                    .header("Authorization", format!("Bearer {}", self.api_token))
+                    .header("Accept", "application/vnd.github+json")
+                    .header("X-GitHub-Api-Version", GITHUB_API_VERSION)
+                    .header("User-Agent", USER_AGENT),
+            )
             .await
             .with_context(|| {
                 format!(
@@ -147,17 +258,68 @@ impl GithubClient {
 
         if !response.status().is_success() {
             let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!(
-                "Failed to resolve GitHub notification for account '{}' ({}): {}",
-                self.account_name,
+            let body = response.text().unwrap_or_default();
+            return Err(status_error(
                 status,
-                body
+                &body,
+                &format!("GitHub token for account '{}'", self.account_name),
+                anyhow::anyhow!(
+                    "Failed to resolve GitHub notification for account '{}' ({}): {}",
+                    self.account_name,
+                    status,
+                    body
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Ignore future notifications on a thread.
+    pub async fn unsubscribe_thread(&self, thread_id: &str) -> Result<()> {
+        let request = self
+            .unsubscribe_request(thread_id)
+            .context("Failed to build GitHub unsubscribe request")?;
+        let response = self.http.execute(request).await.with_context(|| {
+            format!(
+                "Failed to connect to GitHub API for account '{}'",
+                self.account_name
+            )
+        })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(status_error(
+                status,
+                &body,
+                &format!("GitHub token for account '{}'", self.account_name),
+                anyhow::anyhow!(
+                    "Failed to unsubscribe from GitHub notification for account '{}' ({}): {}",
+                    self.account_name,
+                    status,
+                    body
+                ),
             ));
         }
 
         Ok(())
     }
+
+    fn unsubscribe_request(&self, thread_id: &str) -> reqwest::Result<reqwest::Request> {
+        let url = format!(
+            "{}/notifications/threads/{}/subscription",
+            GITHUB_API_URL, thread_id
+        );
+        self.client
+            .put(url)
+            .header("Authorization", format!("Bearer {}", self.api_token))
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", GITHUB_API_VERSION)
+            .header("User-Agent", USER_AGENT)
+            .json(&serde_json::json!({ "ignored": true }))
+            .build()
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -174,6 +336,8 @@ struct GithubThread {
 struct GithubSubject {
     title: String,
     url: Option<String>,
+    #[serde(rename = "type", default)]
+    kind: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -181,6 +345,40 @@ struct GithubRepository {
     full_name: String,
 }
 
+/// Walk notification pages, keeping unread threads, until the inbox runs out or
+/// a limit is hit. Also returns whether more unread threads may remain.
+async fn collect_notifications<F, Fut>(
+    limits: PageLimits,
+    mut fetch_page: F,
+) -> Result<(Vec<GithubThread>, bool)>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: std::future::Future<Output = Result<Vec<GithubThread>>>,
+{
+    let mut threads = Vec::new();
+    let mut has_more = false;
+
+    for page in 1..=limits.max_pages {
+        let page_items = fetch_page(page).await?;
+        let full_page = page_items.len() >= limits.per_page;
+        threads.extend(page_items.into_iter().filter(|thread| thread.unread));
+
+        if let Some(cap) = limits.max_notifications {
+            if threads.len() >= cap {
+                has_more = threads.len() > cap || full_page;
+                threads.truncate(cap);
+                break;
+            }
+        }
+        if !full_page {
+            break;
+        }
+        has_more = page == limits.max_pages;
+    }
+
+    Ok((threads, has_more))
+}
+
 fn build_web_url(thread: &GithubThread) -> String {
     // Prefer opening the underlying issue/PR when available.
     if let Some(url) = thread
@@ -235,25 +433,40 @@ fn parse_updated_at(value: &str) -> Option<DateTime<Utc>> {
         .map(|dt| dt.with_timezone(&Utc))
 }
 
-fn format_relative_time(updated_at: Option<DateTime<Utc>>) -> String {
+fn format_relative_time(
+    updated_at: Option<DateTime<Utc>>,
+    time_format: TimeFormat,
+    language: Language,
+    clock: &dyn Clock,
+) -> String {
     let Some(updated_at) = updated_at else {
-        return "recent".to_string();
+        return language.template(Text::Recent).to_string();
     };
 
-    let now = Utc::now();
+    let now = clock.now();
     let diff = now.signed_duration_since(updated_at);
     if diff.num_days() > 0 {
-        format!("{}d ago", diff.num_days())
+        language.format(Text::DaysAgo, diff.num_days())
     } else if diff.num_hours() > 0 {
-        format!("{}h ago", diff.num_hours())
+        language.format(Text::HoursAgo, diff.num_hours())
     } else if diff.num_minutes() > 0 {
-        format!("{}m ago", diff.num_minutes())
+        language.format(Text::MinutesAgo, diff.num_minutes())
     } else {
         let local = updated_at.with_timezone(&Local);
-        local.format("%H:%M").to_string()
+        time_format.format_time(&local)
     }
 }
 
+/// Reasons where someone is waiting on the user rather than just keeping them posted
+fn is_actionable_reason(reason: &str) -> bool {
+    matches!(reason, "review_requested" | "mention" | "team_mention")
+}
+
+/// Move actionable notifications to the top, keeping GitHub's order otherwise.
+fn sort_actionable_first(notifications: &mut [GithubNotification]) {
+    notifications.sort_by_key(|notification| !notification.is_actionable);
+}
+
 fn humanize_reason(reason: &str) -> String {
     let mut chars = reason.chars();
     let Some(first) = chars.next() else {
@@ -266,7 +479,95 @@ fn humanize_reason(reason: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::api_subject_url_to_web_url;
+    use super::{
+        api_subject_url_to_web_url, collect_notifications, format_relative_time,
+        is_actionable_reason, sort_actionable_first, GithubClient, GithubNotification,
+        GithubNotificationKind, GithubRepository, GithubSubject, GithubThread, PageLimits,
+    };
+    use crate::clock::FixedClock;
+    use crate::http::{InvalidTokenError, MockTransport};
+    use crate::locale::Language;
+    use crate::time_format::TimeFormat;
+    use chrono::{Duration, Utc};
+    use std::cell::Cell;
+    use std::sync::Arc;
+
+    fn thread(id: usize) -> GithubThread {
+        GithubThread {
+            id: id.to_string(),
+            unread: true,
+            reason: "subscribed".to_string(),
+            updated_at: "2026-01-01T00:00:00Z".to_string(),
+            subject: GithubSubject {
+                title: format!("Thread {}", id),
+                url: None,
+                kind: "PullRequest".to_string(),
+            },
+            repository: GithubRepository {
+                full_name: "octo-org/octo-repo".to_string(),
+            },
+        }
+    }
+
+    /// Serve an endless inbox of full pages, counting the requests made.
+    async fn collect(limits: PageLimits, requests: &Cell<usize>) -> (Vec<GithubThread>, bool) {
+        collect_notifications(limits, |page| {
+            requests.set(requests.get() + 1);
+            let first = (page - 1) * limits.per_page;
+            async move { Ok((first..first + limits.per_page).map(thread).collect()) }
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn stops_fetching_once_the_cap_is_reached() {
+        let limits = PageLimits {
+            per_page: 10,
+            max_pages: 10,
+            max_notifications: Some(25),
+        };
+        let requests = Cell::new(0);
+
+        let (threads, has_more) = collect(limits, &requests).await;
+
+        assert_eq!(requests.get(), 3);
+        assert_eq!(threads.len(), 25);
+        assert!(has_more);
+    }
+
+    #[tokio::test]
+    async fn stops_at_max_pages_without_a_cap() {
+        let limits = PageLimits {
+            per_page: 10,
+            max_pages: 2,
+            max_notifications: None,
+        };
+        let requests = Cell::new(0);
+
+        let (threads, has_more) = collect(limits, &requests).await;
+
+        assert_eq!(requests.get(), 2);
+        assert_eq!(threads.len(), 20);
+        assert!(has_more);
+    }
+
+    #[tokio::test]
+    async fn short_page_ends_the_inbox() {
+        let limits = PageLimits::default();
+        let (threads, has_more) = collect_notifications(limits, |page| async move {
+            Ok(if page == 1 {
+                vec![thread(1), thread(2)]
+            } else {
+                Vec::new()
+            })
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(threads.len(), 2);
+        assert!(!has_more);
+    }
 
     #[test]
     fn converts_issue_subject_url_to_web_url() {
@@ -300,4 +601,173 @@ mod tests {
             Some("https://github.com/octo-org/octo-repo/releases")
         );
     }
+
+    fn notification(id: &str, reason: &str) -> GithubNotification {
+        GithubNotification {
+            thread_id: id.to_string(),
+            title: format!("Thread {}", id),
+            repository: "octo-org/octo-repo".to_string(),
+            reason: reason.to_string(),
+            kind: GithubNotificationKind::PullRequest,
+            is_actionable: is_actionable_reason(reason),
+            web_url: String::new(),
+            updated_at: None,
+            display_time: "recent".to_string(),
+        }
+    }
+
+    #[test]
+    fn review_requests_sort_above_subscriptions() {
+        let mut notifications = vec![
+            notification("1", "subscribed"),
+            notification("2", "review_requested"),
+            notification("3", "author"),
+            notification("4", "mention"),
+        ];
+
+        sort_actionable_first(&mut notifications);
+
+        let ids: Vec<&str> = notifications.iter().map(|n| n.thread_id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "4", "1", "3"]);
+        assert!(notifications[0].is_actionable);
+        assert!(!notifications[2].is_actionable);
+    }
+
+    #[test]
+    fn unsubscribe_puts_ignored_subscription() {
+        let client = GithubClient::new(
+            "work".to_string(),
+            "token".to_string(),
+            TimeFormat::default(),
+            PageLimits::default(),
+            Arc::new(MockTransport::default()),
+        );
+
+        let request = client.unsubscribe_request("42").unwrap();
+
+        assert_eq!(request.method(), reqwest::Method::PUT);
+        assert_eq!(
+            request.url().as_str(),
+            "https://api.github.com/notifications/threads/42/subscription"
+        );
+        let body = request.body().and_then(|body| body.as_bytes()).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(body).unwrap(),
+            serde_json::json!({ "ignored": true })
+        );
+    }
+
+    #[tokio::test]
+    async fn unauthorized_response_names_the_rejected_account() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(401, r#"{"message": "Bad credentials"}"#);
+        let client = GithubClient::new(
+            "work".to_string(),
+            "token".to_string(),
+            TimeFormat::default(),
+            PageLimits::default(),
+            http,
+        );
+
+        let error = client.get_notifications().await.unwrap_err();
+
+        assert!(error.downcast_ref::<InvalidTokenError>().is_some());
+        assert_eq!(
+            error.to_string(),
+            "GitHub token for account 'work' is invalid or expired"
+        );
+    }
+
+    #[tokio::test]
+    async fn display_name_titles_the_section_but_not_the_account() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(200, "[]");
+        let client = GithubClient::new(
+            "work".to_string(),
+            "token".to_string(),
+            TimeFormat::default(),
+            PageLimits::default(),
+            http,
+        )
+        .with_display_name(Some("Day job".to_string()));
+
+        let section = client.get_notifications().await.unwrap();
+
+        assert_eq!(client.account_name(), "work");
+        assert_eq!(section.account_name, "work");
+        assert_eq!(section.display_name.as_deref(), Some("Day job"));
+    }
+
+    #[test]
+    fn relative_time_is_measured_from_the_clock() {
+        let now = Utc::now();
+        let clock = FixedClock(now);
+        let ago = |minutes| Some(now - Duration::minutes(minutes));
+
+        assert_eq!(
+            format_relative_time(ago(5), TimeFormat::default(), Language::English, &clock),
+            "5m ago"
+        );
+        assert_eq!(
+            format_relative_time(ago(125), TimeFormat::default(), Language::English, &clock),
+            "2h ago"
+        );
+        assert_eq!(
+            format_relative_time(
+                ago(60 * 24 * 3),
+                TimeFormat::default(),
+                Language::English,
+                &clock
+            ),
+            "3d ago"
+        );
+        assert_eq!(
+            format_relative_time(None, TimeFormat::default(), Language::English, &clock),
+            "recent"
+        );
+    }
+
+    #[test]
+    fn relative_time_follows_the_language() {
+        let now = Utc::now();
+        let clock = FixedClock(now);
+        let ago = |minutes| Some(now - Duration::minutes(minutes));
+        let swedish = |updated_at| {
+            format_relative_time(updated_at, TimeFormat::default(), Language::Swedish, &clock)
+        };
+
+        assert_eq!(swedish(ago(5)), "5 min sedan");
+        assert_eq!(swedish(ago(125)), "2 tim sedan");
+        // No Swedish template yet, so English is used
+        assert_eq!(swedish(None), "recent");
+    }
+
+    #[test]
+    fn subject_types_map_to_kinds() {
+        let cases = [
+            ("PullRequest", GithubNotificationKind::PullRequest),
+            ("Issue", GithubNotificationKind::Issue),
+            ("Release", GithubNotificationKind::Release),
+            ("Discussion", GithubNotificationKind::Discussion),
+            ("CheckSuite", GithubNotificationKind::CheckSuite),
+            ("Commit", GithubNotificationKind::Other),
+            (
+                "RepositoryVulnerabilityAlert",
+                GithubNotificationKind::Other,
+            ),
+            ("", GithubNotificationKind::Other),
+        ];
+        for (subject_type, kind) in cases {
+            assert_eq!(
+                GithubNotificationKind::from_subject_type(subject_type),
+                kind,
+                "{}",
+                subject_type
+            );
+        }
+
+        let subject: GithubSubject =
+            serde_json::from_str(r#"{"title": "Fix", "url": null, "type": "Issue"}"#).unwrap();
+        assert_eq!(subject.kind, "Issue");
+    }
 }
@@ -0,0 +1,135 @@
+//! GitHub notification threads hidden locally for a while, kept across restarts
+//!
+//! Snoozing never touches the thread on GitHub; it stays unread there and
+//! shows up again on the first refresh after the snooze ends.
+
+use crate::github::GithubNotificationSection;
+use crate::json_store;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct SnoozedThread {
+    account_name: String,
+    thread_id: String,
+    until: DateTime<Utc>,
+}
+
+/// Snoozed threads by account
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GithubSnoozes {
+    threads: Vec<SnoozedThread>,
+}
+
+impl GithubSnoozes {
+    /// Hide `thread_id` until `until`, replacing any earlier snooze of it.
+    pub fn snooze(&mut self, account_name: &str, thread_id: &str, until: DateTime<Utc>) {
+        self.threads
+            .retain(|t| !(t.account_name == account_name && t.thread_id == thread_id));
+        self.threads.push(SnoozedThread {
+            account_name: account_name.to_string(),
+            thread_id: thread_id.to_string(),
+            until,
+        });
+    }
+
+    /// Forget snoozes that ended by `now`, returning whether any did.
+    pub fn prune(&mut self, now: DateTime<Utc>) -> bool {
+        let before = self.threads.len();
+        self.threads.retain(|t| t.until > now);
+        self.threads.len() != before
+    }
+
+    /// Drop threads from `section` that are snoozed at `now`.
+    pub fn hide(&self, section: &mut GithubNotificationSection, now: DateTime<Utc>) {
+        section.notifications.retain(|notification| {
+            !self.threads.iter().any(|t| {
+                t.account_name == section.account_name
+                    && t.thread_id == notification.thread_id
+                    && t.until > now
+            })
+        });
+    }
+}
+
+/// Get the path to the GitHub snooze file
+pub fn store_path() -> Result<PathBuf> {
+    json_store::path("github-snoozes.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn section(thread_ids: &[&str]) -> GithubNotificationSection {
+        let notifications = thread_ids
+            .iter()
+            .map(|id| {
+                serde_json::from_value(serde_json::json!({
+                    "thread_id": id,
+                    "title": "Review",
+                    "repository": "octo-org/octo-repo",
+                    "reason": "review_requested",
+                    "web_url": "https://github.com",
+                    "updated_at": null,
+                    "display_time": "",
+                }))
+                .unwrap()
+            })
+            .collect();
+        GithubNotificationSection {
+            account_name: "work".to_string(),
+            display_name: None,
+            notifications,
+            has_more: false,
+        }
+    }
+
+    fn thread_ids(section: &GithubNotificationSection) -> Vec<&str> {
+        section
+            .notifications
+            .iter()
+            .map(|n| n.thread_id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn snoozed_threads_are_hidden_until_the_snooze_ends() {
+        let now = Utc::now();
+        let until = now + chrono::Duration::minutes(30);
+        let mut snoozes = GithubSnoozes::default();
+        snoozes.snooze("work", "1", until);
+        snoozes.snooze("home", "2", until);
+
+        let mut during = section(&["1", "2"]);
+        snoozes.hide(&mut during, now);
+        assert_eq!(thread_ids(&during), vec!["2"]);
+
+        assert!(!snoozes.prune(now));
+        let after = until + chrono::Duration::seconds(1);
+        let mut resurfaced = section(&["1", "2"]);
+        snoozes.hide(&mut resurfaced, after);
+        assert_eq!(thread_ids(&resurfaced), vec!["1", "2"]);
+        assert!(snoozes.prune(after));
+        assert!(snoozes.threads.is_empty());
+    }
+
+    #[test]
+    fn snoozes_round_trip_through_disk() {
+        let path = std::env::temp_dir()
+            .join(format!("todo-tray-snoozes-{}", std::process::id()))
+            .join("github-snoozes.json");
+        let until = Utc::now() + chrono::Duration::hours(1);
+        let mut snoozes = GithubSnoozes::default();
+        snoozes.snooze("work", "42", until);
+
+        json_store::save(&path, &snoozes).unwrap();
+        let loaded: GithubSnoozes = json_store::load_or_default(&path);
+
+        assert_eq!(loaded.threads, snoozes.threads);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
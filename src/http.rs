@@ -0,0 +1,401 @@
+//! HTTP transport shared by the API clients
+//!
+//! Clients build requests with `reqwest` but send them through an
+//! [`HttpTransport`], so tests can swap in canned responses.
+
+use anyhow::{Context, Result};
+use reqwest::{Certificate, Client, Identity, NoProxy, Proxy, Request, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use std::collections::HashSet;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::time::Duration;
+
+/// An API rejected the credentials it was given
+#[derive(Debug, thiserror::Error)]
+#[error("{credential} is invalid or expired")]
+pub struct InvalidTokenError {
+    /// What was rejected, e.g. "GitHub token for account 'work'"
+    pub credential: String,
+}
+
+/// Error for a failed response: [`InvalidTokenError`] when `credential` was
+/// rejected, otherwise `error`.
+///
+/// GitHub also answers 403 when rate limited, which says nothing about the token.
+pub fn status_error(
+    status: StatusCode,
+    body: &str,
+    credential: &str,
+    error: anyhow::Error,
+) -> anyhow::Error {
+    let rejected = status == StatusCode::UNAUTHORIZED
+        || (status == StatusCode::FORBIDDEN && !body.to_lowercase().contains("rate limit"));
+    if rejected {
+        InvalidTokenError {
+            credential: credential.to_string(),
+        }
+        .into()
+    } else {
+        error
+    }
+}
+
+/// Most pages one cursor loop fetches
+pub const MAX_CURSOR_PAGES: usize = 100;
+
+/// Stops a cursor pagination loop that would never end, because the API
+/// keeps returning a cursor it already gave or simply never runs out.
+#[derive(Debug)]
+pub struct CursorGuard {
+    /// Names the loop in warnings, e.g. "Todoist tasks"
+    what: &'static str,
+    seen: HashSet<String>,
+}
+
+impl CursorGuard {
+    pub fn new(what: &'static str) -> Self {
+        Self {
+            what,
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Whether to fetch the page at `cursor`. Logs a warning and returns
+    /// false for a repeated cursor or past [`MAX_CURSOR_PAGES`].
+    pub fn follow(&mut self, cursor: &str) -> bool {
+        if self.seen.len() + 1 >= MAX_CURSOR_PAGES {
+            tracing::warn!(
+                what = self.what,
+                pages = MAX_CURSOR_PAGES,
+                "Stopped paging at the page limit"
+            );
+            return false;
+        }
+        if !self.seen.insert(cursor.to_string()) {
+            tracing::warn!(
+                what = self.what,
+                cursor,
+                "Stopped paging at a repeated cursor"
+            );
+            return false;
+        }
+        true
+    }
+}
+
+pub type ResponseFuture<'a> = Pin<Box<dyn Future<Output = Result<HttpResponse>> + Send + 'a>>;
+
+/// Sends requests and reads whole responses
+pub trait HttpTransport: Send + Sync {
+    fn execute(&self, request: Request) -> ResponseFuture<'_>;
+
+    /// Build `request` and send it.
+    fn send(&self, request: RequestBuilder) -> ResponseFuture<'_> {
+        match request.build() {
+            Ok(request) => self.execute(request),
+            Err(e) => Box::pin(async move { Err(e.into()) }),
+        }
+    }
+}
+
+/// A fully read response
+#[derive(Debug)]
+pub struct HttpResponse {
+    status: StatusCode,
+    body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn new(status: StatusCode, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            body: body.into(),
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn text(&self) -> Result<String> {
+        String::from_utf8(self.body.clone()).context("Response body is not valid UTF-8")
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+/// Proxies from config.toml. Any proxy set here replaces the ones reqwest
+/// would pick up from `HTTP_PROXY`/`HTTPS_PROXY`.
+#[derive(Clone, Debug, Default)]
+pub struct ProxySettings {
+    /// Proxy for plain HTTP requests, e.g. "http://proxy.corp:8080"
+    pub http_proxy: Option<String>,
+    /// Proxy for HTTPS requests
+    pub https_proxy: Option<String>,
+    /// Comma-separated hosts that skip the proxies above, e.g. "localhost,.corp"
+    pub no_proxy: Option<String>,
+}
+
+/// Extra TLS setup for a server behind a private CA or asking for a client
+/// certificate
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TlsSettings {
+    /// PEM CA certificate trusted on top of the system roots
+    pub ca_cert_path: Option<PathBuf>,
+    /// PEM client certificate and its PKCS#8 PEM private key
+    pub client_cert: Option<(PathBuf, PathBuf)>,
+}
+
+impl TlsSettings {
+    pub fn is_empty(&self) -> bool {
+        self.ca_cert_path.is_none() && self.client_cert.is_none()
+    }
+}
+
+fn read_pem(path: &Path, what: &str) -> Result<Vec<u8>> {
+    fs::read(path).with_context(|| format!("Failed to read {} {}", what, path.display()))
+}
+
+/// The `reqwest` client API clients send through, with `proxy` and `tls`
+/// applied. Certificate files are read and checked here, so a bad path or
+/// file fails up front rather than on the first request. `no_proxy` without
+/// a proxy to exempt hosts from is rejected rather than silently ignored.
+pub fn build_http_client(proxy: &ProxySettings, tls: &TlsSettings) -> Result<Client> {
+    let set = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    let http_proxy = set(&proxy.http_proxy);
+    let https_proxy = set(&proxy.https_proxy);
+    let no_proxy = set(&proxy.no_proxy);
+    if no_proxy.is_some() && http_proxy.is_none() && https_proxy.is_none() {
+        anyhow::bail!("no_proxy only applies to http_proxy and https_proxy; set one of them too");
+    }
+    let no_proxy = no_proxy.and_then(|hosts| NoProxy::from_string(&hosts));
+
+    let mut builder = Client::builder().timeout(Duration::from_secs(30));
+    if let Some(url) = http_proxy {
+        let proxy = Proxy::http(&url).with_context(|| format!("Invalid http_proxy '{}'", url))?;
+        builder = builder.proxy(proxy.no_proxy(no_proxy.clone()));
+    }
+    if let Some(url) = https_proxy {
+        let proxy = Proxy::https(&url).with_context(|| format!("Invalid https_proxy '{}'", url))?;
+        builder = builder.proxy(proxy.no_proxy(no_proxy));
+    }
+    if let Some(path) = &tls.ca_cert_path {
+        let pem = read_pem(path, "CA certificate")?;
+        let cert = Certificate::from_pem(&pem)
+            .with_context(|| format!("{} is not a PEM certificate", path.display()))?;
+        builder = builder.add_root_certificate(cert);
+    }
+    if let Some((cert_path, key_path)) = &tls.client_cert {
+        let cert = read_pem(cert_path, "client certificate")?;
+        let key = read_pem(key_path, "client key")?;
+        let identity = Identity::from_pkcs8_pem(&cert, &key).with_context(|| {
+            format!(
+                "{} and {} are not a PEM certificate and PKCS#8 key",
+                cert_path.display(),
+                key_path.display()
+            )
+        })?;
+        builder = builder.identity(identity);
+    }
+    builder.build().context("Failed to create HTTP client")
+}
+
+/// Sends requests over the network with `reqwest`
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    /// Send through `client`, usually from [`build_http_client`].
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+impl HttpTransport for ReqwestTransport {
+    fn execute(&self, request: Request) -> ResponseFuture<'_> {
+        Box::pin(async move {
+            let response = self.client.execute(request).await?;
+            let status = response.status();
+            let body = response.bytes().await?;
+            Ok(HttpResponse::new(status, body.to_vec()))
+        })
+    }
+}
+
+/// Answers requests with queued responses and records what was sent
+#[cfg(test)]
+#[derive(Default)]
+pub struct MockTransport {
+    responses: std::sync::Mutex<std::collections::VecDeque<HttpResponse>>,
+    requests: std::sync::Mutex<Vec<Request>>,
+}
+
+#[cfg(test)]
+impl MockTransport {
+    /// Queue a response for the next request.
+    pub fn respond(&self, status: u16, body: &str) {
+        let status = StatusCode::from_u16(status).expect("valid status code");
+        self.responses
+            .lock()
+            .unwrap()
+            .push_back(HttpResponse::new(status, body));
+    }
+
+    /// Requests sent so far, oldest first.
+    pub fn take_requests(&self) -> Vec<Request> {
+        std::mem::take(&mut *self.requests.lock().unwrap())
+    }
+}
+
+#[cfg(test)]
+impl HttpTransport for MockTransport {
+    fn execute(&self, request: Request) -> ResponseFuture<'_> {
+        let url = request.url().to_string();
+        self.requests.lock().unwrap().push(request);
+        let response = self.responses.lock().unwrap().pop_front();
+        Box::pin(async move { response.with_context(|| format!("No mock response for {}", url)) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build_http_client, status_error, CursorGuard, InvalidTokenError, ProxySettings,
+        TlsSettings, MAX_CURSOR_PAGES,
+    };
+    use reqwest::StatusCode;
+    use std::io::{Read, Write};
+
+    #[test]
+    fn cursor_guard_stops_at_repeats_and_the_page_limit() {
+        let mut guard = CursorGuard::new("test");
+        assert!(guard.follow("a"));
+        assert!(guard.follow("b"));
+        assert!(!guard.follow("a"));
+
+        let mut guard = CursorGuard::new("test");
+        let followed = (0..MAX_CURSOR_PAGES * 2)
+            .take_while(|page| guard.follow(&page.to_string()))
+            .count();
+        assert_eq!(followed + 1, MAX_CURSOR_PAGES);
+    }
+
+    #[test]
+    fn rejected_tokens_are_reported_as_invalid() {
+        let classify = |status, body| {
+            status_error(status, body, "Todoist token", anyhow::anyhow!("other"))
+                .downcast_ref::<InvalidTokenError>()
+                .is_some()
+        };
+
+        assert!(classify(StatusCode::UNAUTHORIZED, "Unauthorized"));
+        assert!(classify(StatusCode::FORBIDDEN, "Forbidden"));
+        assert!(!classify(
+            StatusCode::FORBIDDEN,
+            r#"{"message": "API rate limit exceeded"}"#
+        ));
+        assert!(!classify(StatusCode::BAD_GATEWAY, "upstream"));
+    }
+
+    #[tokio::test]
+    async fn configured_proxy_receives_requests() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let proxy_url = format!("http://{}", listener.local_addr().unwrap());
+        let proxy = std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut buf = vec![0; 1024];
+            let read = socket.read(&mut buf).unwrap();
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                .unwrap();
+            String::from_utf8_lossy(&buf[..read]).into_owned()
+        });
+        let proxy_settings = ProxySettings {
+            http_proxy: Some(proxy_url),
+            ..Default::default()
+        };
+        let client = build_http_client(&proxy_settings, &TlsSettings::default()).unwrap();
+
+        let response = client
+            .get("http://todoist.invalid/api/v1/tasks")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.text().await.unwrap(), "ok");
+        let request = proxy.join().unwrap();
+        assert!(
+            request.starts_with("GET http://todoist.invalid/api/v1/tasks HTTP/1.1"),
+            "{}",
+            request
+        );
+    }
+
+    #[test]
+    fn invalid_proxy_url_is_rejected() {
+        let proxy = ProxySettings {
+            https_proxy: Some("not a url".to_string()),
+            ..Default::default()
+        };
+        let result = build_http_client(&proxy, &TlsSettings::default());
+
+        assert!(result.unwrap_err().to_string().contains("https_proxy"));
+    }
+
+    #[test]
+    fn no_proxy_without_a_proxy_is_rejected() {
+        let proxy = ProxySettings {
+            no_proxy: Some("localhost".to_string()),
+            ..Default::default()
+        };
+        let result = build_http_client(&proxy, &TlsSettings::default());
+
+        assert!(result.unwrap_err().to_string().contains("no_proxy"));
+    }
+
+    /// Self-signed certificates under testdata/tls, valid until 2125
+    fn fixture(name: &str) -> std::path::PathBuf {
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("testdata/tls")
+            .join(name)
+    }
+
+    #[test]
+    fn pem_ca_and_client_certificate_are_accepted() {
+        let tls = TlsSettings {
+            ca_cert_path: Some(fixture("ca.pem")),
+            client_cert: Some((fixture("client.pem"), fixture("client-key.pem"))),
+        };
+
+        assert!(build_http_client(&ProxySettings::default(), &tls).is_ok());
+    }
+
+    #[test]
+    fn unreadable_or_invalid_ca_is_reported_with_its_path() {
+        let missing = TlsSettings {
+            ca_cert_path: Some(fixture("missing.pem")),
+            ..Default::default()
+        };
+        let error = build_http_client(&ProxySettings::default(), &missing).unwrap_err();
+        assert!(error.to_string().contains("Failed to read CA certificate"));
+
+        let not_a_cert = TlsSettings {
+            ca_cert_path: Some(fixture("client-key.pem")),
+            ..Default::default()
+        };
+        let error = build_http_client(&ProxySettings::default(), &not_a_cert).unwrap_err();
+        assert!(error.to_string().contains("is not a PEM certificate"));
+    }
+}
@@ -0,0 +1,31 @@
+//! Small JSON files kept in the Todo Tray config directory
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Get the path to `file_name` in the Todo Tray config directory
+pub fn path(file_name: &str) -> Result<PathBuf> {
+    let config_dir = dirs::config_dir().context("Could not find config directory")?;
+    Ok(config_dir.join("todo-tray").join(file_name))
+}
+
+/// Read `path`, falling back to the default when it is missing or corrupt.
+pub fn load_or_default<T: DeserializeOwned + Default>(path: &Path) -> T {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Write `value` to `path`, creating the parent directory if needed.
+pub fn save<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {:?}", parent))?;
+    }
+    let content = serde_json::to_string(value).context("Failed to serialize")?;
+    fs::write(path, content).with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(())
+}
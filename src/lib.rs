@@ -6,15 +6,37 @@
 uniffi::setup_scaffolding!();
 
 mod autostart;
+mod cache;
 mod calendar;
+mod clock;
 mod config;
+mod config_migration;
 mod core;
+mod diagnostics;
 mod github;
+mod github_snooze;
+mod http;
+mod json_store;
 mod linear;
+mod locale;
+mod logging;
+mod next_up;
+mod overdue_alert;
+mod section_order;
+mod snooze;
+mod source_toggles;
 mod task;
+mod time_format;
+mod title;
 mod todoist;
 
 pub use calendar::{CalendarEvent, CalendarEventSection};
-pub use core::{AppState, EventHandler, TodoTrayCore, TodoTrayError};
-pub use github::{GithubNotification, GithubNotificationSection};
+pub use core::{
+    write_default_config, AppState, BulkCompletion, EventHandler, SourceError, TodoTrayCore,
+    TodoTrayError,
+};
+pub use diagnostics::{Diagnostics, SourceHealth};
+pub use github::{GithubNotification, GithubNotificationKind, GithubNotificationSection};
+pub use overdue_alert::OverdueAlert;
 pub use task::{TaskList, TodoTask};
+pub use todoist::{QuickAddResult, TodoistProject};
@@ -1,25 +1,36 @@
 //! Linear API client
 
+use crate::http::{status_error, CursorGuard, HttpTransport};
+use crate::locale::Language;
 use crate::task::TodoTask;
 use anyhow::{Context, Result};
 use reqwest::Client;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::sync::Arc;
 
 const LINEAR_API_URL: &str = "https://api.linear.app/graphql";
 const ASSIGNED_ISSUES_QUERY: &str = r#"
 query AssignedIssues($after: String) {
   viewer {
+    organization {
+      urlKey
+    }
     assignedIssues(first: 50, after: $after) {
       nodes {
         id
         identifier
         title
+        url
         dueDate
+        priority
         state {
           name
           type
         }
+        parent {
+          identifier
+        }
       }
       pageInfo {
         hasNextPage
@@ -29,81 +40,100 @@ query AssignedIssues($after: String) {
   }
 }
 "#;
+const ISSUE_TEAM_STATES_QUERY: &str = r#"
+query IssueTeamStates($id: String!) {
+  issue(id: $id) {
+    team {
+      states {
+        nodes {
+          id
+          name
+          type
+        }
+      }
+    }
+  }
+}
+"#;
+const COMPLETE_ISSUE_MUTATION: &str = r#"
+mutation CompleteIssue($id: String!, $input: IssueUpdateInput!) {
+  issueUpdate(id: $id, input: $input) {
+    success
+  }
+}
+"#;
 
 /// Linear API client
 pub struct LinearClient {
+    /// Builds requests; `http` sends them
     client: Client,
+    http: Arc<dyn HttpTransport>,
     api_token: String,
+    states: Vec<String>,
+    language: Language,
 }
 
 impl LinearClient {
-    pub fn new(api_token: String) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .expect("Failed to create HTTP client");
+    /// `states` lists the workflow state types or names to include.
+    ///
+    /// Requests are sent through `http`, shared with the other clients.
+    pub fn new(api_token: String, states: Vec<String>, http: Arc<dyn HttpTransport>) -> Self {
+        let client = Client::new();
+        Self {
+            client,
+            http,
+            api_token,
+            states,
+            language: Language::default(),
+        }
+    }
 
-        Self { client, api_token }
+    /// Write due dates like "Oct 3" in `language`.
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
     }
 
-    /// Get issues assigned to the current user in "In Progress" state.
+    /// Get issues assigned to the current user in one of the configured states.
+    #[tracing::instrument(name = "linear_fetch", skip_all, fields(issues))]
     pub async fn get_in_progress_issues(&self) -> Result<Vec<TodoTask>> {
         let mut tasks = Vec::new();
         let mut after: Option<String> = None;
+        let mut pages = CursorGuard::new("Linear issues");
 
         loop {
-            let request = GraphqlRequest {
-                query: ASSIGNED_ISSUES_QUERY,
-                variables: GraphqlVariables {
-                    after: after.clone(),
-                },
-            };
-
-            let response = self
-                .client
-                .post(LINEAR_API_URL)
-                .header("Authorization", self.api_token.as_str())
-                .json(&request)
-                .send()
-                .await
-                .context("Failed to connect to Linear API")?;
-
-            if !response.status().is_success() {
-                let status = response.status();
-                let body = response.text().await.unwrap_or_default();
-                return Err(anyhow::anyhow!("Linear API error ({}): {}", status, body));
-            }
-
-            let data: GraphqlResponse = response
-                .json()
-                .await
-                .context("Failed to parse Linear response")?;
-
-            if let Some(errors) = data.errors {
-                let message = errors
-                    .into_iter()
-                    .map(|e| e.message)
-                    .collect::<Vec<_>>()
-                    .join("; ");
-                return Err(anyhow::anyhow!("Linear GraphQL error: {}", message));
-            }
-
-            let payload = data
-                .data
-                .context("Linear response was missing data payload")?;
+            let payload: AssignedIssuesData = self
+                .execute(ASSIGNED_ISSUES_QUERY, AssignedIssuesVariables { after })
+                .await?;
 
+            let org_key = payload.viewer.organization.map(|org| org.url_key);
             let connection = payload.viewer.assigned_issues;
             tasks.extend(
                 connection
                     .nodes
                     .into_iter()
-                    .filter(Self::is_in_progress)
+                    .filter(|issue| matches_state(&issue.state, &self.states))
                     .map(|issue| {
+                        let url = issue.url.filter(|url| !url.is_empty()).or_else(|| {
+                            org_key.as_ref().map(|key| {
+                                format!("https://linear.app/{}/issue/{}", key, issue.identifier)
+                            })
+                        });
                         TodoTask::from_linear(
                             issue.id,
                             issue.identifier,
                             issue.title,
                             issue.due_date,
+                            issue.state.name,
+                            issue.priority,
+                            url,
+                            self.language,
+                        )
+                        .with_linear_parent(
+                            issue
+                                .parent
+                                .as_ref()
+                                .map(|parent| parent.identifier.as_str()),
                         )
                     }),
             );
@@ -112,35 +142,170 @@ impl LinearClient {
                 break;
             }
 
-            after = connection.page_info.end_cursor;
-            if after.is_none() {
-                break;
+            match connection.page_info.end_cursor {
+                Some(cursor) if pages.follow(&cursor) => after = Some(cursor),
+                _ => break,
             }
         }
 
+        tracing::Span::current().record("issues", tasks.len());
         Ok(tasks)
     }
 
-    fn is_in_progress(issue: &LinearIssueNode) -> bool {
-        issue.state.kind.eq_ignore_ascii_case("started")
-            || issue.state.name.eq_ignore_ascii_case("in progress")
+    /// Move an issue to the team's workflow state matching `target_state`
+    /// (a state id, type or name, e.g. "completed").
+    pub async fn complete_issue(&self, issue_id: &str, target_state: &str) -> Result<()> {
+        let payload: IssueTeamStatesData = self
+            .execute(
+                ISSUE_TEAM_STATES_QUERY,
+                IssueVariables {
+                    id: issue_id.to_string(),
+                },
+            )
+            .await?;
+
+        let issue = payload
+            .issue
+            .with_context(|| format!("Linear issue not found: {}", issue_id))?;
+        let state_id = find_target_state(&issue.team.states.nodes, target_state)
+            .map(|state| state.id.clone())
+            .with_context(|| {
+                format!(
+                    "No Linear workflow state matching '{}' for this issue's team",
+                    target_state
+                )
+            })?;
+
+        let payload: IssueUpdateData = self
+            .execute(
+                COMPLETE_ISSUE_MUTATION,
+                complete_issue_variables(issue_id, &state_id),
+            )
+            .await?;
+
+        if !payload.issue_update.success {
+            return Err(anyhow::anyhow!(
+                "Linear did not accept the state change for issue {}",
+                issue_id
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Run a GraphQL operation and return its data payload.
+    async fn execute<V, D>(&self, query: &'static str, variables: V) -> Result<D>
+    where
+        V: Serialize,
+        D: DeserializeOwned,
+    {
+        let response = self
+            .http
+            .send(
+                self.client
+                    .post(LINEAR_API_URL)
+                    .header("Authorization", self.api_token.as_str())
+                    .json(&GraphqlRequest { query, variables }),
+            )
+            .await
+            .context("Failed to connect to Linear API")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(status_error(
+                status,
+                &body,
+                "Linear API key",
+                anyhow::anyhow!("Linear API error ({}): {}", status, body),
+            ));
+        }
+
+        let data: GraphqlResponse<D> =
+            response.json().context("Failed to parse Linear response")?;
+
+        if let Some(errors) = data.errors {
+            let message = errors
+                .into_iter()
+                .map(|e| e.message)
+                .collect::<Vec<_>>()
+                .join("; ");
+            return Err(anyhow::anyhow!("Linear GraphQL error: {}", message));
+        }
+
+        data.data
+            .context("Linear response was missing data payload")
+    }
+}
+
+fn complete_issue_variables(issue_id: &str, state_id: &str) -> IssueUpdateVariables {
+    IssueUpdateVariables {
+        id: issue_id.to_string(),
+        input: IssueUpdateInput {
+            state_id: state_id.to_string(),
+        },
     }
 }
 
+/// Pick the workflow state to complete into: exact id first, then type or name.
+fn find_target_state<'a>(
+    states: &'a [LinearWorkflowState],
+    target: &str,
+) -> Option<&'a LinearWorkflowState> {
+    let target = target.trim();
+    states
+        .iter()
+        .find(|state| state.id == target)
+        .or_else(|| {
+            states
+                .iter()
+                .find(|state| state.kind.eq_ignore_ascii_case(target))
+        })
+        .or_else(|| {
+            states
+                .iter()
+                .find(|state| state.name.eq_ignore_ascii_case(target))
+        })
+}
+
+/// Whether an issue state matches any configured state type or name.
+fn matches_state(state: &LinearIssueState, states: &[String]) -> bool {
+    states.iter().map(|s| s.trim()).any(|wanted| {
+        state.kind.eq_ignore_ascii_case(wanted) || state.name.eq_ignore_ascii_case(wanted)
+    })
+}
+
 #[derive(Debug, Serialize)]
-struct GraphqlRequest {
+struct GraphqlRequest<V> {
     query: &'static str,
-    variables: GraphqlVariables,
+    variables: V,
 }
 
 #[derive(Debug, Serialize)]
-struct GraphqlVariables {
+struct AssignedIssuesVariables {
     after: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+struct IssueVariables {
+    id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct IssueUpdateVariables {
+    id: String,
+    input: IssueUpdateInput,
+}
+
+#[derive(Debug, Serialize)]
+struct IssueUpdateInput {
+    #[serde(rename = "stateId")]
+    state_id: String,
+}
+
 #[derive(Debug, Deserialize)]
-struct GraphqlResponse {
-    data: Option<GraphqlData>,
+struct GraphqlResponse<D> {
+    data: Option<D>,
     errors: Option<Vec<GraphqlError>>,
 }
 
@@ -150,16 +315,64 @@ struct GraphqlError {
 }
 
 #[derive(Debug, Deserialize)]
-struct GraphqlData {
+struct AssignedIssuesData {
     viewer: LinearViewer,
 }
 
+#[derive(Debug, Deserialize)]
+struct IssueTeamStatesData {
+    issue: Option<LinearIssueWithTeam>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinearIssueWithTeam {
+    team: LinearTeam,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinearTeam {
+    states: LinearWorkflowStateConnection,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinearWorkflowStateConnection {
+    nodes: Vec<LinearWorkflowState>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinearWorkflowState {
+    id: String,
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueUpdateData {
+    #[serde(rename = "issueUpdate")]
+    issue_update: IssueUpdatePayload,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueUpdatePayload {
+    success: bool,
+}
+
 #[derive(Debug, Deserialize)]
 struct LinearViewer {
+    /// Names the workspace in issue links
+    #[serde(default)]
+    organization: Option<LinearOrganization>,
     #[serde(rename = "assignedIssues")]
     assigned_issues: LinearIssueConnection,
 }
 
+#[derive(Debug, Deserialize)]
+struct LinearOrganization {
+    #[serde(rename = "urlKey")]
+    url_key: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct LinearIssueConnection {
     nodes: Vec<LinearIssueNode>,
@@ -180,9 +393,22 @@ struct LinearIssueNode {
     id: String,
     identifier: String,
     title: String,
+    #[serde(default)]
+    url: Option<String>,
     #[serde(rename = "dueDate")]
     due_date: Option<String>,
+    /// 0 = no priority, 1 = urgent … 4 = low
+    #[serde(default)]
+    priority: u8,
     state: LinearIssueState,
+    /// Set for sub-issues
+    #[serde(default)]
+    parent: Option<LinearIssueParent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinearIssueParent {
+    identifier: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -191,3 +417,202 @@ struct LinearIssueState {
     #[serde(rename = "type")]
     kind: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        complete_issue_variables, find_target_state, matches_state, GraphqlRequest, LinearClient,
+        LinearIssueState, LinearWorkflowState, COMPLETE_ISSUE_MUTATION,
+    };
+    use crate::http::{InvalidTokenError, MockTransport};
+    use std::sync::Arc;
+
+    fn state(name: &str, kind: &str) -> LinearIssueState {
+        LinearIssueState {
+            name: name.to_string(),
+            kind: kind.to_string(),
+        }
+    }
+
+    #[test]
+    fn matches_configured_states_by_type_or_name_case_insensitively() {
+        let states = vec!["STARTED".to_string(), "in review".to_string()];
+
+        assert!(matches_state(&state("In Progress", "started"), &states));
+        assert!(matches_state(&state("In Review", "started"), &states));
+        assert!(matches_state(&state("In Review", "unstarted"), &states));
+        assert!(!matches_state(&state("Todo", "unstarted"), &states));
+    }
+
+    #[test]
+    fn default_state_only_matches_started_issues() {
+        let states = crate::config::default_linear_states();
+
+        assert!(matches_state(&state("In Progress", "started"), &states));
+        assert!(!matches_state(&state("Backlog", "backlog"), &states));
+    }
+
+    #[test]
+    fn complete_issue_mutation_sends_state_id_input() {
+        let request = GraphqlRequest {
+            query: COMPLETE_ISSUE_MUTATION,
+            variables: complete_issue_variables("issue-1", "state-done"),
+        };
+
+        let body = serde_json::to_value(&request).unwrap();
+
+        assert!(body["query"]
+            .as_str()
+            .unwrap()
+            .contains("issueUpdate(id: $id, input: $input)"));
+        assert_eq!(
+            body["variables"],
+            serde_json::json!({ "id": "issue-1", "input": { "stateId": "state-done" } })
+        );
+    }
+
+    #[test]
+    fn finds_target_state_by_id_type_or_name() {
+        let states = vec![
+            LinearWorkflowState {
+                id: "s1".to_string(),
+                name: "In Progress".to_string(),
+                kind: "started".to_string(),
+            },
+            LinearWorkflowState {
+                id: "s2".to_string(),
+                name: "Done".to_string(),
+                kind: "completed".to_string(),
+            },
+        ];
+
+        assert_eq!(find_target_state(&states, "completed").unwrap().id, "s2");
+        assert_eq!(find_target_state(&states, "done").unwrap().id, "s2");
+        assert_eq!(find_target_state(&states, "s1").unwrap().id, "s1");
+        assert!(find_target_state(&states, "canceled").is_none());
+    }
+
+    #[tokio::test]
+    async fn repeated_end_cursor_stops_pagination() {
+        let http = Arc::new(MockTransport::default());
+        for id in ["1", "2", "3"] {
+            http.respond(
+                200,
+                &format!(
+                    r#"{{"data": {{"viewer": {{"assignedIssues": {{
+                        "nodes": [{{"id": "{}", "identifier": "ENG-{}", "title": "Loop",
+                                   "dueDate": null, "priority": 0,
+                                   "state": {{"name": "In Progress", "type": "started"}}}}],
+                        "pageInfo": {{"hasNextPage": true, "endCursor": "stuck"}}
+                    }}}}}}}}"#,
+                    id, id
+                ),
+            );
+        }
+        let client = LinearClient::new(
+            "token".to_string(),
+            vec!["started".to_string()],
+            http.clone(),
+        );
+
+        let tasks = client.get_in_progress_issues().await.unwrap();
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(http.take_requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn issue_url_becomes_the_task_link() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(
+            200,
+            r#"{"data": {"viewer": {"organization": {"urlKey": "acme"}, "assignedIssues": {
+                "nodes": [
+                    {"id": "1", "identifier": "ENG-1", "title": "Linked",
+                     "url": "https://linear.app/acme/issue/ENG-1/linked",
+                     "dueDate": null, "priority": 0,
+                     "state": {"name": "In Progress", "type": "started"}},
+                    {"id": "2", "identifier": "ENG-2", "title": "Unlinked",
+                     "dueDate": null, "priority": 0,
+                     "state": {"name": "In Progress", "type": "started"}}
+                ],
+                "pageInfo": {"hasNextPage": false, "endCursor": null}
+            }}}}"#,
+        );
+        let client = LinearClient::new("token".to_string(), vec!["started".to_string()], http);
+
+        let tasks = client.get_in_progress_issues().await.unwrap();
+
+        assert_eq!(
+            tasks[0].open_url.as_deref(),
+            Some("https://linear.app/acme/issue/ENG-1/linked")
+        );
+        assert_eq!(
+            tasks[1].open_url.as_deref(),
+            Some("https://linear.app/acme/issue/ENG-2")
+        );
+    }
+
+    #[tokio::test]
+    async fn issue_without_a_url_or_organization_has_no_link() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(
+            200,
+            r#"{"data": {"viewer": {"assignedIssues": {
+                "nodes": [
+                    {"id": "1", "identifier": "ENG-1", "title": "Unlinked",
+                     "dueDate": null, "priority": 0,
+                     "state": {"name": "In Progress", "type": "started"}}
+                ],
+                "pageInfo": {"hasNextPage": false, "endCursor": null}
+            }}}}"#,
+        );
+        let client = LinearClient::new("token".to_string(), vec!["started".to_string()], http);
+
+        let tasks = client.get_in_progress_issues().await.unwrap();
+
+        assert_eq!(tasks[0].open_url, None);
+    }
+
+    #[tokio::test]
+    async fn sub_issues_show_their_parent_identifier() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(
+            200,
+            r#"{"data": {"viewer": {"assignedIssues": {
+                "nodes": [
+                    {"id": "1", "identifier": "PROJ-34", "title": "Child",
+                     "dueDate": null, "priority": 0,
+                     "state": {"name": "In Progress", "type": "started"},
+                     "parent": {"identifier": "PROJ-12"}},
+                    {"id": "2", "identifier": "PROJ-35", "title": "Standalone",
+                     "dueDate": null, "priority": 0,
+                     "state": {"name": "In Progress", "type": "started"},
+                     "parent": null}
+                ],
+                "pageInfo": {"hasNextPage": false, "endCursor": null}
+            }}}}"#,
+        );
+        let client = LinearClient::new("token".to_string(), vec!["started".to_string()], http);
+
+        let tasks = client.get_in_progress_issues().await.unwrap();
+
+        assert_eq!(tasks[0].content, "PROJ-12 ▸ [PROJ-34] Child");
+        assert_eq!(tasks[1].content, "[PROJ-35] Standalone");
+    }
+
+    #[tokio::test]
+    async fn unauthorized_response_reports_an_invalid_key() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(
+            401,
+            r#"{"errors": [{"message": "Authentication required"}]}"#,
+        );
+        let client = LinearClient::new("key".to_string(), Vec::new(), http);
+
+        let error = client.get_in_progress_issues().await.unwrap_err();
+
+        assert!(error.downcast_ref::<InvalidTokenError>().is_some());
+        assert_eq!(error.to_string(), "Linear API key is invalid or expired");
+    }
+}
@@ -0,0 +1,225 @@
+//! Translations for the relative times and labels shown next to items, and
+//! for the tooltip and spoken summaries
+//!
+//! Every string has an English template; other languages may leave some out,
+//! in which case the English one is used. Weekday and month names are
+//! complete for every language.
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Language of the display strings, set with `language` in config.toml
+#[derive(uniffi::Enum, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "sv")]
+    Swedish,
+}
+
+/// A translatable string. Templates may contain `{n}` for a number; the
+/// summary sentences also use `{items}` and `{time}`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Text {
+    /// Task without a due date
+    NoDueDate,
+    /// Task due earlier today, less than an hour ago
+    Overdue,
+    Yesterday,
+    /// Upcoming task due within the minute
+    Now,
+    InMinutes,
+    InHours,
+    MinutesAgo,
+    HoursAgo,
+    DaysAgo,
+    /// GitHub notification without an update time
+    Recent,
+    /// All-day calendar event
+    AllDay,
+    /// Tooltip summary counts, e.g. "2 overdue · 5 today"
+    OverdueCount,
+    TodayCount,
+    InProgressCount,
+    Notification,
+    Notifications,
+    NothingDue,
+    /// Spoken summary counts, e.g. "You have 2 overdue tasks"
+    OverdueTask,
+    OverdueTasks,
+    TaskToday,
+    TasksToday,
+    IssueInProgress,
+    IssuesInProgress,
+    GithubNotification,
+    GithubNotifications,
+    /// Timed calendar event that marks the time as busy
+    Meeting,
+    Meetings,
+    /// All-day or free calendar event
+    Event,
+    Events,
+    /// Start of the only meeting, appended to its count
+    MeetingAt,
+    /// Start of the next of several meetings, appended to their count
+    NextMeetingAt,
+    /// Joins the last two counts of the summary
+    And,
+    /// Joins the last count of three or more
+    ListAnd,
+    YouHave,
+    AllCaughtUp,
+}
+
+impl Language {
+    /// The template for `text`, falling back to English when this language
+    /// has none.
+    pub fn template(self, text: Text) -> &'static str {
+        let translated = match self {
+            Language::English => None,
+            Language::Swedish => swedish(text),
+        };
+        translated.unwrap_or_else(|| english(text))
+    }
+
+    /// `text` with `{n}` replaced by `n`.
+    pub fn format(self, text: Text, n: i64) -> String {
+        self.template(text).replace("{n}", &n.to_string())
+    }
+
+    /// Short weekday name, e.g. "Mon"
+    pub fn weekday(self, weekday: Weekday) -> &'static str {
+        let names = match self {
+            Language::English => ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+            Language::Swedish => ["mån", "tis", "ons", "tors", "fre", "lör", "sön"],
+        };
+        names[weekday.num_days_from_monday() as usize]
+    }
+
+    /// Day of the month with a short month name, e.g. "Oct 3"
+    pub fn short_date(self, date: NaiveDate) -> String {
+        let month = date.month0() as usize;
+        match self {
+            Language::English => format!("{} {}", ENGLISH_MONTHS[month], date.day()),
+            Language::Swedish => format!("{} {}", date.day(), SWEDISH_MONTHS[month]),
+        }
+    }
+}
+
+const ENGLISH_MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+const SWEDISH_MONTHS: [&str; 12] = [
+    "jan", "feb", "mars", "apr", "maj", "juni", "juli", "aug", "sep", "okt", "nov", "dec",
+];
+
+fn english(text: Text) -> &'static str {
+    match text {
+        Text::NoDueDate => "no due date",
+        Text::Overdue => "overdue",
+        Text::Yesterday => "Yesterday",
+        Text::Now => "now",
+        Text::InMinutes => "in {n}m",
+        Text::InHours => "in {n}h",
+        Text::MinutesAgo => "{n}m ago",
+        Text::HoursAgo => "{n}h ago",
+        Text::DaysAgo => "{n}d ago",
+        Text::Recent => "recent",
+        Text::AllDay => "All day",
+        Text::OverdueCount => "{n} overdue",
+        Text::TodayCount => "{n} today",
+        Text::InProgressCount => "{n} in progress",
+        Text::Notification => "{n} notification",
+        Text::Notifications => "{n} notifications",
+        Text::NothingDue => "Nothing due",
+        Text::OverdueTask => "{n} overdue task",
+        Text::OverdueTasks => "{n} overdue tasks",
+        Text::TaskToday => "{n} task today",
+        Text::TasksToday => "{n} tasks today",
+        Text::IssueInProgress => "{n} issue in progress",
+        Text::IssuesInProgress => "{n} issues in progress",
+        Text::GithubNotification => "{n} GitHub notification",
+        Text::GithubNotifications => "{n} GitHub notifications",
+        Text::Meeting => "{n} meeting",
+        Text::Meetings => "{n} meetings",
+        Text::Event => "{n} event",
+        Text::Events => "{n} events",
+        Text::MeetingAt => " at {time}",
+        Text::NextMeetingAt => ", the next at {time}",
+        Text::And => " and ",
+        Text::ListAnd => ", and ",
+        Text::YouHave => "You have {items}.",
+        Text::AllCaughtUp => "All caught up.",
+    }
+}
+
+/// Partial, so untranslated strings show in English
+fn swedish(text: Text) -> Option<&'static str> {
+    match text {
+        Text::Yesterday => Some("Igår"),
+        Text::Now => Some("nu"),
+        Text::MinutesAgo => Some("{n} min sedan"),
+        Text::HoursAgo => Some("{n} tim sedan"),
+        Text::DaysAgo => Some("{n} d sedan"),
+        Text::AllDay => Some("Heldag"),
+        Text::OverdueCount => Some("{n} försenade"),
+        Text::TodayCount => Some("{n} idag"),
+        Text::InProgressCount => Some("{n} pågående"),
+        Text::Notification => Some("{n} avisering"),
+        Text::Notifications => Some("{n} aviseringar"),
+        Text::NothingDue => Some("Inget att göra"),
+        Text::OverdueTask => Some("{n} försenad uppgift"),
+        Text::OverdueTasks => Some("{n} försenade uppgifter"),
+        Text::TaskToday => Some("{n} uppgift idag"),
+        Text::TasksToday => Some("{n} uppgifter idag"),
+        Text::IssueInProgress => Some("{n} pågående ärende"),
+        Text::IssuesInProgress => Some("{n} pågående ärenden"),
+        Text::GithubNotification => Some("{n} GitHub-avisering"),
+        Text::GithubNotifications => Some("{n} GitHub-aviseringar"),
+        Text::Meeting => Some("{n} möte"),
+        Text::Meetings => Some("{n} möten"),
+        Text::Event => Some("{n} händelse"),
+        Text::Events => Some("{n} händelser"),
+        Text::MeetingAt => Some(" kl. {time}"),
+        Text::NextMeetingAt => Some(", nästa kl. {time}"),
+        Text::And | Text::ListAnd => Some(" och "),
+        Text::YouHave => Some("Du har {items}."),
+        Text::AllCaughtUp => Some("Allt klart."),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_translations_fall_back_to_english() {
+        assert_eq!(Language::Swedish.format(Text::HoursAgo, 3), "3 tim sedan");
+        assert_eq!(Language::Swedish.format(Text::InHours, 2), "in 2h");
+        assert_eq!(Language::English.format(Text::HoursAgo, 3), "3h ago");
+    }
+
+    #[test]
+    fn weekdays_and_dates_follow_the_language() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 3).unwrap();
+
+        assert_eq!(Language::English.weekday(Weekday::Mon), "Mon");
+        assert_eq!(Language::Swedish.weekday(Weekday::Thu), "tors");
+        assert_eq!(Language::English.short_date(date), "Oct 3");
+        assert_eq!(Language::Swedish.short_date(date), "3 okt");
+    }
+
+    #[test]
+    fn parses_config_values() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            language: Language,
+        }
+
+        let parsed: Wrapper = toml::from_str("language = \"sv\"").unwrap();
+        assert_eq!(parsed.language, Language::Swedish);
+    }
+}
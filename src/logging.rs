@@ -0,0 +1,49 @@
+//! Opt-in log output
+//!
+//! The core reports what it does through `tracing`. Nothing is printed unless
+//! `TODO_TRAY_LOG` names a level, e.g. `TODO_TRAY_LOG=debug`.
+
+use std::sync::Once;
+use tracing::Level;
+
+/// Environment variable holding the most verbose level to print
+pub const LOG_LEVEL_ENV: &str = "TODO_TRAY_LOG";
+
+/// Print events to stderr at the level from `TODO_TRAY_LOG`, if it is set.
+///
+/// Safe to call more than once; only the first call has any effect.
+pub fn init() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        let Some(level) = std::env::var(LOG_LEVEL_ENV)
+            .ok()
+            .and_then(|value| parse_level(&value))
+        else {
+            return;
+        };
+        // The host app may already have installed a subscriber.
+        let _ = tracing_subscriber::fmt()
+            .with_max_level(level)
+            .with_writer(std::io::stderr)
+            .try_init();
+    });
+}
+
+/// Parse a level name such as "info" or "DEBUG"; unknown names turn logging off.
+fn parse_level(value: &str) -> Option<Level> {
+    value.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_level;
+    use tracing::Level;
+
+    #[test]
+    fn log_level_is_read_case_insensitively() {
+        assert_eq!(parse_level("debug"), Some(Level::DEBUG));
+        assert_eq!(parse_level(" WARN "), Some(Level::WARN));
+        assert_eq!(parse_level("off"), None);
+        assert_eq!(parse_level(""), None);
+    }
+}
@@ -0,0 +1,191 @@
+//! The single most pressing item across tasks and meetings
+
+use crate::calendar::CalendarEventSection;
+use crate::task::TaskList;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// What kind of item `NextUpItem` is, in tie-break order
+#[derive(uniffi::Enum, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum NextUpKind {
+    OverdueTask,
+    /// A task due at a time later today
+    Task,
+    Meeting,
+}
+
+/// The earliest thing needing attention, for a "what's next" line
+#[derive(uniffi::Record, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct NextUpItem {
+    pub kind: NextUpKind,
+    pub title: String,
+    /// When the task is due or the meeting starts (RFC3339)
+    pub when: String,
+    /// `when` as shown elsewhere in the menu, e.g. "14:30" or "2h ago"
+    pub display_time: String,
+}
+
+/// Pick the earliest of overdue tasks, today's remaining timed tasks and
+/// meetings that haven't started yet. Items due at the same instant go by
+/// `NextUpKind` order, so tasks beat meetings.
+pub fn pick(
+    tasks: &TaskList,
+    calendar: &[CalendarEventSection],
+    now: DateTime<Utc>,
+) -> Option<NextUpItem> {
+    let overdue = tasks
+        .overdue
+        .iter()
+        .map(|task| (NextUpKind::OverdueTask, task));
+    let later_today = tasks
+        .today
+        .iter()
+        .filter(|task| !task.all_day)
+        .map(|task| (NextUpKind::Task, task));
+    let task_items = overdue.chain(later_today).filter_map(|(kind, task)| {
+        let due = parse(task.due_datetime.as_deref()?)?;
+        (kind == NextUpKind::OverdueTask || due > now).then(|| {
+            (
+                due,
+                NextUpItem {
+                    kind,
+                    title: task.content.clone(),
+                    when: due.to_rfc3339(),
+                    display_time: task.display_time.clone(),
+                },
+            )
+        })
+    });
+    let meetings = calendar
+        .iter()
+        .flat_map(|section| &section.events)
+        .filter(|event| !event.is_all_day && !event.is_free)
+        .filter_map(|event| {
+            let start = parse(event.start_at.as_deref()?)?;
+            (start > now).then(|| {
+                (
+                    start,
+                    NextUpItem {
+                        kind: NextUpKind::Meeting,
+                        title: event.title.clone(),
+                        when: start.to_rfc3339(),
+                        display_time: event.display_time.clone(),
+                    },
+                )
+            })
+        });
+
+    task_items
+        .chain(meetings)
+        .min_by_key(|(when, item)| (*when, item.kind))
+        .map(|(_, item)| item)
+}
+
+fn parse(rfc3339: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(rfc3339)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pick, NextUpKind};
+    use crate::calendar::{CalendarEvent, CalendarEventSection};
+    use crate::locale::Language;
+    use crate::task::{TaskList, TodoTask, TodoistTask};
+    use crate::time_format::TimeFormat;
+    use chrono::{DateTime, Duration, Local, Utc};
+
+    /// Todoist task due at `due`, or on its local day when `all_day`
+    fn task(id: &str, due: DateTime<Utc>, all_day: bool) -> TodoTask {
+        let date = due.with_timezone(&Local).date_naive().to_string();
+        let due = if all_day {
+            serde_json::json!({"date": date})
+        } else {
+            serde_json::json!({"date": date, "datetime": due.to_rfc3339()})
+        };
+        let todoist: TodoistTask =
+            serde_json::from_value(serde_json::json!({"id": id, "content": id, "due": due}))
+                .unwrap();
+        TodoTask::from_todoist(
+            todoist,
+            TimeFormat::default(),
+            Duration::minutes(60),
+            false,
+            Language::English,
+        )
+    }
+
+    fn meetings(starts: &[(&str, DateTime<Utc>)]) -> Vec<CalendarEventSection> {
+        vec![CalendarEventSection {
+            account_name: "Work".to_string(),
+            events: starts
+                .iter()
+                .map(|(title, start)| CalendarEvent {
+                    event_id: title.to_string(),
+                    title: title.to_string(),
+                    start_at: Some(start.to_rfc3339()),
+                    end_at: None,
+                    display_time: String::new(),
+                    is_all_day: false,
+                    location: None,
+                    my_response: None,
+                    is_free: false,
+                    open_url: None,
+                })
+                .collect(),
+            remaining_count: 0,
+        }]
+    }
+
+    #[test]
+    fn earliest_item_wins_across_sources() {
+        let now = Utc::now();
+        let tasks = TaskList {
+            today: vec![
+                task("all day", now + Duration::minutes(5), true),
+                task("call", now + Duration::hours(2), false),
+                task("missed", now - Duration::minutes(5), false),
+            ],
+            ..Default::default()
+        };
+        let calendar = meetings(&[
+            ("started", now - Duration::minutes(10)),
+            ("standup", now + Duration::hours(1)),
+        ]);
+
+        let next = pick(&tasks, &calendar, now).unwrap();
+        assert_eq!(next.kind, NextUpKind::Meeting);
+        assert_eq!(next.title, "standup");
+
+        let calendar = meetings(&[("review", now + Duration::hours(3))]);
+        let next = pick(&tasks, &calendar, now).unwrap();
+        assert_eq!((next.kind, next.title.as_str()), (NextUpKind::Task, "call"));
+    }
+
+    #[test]
+    fn overdue_tasks_come_first_and_tasks_win_ties() {
+        let now = Utc::now();
+        let at = now + Duration::minutes(30);
+        let calendar = meetings(&[("sync", at)]);
+        let tasks = TaskList {
+            overdue: vec![
+                task("late", now - Duration::days(1), false),
+                task("later", now - Duration::hours(1), false),
+            ],
+            ..Default::default()
+        };
+
+        let next = pick(&tasks, &calendar, now).unwrap();
+        assert_eq!(next.title, "late");
+        assert_eq!(next.kind, NextUpKind::OverdueTask);
+
+        let tied = TaskList {
+            today: vec![task("tied", at, false)],
+            ..Default::default()
+        };
+        assert_eq!(pick(&tied, &calendar, now).unwrap().title, "tied");
+
+        assert_eq!(pick(&TaskList::default(), &[], now), None);
+    }
+}
@@ -0,0 +1,142 @@
+//! Notifications for tasks that just became overdue, louder for higher priorities
+
+use crate::task::TodoTask;
+use std::collections::HashSet;
+
+/// A notification about tasks that became overdue since the last refresh
+#[derive(uniffi::Record, Clone, Debug, PartialEq, Eq)]
+pub struct OverdueAlert {
+    /// Notification title, naming the top priority when it's high
+    pub title: String,
+    /// Most pressing first
+    pub task_names: Vec<String>,
+    /// 1 (urgent) to 4 (low), of the most pressing task
+    pub top_priority: u8,
+    /// A p1 task is among them; worth breaking through Focus
+    pub is_urgent: bool,
+}
+
+/// Priority for comparisons, with unprioritized tasks counted as low.
+fn effective_priority(priority: u8) -> u8 {
+    match priority {
+        0 => 4,
+        priority => priority,
+    }
+}
+
+/// Whether a task at `priority` (1 urgent to 4 low, 0 none) may notify.
+/// Without `min_priority` every task does.
+pub fn meets_min_priority(priority: u8, min_priority: Option<u8>) -> bool {
+    min_priority.is_none_or(|min| effective_priority(priority) <= min)
+}
+
+/// Notification title for `count` overdue tasks whose most pressing has `top_priority`.
+pub fn title(count: usize, top_priority: u8) -> String {
+    let level = match top_priority {
+        1 => Some("Urgent"),
+        2 => Some("High-Priority"),
+        _ => None,
+    };
+    match (count, level) {
+        (1, Some(level)) => format!("{} Task Overdue", level),
+        (1, None) => "Task Overdue".to_string(),
+        (_, Some(level)) => format!("{} Tasks Overdue, {} First", count, level),
+        (_, None) => format!("{} Tasks Overdue", count),
+    }
+}
+
+/// Overdue tasks already notified about, so each one only alerts once
+/// while it stays overdue.
+#[derive(Debug, Default)]
+pub struct OverdueAlertTracker {
+    notified: HashSet<String>,
+}
+
+impl OverdueAlertTracker {
+    /// Record the current overdue tasks and return an alert for the ones that
+    /// weren't overdue before and meet `min_priority`.
+    pub fn new_alert(
+        &mut self,
+        overdue: &[TodoTask],
+        min_priority: Option<u8>,
+    ) -> Option<OverdueAlert> {
+        let key = |task: &TodoTask| format!("{}:{}", task.source, task.id);
+        // A task rescheduled and overdue again should alert again.
+        let current: HashSet<String> = overdue.iter().map(key).collect();
+        self.notified.retain(|id| current.contains(id));
+
+        let mut fresh: Vec<&TodoTask> = overdue
+            .iter()
+            .filter(|task| self.notified.insert(key(task)))
+            .filter(|task| meets_min_priority(task.priority, min_priority))
+            .collect();
+        fresh.sort_by_key(|task| effective_priority(task.priority));
+        let top_priority = effective_priority(fresh.first()?.priority);
+        Some(OverdueAlert {
+            title: title(fresh.len(), top_priority),
+            task_names: fresh.iter().map(|task| task.content.clone()).collect(),
+            top_priority,
+            is_urgent: top_priority == 1,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locale::Language;
+    use crate::task::TodoistTask;
+    use crate::time_format::TimeFormat;
+
+    /// Overdue Todoist task at `priority`, 1 (p1) to 4 (p4)
+    fn task(id: &str, priority: u8) -> TodoTask {
+        let todoist: TodoistTask = serde_json::from_value(serde_json::json!({
+            "id": id,
+            "content": format!("Task {}", id),
+            "due": {"date": "2020-01-01"},
+            "priority": 5 - priority,
+        }))
+        .unwrap();
+        TodoTask::from_todoist(
+            todoist,
+            TimeFormat::default(),
+            chrono::Duration::minutes(60),
+            false,
+            Language::English,
+        )
+    }
+
+    #[test]
+    fn min_priority_keeps_lower_priorities_quiet() {
+        assert!(meets_min_priority(4, None));
+        assert!(meets_min_priority(0, None));
+        assert!(meets_min_priority(1, Some(2)));
+        assert!(meets_min_priority(2, Some(2)));
+        assert!(!meets_min_priority(3, Some(2)));
+        assert!(!meets_min_priority(0, Some(3)));
+    }
+
+    #[test]
+    fn title_names_the_top_priority() {
+        assert_eq!(title(1, 1), "Urgent Task Overdue");
+        assert_eq!(title(3, 2), "3 Tasks Overdue, High-Priority First");
+        assert_eq!(title(1, 4), "Task Overdue");
+        assert_eq!(title(2, 3), "2 Tasks Overdue");
+    }
+
+    #[test]
+    fn each_overdue_task_alerts_once_most_pressing_first() {
+        let mut tracker = OverdueAlertTracker::default();
+
+        let alert = tracker
+            .new_alert(&[task("a", 4), task("b", 1), task("c", 3)], Some(3))
+            .unwrap();
+        assert_eq!(alert.task_names, vec!["Task b", "Task c"]);
+        assert_eq!(alert.title, "2 Tasks Overdue, Urgent First");
+        assert!(alert.is_urgent);
+
+        assert_eq!(tracker.new_alert(&[task("b", 1)], Some(3)), None);
+        let again = tracker.new_alert(&[task("b", 1), task("c", 3)], Some(3));
+        assert_eq!(again.unwrap().task_names, vec!["Task c"]);
+    }
+}
@@ -0,0 +1,118 @@
+//! The order of the menu's task, GitHub and calendar blocks
+
+use serde::Serialize;
+
+/// A block of the menu
+#[derive(uniffi::Enum, Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum MenuSectionKind {
+    /// Todoist and Linear tasks
+    Tasks,
+    Github,
+    Calendar,
+}
+
+impl MenuSectionKind {
+    /// Every block, in the default order
+    pub const ALL: [Self; 3] = [Self::Tasks, Self::Github, Self::Calendar];
+
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "tasks" => Some(Self::Tasks),
+            "github" => Some(Self::Github),
+            "calendar" => Some(Self::Calendar),
+            _ => None,
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Tasks => "Tasks",
+            Self::Github => "GitHub",
+            Self::Calendar => "Calendar",
+        }
+    }
+}
+
+/// One menu block, listed in the order the menu should show them
+#[derive(uniffi::Record, Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SectionDescriptor {
+    pub kind: MenuSectionKind,
+    pub title: String,
+    /// Items in the block
+    pub count: u32,
+}
+
+impl SectionDescriptor {
+    pub fn new(kind: MenuSectionKind, count: u32) -> Self {
+        Self {
+            kind,
+            title: kind.title().to_string(),
+            count,
+        }
+    }
+}
+
+/// Read `section_order` names such as "calendar" into a full order.
+///
+/// Blocks left out follow in their default order. Unknown and repeated names
+/// are errors.
+pub fn parse(names: &[String]) -> Result<Vec<MenuSectionKind>, String> {
+    let mut order = Vec::new();
+    for name in names {
+        let kind = MenuSectionKind::parse(name).ok_or_else(|| {
+            format!(
+                "Unknown section '{}' in section_order (expected tasks, github or calendar)",
+                name
+            )
+        })?;
+        if order.contains(&kind) {
+            return Err(format!(
+                "Section '{}' is listed twice in section_order",
+                name
+            ));
+        }
+        order.push(kind);
+    }
+    for kind in MenuSectionKind::ALL {
+        if !order.contains(&kind) {
+            order.push(kind);
+        }
+    }
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, MenuSectionKind};
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn configured_sections_come_first_and_the_rest_follow() {
+        assert_eq!(
+            parse(&names(&["calendar", "tasks", "github"])).unwrap(),
+            vec![
+                MenuSectionKind::Calendar,
+                MenuSectionKind::Tasks,
+                MenuSectionKind::Github
+            ]
+        );
+        assert_eq!(
+            parse(&names(&[" GitHub "])).unwrap(),
+            vec![
+                MenuSectionKind::Github,
+                MenuSectionKind::Tasks,
+                MenuSectionKind::Calendar
+            ]
+        );
+        assert_eq!(parse(&[]).unwrap(), MenuSectionKind::ALL.to_vec());
+    }
+
+    #[test]
+    fn unknown_and_repeated_sections_are_rejected() {
+        assert!(parse(&names(&["tasks", "email"])).is_err());
+        assert!(parse(&names(&["tasks", "Tasks"])).is_err());
+    }
+}
@@ -0,0 +1,365 @@
+//! Snooze option parsing
+//!
+//! Options are either offsets from the current due date ("30m", "2h", "1d")
+//! or absolute local times ("tomorrow-9am", "tonight-18:00").
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, Utc, Weekday};
+
+/// Time of day given to date-only tasks snoozed by less than a day
+const DEFAULT_DAY_START: (u32, u32) = (9, 0);
+
+/// A task due date, with or without a time of day
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Due {
+    Date(NaiveDate),
+    DateTime(DateTime<Utc>),
+}
+
+/// Where a snooze option moves a task's due date
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnoozeTarget {
+    /// Push the current due date forward
+    Offset(Duration),
+    /// A local time of day, `days_ahead` days from today
+    At { days_ahead: i64, time: NaiveTime },
+}
+
+impl SnoozeTarget {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let value = input.trim().to_lowercase();
+        if let Some((day, time)) = value.split_once('-') {
+            let days_ahead = match day {
+                "today" | "tonight" => Some(0),
+                "tomorrow" => Some(1),
+                _ => None,
+            };
+            if let Some(days_ahead) = days_ahead {
+                let time = parse_time_of_day(time)
+                    .ok_or_else(|| format!("Invalid snooze time in '{}'", input))?;
+                return Ok(SnoozeTarget::At { days_ahead, time });
+            }
+        }
+
+        parse_snooze_duration(input).map(SnoozeTarget::Offset)
+    }
+
+    /// Compute the new due date. Offsets need the current `due`; absolute
+    /// targets ignore it, and a same-day time that has already passed rolls
+    /// over to tomorrow.
+    ///
+    /// Whole-day offsets move by local calendar days and keep the time of day.
+    /// Shorter offsets give date-only tasks a concrete time, counted from the
+    /// due day's morning or from now, whichever is later.
+    ///
+    /// With `skip_weekends`, whole-day offsets and "tomorrow" targets that land
+    /// on a Saturday or Sunday move on to Monday.
+    pub fn resolve(
+        &self,
+        due: Option<Due>,
+        now: DateTime<Local>,
+        skip_weekends: bool,
+    ) -> Option<Due> {
+        let day = |date: NaiveDate| {
+            if skip_weekends {
+                next_working_day(date)
+            } else {
+                date
+            }
+        };
+        match *self {
+            SnoozeTarget::Offset(duration)
+                if duration.num_seconds() % Duration::days(1).num_seconds() == 0 =>
+            {
+                let days = Duration::days(duration.num_days());
+                match due? {
+                    Due::Date(date) => Some(Due::Date(day(date + days))),
+                    Due::DateTime(due) => {
+                        let due = due.with_timezone(&Local);
+                        let moved = day(due.date_naive() + days)
+                            .and_time(due.time())
+                            .and_local_timezone(Local)
+                            .earliest()?;
+                        Some(Due::DateTime(moved.with_timezone(&Utc)))
+                    }
+                }
+            }
+            SnoozeTarget::Offset(duration) => match due? {
+                Due::DateTime(due) => Some(Due::DateTime(due + duration)),
+                Due::Date(date) => {
+                    let (hour, minute) = DEFAULT_DAY_START;
+                    let morning = date
+                        .and_time(NaiveTime::from_hms_opt(hour, minute, 0)?)
+                        .and_local_timezone(Local)
+                        .earliest()?;
+                    let start = morning.max(now);
+                    Some(Due::DateTime(start.with_timezone(&Utc) + duration))
+                }
+            },
+            SnoozeTarget::At { days_ahead, time } => {
+                let at = |date: NaiveDate| date.and_time(time).and_local_timezone(Local).earliest();
+                let today = now.date_naive();
+                let mut target = if days_ahead > 0 {
+                    at(day(today + Duration::days(days_ahead)))?
+                } else {
+                    at(today)?
+                };
+                if days_ahead == 0 && target <= now {
+                    target = at(today + Duration::days(1))?;
+                }
+                Some(Due::DateTime(target.with_timezone(&Utc)))
+            }
+        }
+    }
+}
+
+/// Move a Saturday or Sunday on to the following Monday.
+fn next_working_day(date: NaiveDate) -> NaiveDate {
+    match date.weekday() {
+        Weekday::Sat => date + Duration::days(2),
+        Weekday::Sun => date + Duration::days(1),
+        _ => date,
+    }
+}
+
+/// Parse "9am", "9:30pm" or "18:00"
+fn parse_time_of_day(value: &str) -> Option<NaiveTime> {
+    let meridiem = if let Some(rest) = value.strip_suffix("am") {
+        Some((rest, 0))
+    } else {
+        value.strip_suffix("pm").map(|rest| (rest, 12))
+    };
+
+    let Some((clock, offset)) = meridiem else {
+        return NaiveTime::parse_from_str(value, "%H:%M").ok();
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        None => (clock.parse::<u32>().ok()?, 0),
+    };
+    if !(1..=12).contains(&hour) {
+        return None;
+    }
+    NaiveTime::from_hms_opt(hour % 12 + offset, minute, 0)
+}
+
+fn parse_snooze_duration(input: &str) -> Result<Duration, String> {
+    let value = input.trim().to_lowercase();
+    // Split on the last char, not the last byte, so multibyte input can't panic.
+    let Some((unit_start, _)) = value.char_indices().last().filter(|(i, _)| *i > 0) else {
+        return Err(format!("Invalid snooze duration '{}'", input));
+    };
+
+    let (number_part, unit_part) = value.split_at(unit_start);
+    let amount: i64 = number_part
+        .parse()
+        .map_err(|_| format!("Invalid snooze duration '{}'", input))?;
+    if amount <= 0 {
+        return Err(format!("Snooze duration must be positive: '{}'", input));
+    }
+
+    match unit_part {
+        "m" => Ok(Duration::minutes(amount)),
+        "h" => Ok(Duration::hours(amount)),
+        "d" => Ok(Duration::days(amount)),
+        _ => Err(format!(
+            "Unsupported snooze duration unit in '{}'. Use m, h, or d.",
+            input
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn local(date: (i32, u32, u32), hour: u32, minute: u32) -> DateTime<Local> {
+        NaiveDate::from_ymd_opt(date.0, date.1, date.2)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+            .and_local_timezone(Local)
+            .earliest()
+            .unwrap()
+    }
+
+    fn resolve_local(label: &str, now: DateTime<Local>) -> DateTime<Local> {
+        match SnoozeTarget::parse(label)
+            .unwrap()
+            .resolve(None, now, false)
+        {
+            Some(Due::DateTime(dt)) => dt.with_timezone(&Local),
+            other => panic!("expected a datetime, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn snooze_duration_with_multibyte_unit_is_rejected_without_panicking() {
+        assert!(parse_snooze_duration("30é").is_err());
+        assert!(parse_snooze_duration("5🍅").is_err());
+        assert!(parse_snooze_duration("🍅").is_err());
+        assert!(parse_snooze_duration("ñ").is_err());
+    }
+
+    #[test]
+    fn snooze_duration_parses_supported_units() {
+        assert_eq!(
+            parse_snooze_duration(" 30M ").unwrap(),
+            Duration::minutes(30)
+        );
+        assert_eq!(parse_snooze_duration("2h").unwrap(), Duration::hours(2));
+        assert_eq!(parse_snooze_duration("1d").unwrap(), Duration::days(1));
+        assert!(parse_snooze_duration("d").is_err());
+    }
+
+    #[test]
+    fn parses_absolute_targets() {
+        assert_eq!(
+            SnoozeTarget::parse("tomorrow-9am").unwrap(),
+            SnoozeTarget::At {
+                days_ahead: 1,
+                time: NaiveTime::from_hms_opt(9, 0, 0).unwrap()
+            }
+        );
+        assert_eq!(
+            SnoozeTarget::parse("Tonight-18:00").unwrap(),
+            SnoozeTarget::At {
+                days_ahead: 0,
+                time: NaiveTime::from_hms_opt(18, 0, 0).unwrap()
+            }
+        );
+        assert_eq!(
+            SnoozeTarget::parse("tomorrow-12:30pm").unwrap(),
+            SnoozeTarget::At {
+                days_ahead: 1,
+                time: NaiveTime::from_hms_opt(12, 30, 0).unwrap()
+            }
+        );
+        assert!(SnoozeTarget::parse("tomorrow-13pm").is_err());
+        assert!(SnoozeTarget::parse("someday-9am").is_err());
+    }
+
+    #[test]
+    fn tomorrow_morning_from_various_times() {
+        let expected = local((2026, 3, 2), 9, 0);
+
+        assert_eq!(
+            resolve_local("tomorrow-9am", local((2026, 3, 1), 8, 0)),
+            expected
+        );
+        assert_eq!(
+            resolve_local("tomorrow-9am", local((2026, 3, 1), 15, 0)),
+            expected
+        );
+        assert_eq!(
+            resolve_local("tomorrow-9am", local((2026, 3, 1), 23, 59)),
+            expected
+        );
+        // Just after midnight, "tomorrow" is the next calendar day.
+        assert_eq!(
+            resolve_local("tomorrow-9am", local((2026, 3, 2), 0, 5)),
+            local((2026, 3, 3), 9, 0)
+        );
+    }
+
+    #[test]
+    fn tonight_rolls_over_once_the_time_has_passed() {
+        assert_eq!(
+            resolve_local("tonight-18:00", local((2026, 3, 1), 12, 0)),
+            local((2026, 3, 1), 18, 0)
+        );
+        assert_eq!(
+            resolve_local("tonight-18:00", local((2026, 3, 1), 19, 0)),
+            local((2026, 3, 2), 18, 0)
+        );
+    }
+
+    #[test]
+    fn offsets_push_the_current_due_date() {
+        let due = Utc::now();
+        let target = SnoozeTarget::parse("30m").unwrap();
+
+        assert_eq!(
+            target.resolve(Some(Due::DateTime(due)), Local::now(), false),
+            Some(Due::DateTime(due + Duration::minutes(30)))
+        );
+        assert_eq!(target.resolve(None, Local::now(), false), None);
+    }
+
+    #[test]
+    fn date_only_task_moves_by_whole_days() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 1).unwrap();
+        let target = SnoozeTarget::parse("1d").unwrap();
+
+        assert_eq!(
+            target.resolve(Some(Due::Date(date)), local((2026, 3, 1), 15, 0), false),
+            Some(Due::Date(NaiveDate::from_ymd_opt(2026, 3, 2).unwrap()))
+        );
+    }
+
+    #[test]
+    fn date_only_task_snoozed_by_minutes_gets_a_concrete_time() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let target = SnoozeTarget::parse("30m").unwrap();
+
+        // Due on a future day: half an hour after that morning.
+        assert_eq!(
+            target.resolve(Some(Due::Date(date)), local((2026, 3, 1), 15, 0), false),
+            Some(Due::DateTime(
+                local((2026, 3, 2), 9, 30).with_timezone(&Utc)
+            ))
+        );
+        // Due today, later than the morning: half an hour from now.
+        assert_eq!(
+            target.resolve(Some(Due::Date(date)), local((2026, 3, 2), 14, 0), false),
+            Some(Due::DateTime(
+                local((2026, 3, 2), 14, 30).with_timezone(&Utc)
+            ))
+        );
+    }
+
+    #[test]
+    fn day_snoozes_skip_weekends_when_asked() {
+        let friday = NaiveDate::from_ymd_opt(2026, 3, 6).unwrap();
+        let saturday = NaiveDate::from_ymd_opt(2026, 3, 7).unwrap();
+        let monday = NaiveDate::from_ymd_opt(2026, 3, 9).unwrap();
+        let one_day = SnoozeTarget::parse("1d").unwrap();
+        let now = local((2026, 3, 6), 15, 0);
+
+        assert_eq!(
+            one_day.resolve(Some(Due::Date(friday)), now, true),
+            Some(Due::Date(monday))
+        );
+        assert_eq!(
+            one_day.resolve(Some(Due::Date(friday)), now, false),
+            Some(Due::Date(saturday))
+        );
+        let friday_evening = local((2026, 3, 6), 17, 30).with_timezone(&Utc);
+        assert_eq!(
+            one_day.resolve(Some(Due::DateTime(friday_evening)), now, true),
+            Some(Due::DateTime(
+                local((2026, 3, 9), 17, 30).with_timezone(&Utc)
+            ))
+        );
+        assert_eq!(
+            SnoozeTarget::parse("tomorrow-9am")
+                .unwrap()
+                .resolve(None, now, true),
+            Some(Due::DateTime(local((2026, 3, 9), 9, 0).with_timezone(&Utc)))
+        );
+    }
+
+    #[test]
+    fn sub_day_snoozes_ignore_weekends() {
+        let late_friday = local((2026, 3, 6), 23, 50).with_timezone(&Utc);
+
+        assert_eq!(
+            SnoozeTarget::parse("30m").unwrap().resolve(
+                Some(Due::DateTime(late_friday)),
+                Local::now(),
+                true
+            ),
+            Some(Due::DateTime(late_friday + Duration::minutes(30)))
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! Sources turned off from the menu, kept across restarts
+//!
+//! A disabled source keeps its config; it is just not fetched and its
+//! section stays empty until it is turned back on.
+
+use crate::json_store;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Every source that can be turned off
+pub const SOURCES: [&str; 4] = ["todoist", "linear", "github", "calendar"];
+
+/// Names of the disabled sources
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DisabledSources {
+    sources: BTreeSet<String>,
+}
+
+impl DisabledSources {
+    /// Turn `source` on or off, returning whether that changed anything.
+    pub fn set(&mut self, source: &str, enabled: bool) -> bool {
+        if enabled {
+            self.sources.remove(source)
+        } else {
+            self.sources.insert(source.to_string())
+        }
+    }
+
+    pub fn contains(&self, source: &str) -> bool {
+        self.sources.contains(source)
+    }
+
+    /// Disabled sources in name order
+    pub fn names(&self) -> Vec<String> {
+        self.sources.iter().cloned().collect()
+    }
+}
+
+/// Get the path to the source toggle file
+pub fn store_path() -> Result<PathBuf> {
+    json_store::path("disabled-sources.json")
+}
+
+/// Load toggles, with everything enabled when the file is missing or corrupt.
+/// Names that aren't sources any more are dropped.
+pub fn load(path: &Path) -> DisabledSources {
+    let mut disabled: DisabledSources = json_store::load_or_default(path);
+    disabled
+        .sources
+        .retain(|source| SOURCES.contains(&source.as_str()));
+    disabled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn toggles_round_trip_through_disk_without_unknown_sources() {
+        let path = std::env::temp_dir()
+            .join(format!("todo-tray-sources-{}", std::process::id()))
+            .join("disabled-sources.json");
+        let mut disabled = DisabledSources::default();
+        assert!(disabled.set("github", false));
+        assert!(!disabled.set("github", false));
+        disabled.set("jira", false);
+
+        json_store::save(&path, &disabled).unwrap();
+        let loaded = load(&path);
+
+        assert_eq!(loaded.names(), vec!["github"]);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -1,13 +1,22 @@
 //! Task data structures for FFI
 
-use chrono::{DateTime, Local, Utc};
-use serde::Deserialize;
+use crate::clock::{Clock, SystemClock};
+use crate::locale::{Language, Text};
+use crate::time_format::TimeFormat;
+use chrono::{DateTime, Datelike, Local, NaiveDateTime, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// A task from Todoist
-#[derive(uniffi::Record, Clone, Debug)]
+#[derive(uniffi::Record, Clone, Debug, Serialize, Deserialize)]
 pub struct TodoTask {
     pub id: String,
+    /// Title as shown in the menu
     pub content: String,
+    /// Title as stored in the source, before any `TitleCleaning`
+    #[serde(default)]
+    pub raw_content: String,
     pub source: String,
     pub can_complete: bool,
     pub open_url: Option<String>,
@@ -15,18 +24,154 @@ pub struct TodoTask {
     pub is_overdue: bool,
     pub is_today: bool,
     pub is_tomorrow: bool,
+    /// Due on a date with no time of day; `due_datetime` is then end of that day
+    pub all_day: bool,
+    /// Has a due time within the configured "due soon" window and isn't overdue yet
+    pub is_due_soon: bool,
     pub display_time: String,
+    /// Time of day a timed Todoist task is due ("14:30"), kept alongside a
+    /// countdown `display_time`; `None` for all-day and undated tasks
+    #[serde(default)]
+    pub due_time: Option<String>,
+    /// 1 (urgent) to 4 (low); 0 when the source has no priority set
+    pub priority: u8,
+    pub project_name: Option<String>,
+    /// Todoist project id; `None` for other sources
+    #[serde(default)]
+    pub project_id: Option<String>,
+    /// Todoist section within `project_name`, if the task is in one
+    #[serde(default)]
+    pub section_name: Option<String>,
+    /// Collaborator a Todoist task in a shared project is assigned to
+    #[serde(default)]
+    pub assignee_name: Option<String>,
+    /// Collaborator who assigned the task to `assignee_name`
+    #[serde(default)]
+    pub assigner_name: Option<String>,
+    /// Earliest Todoist reminder still to come (RFC3339), with `show_reminders`
+    #[serde(default)]
+    pub next_reminder: Option<String>,
+    /// Todoist label names; always empty for other sources
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Number of comments on the task; 0 for sources without comments
+    #[serde(default)]
+    pub comment_count: u32,
+    /// Parent task for Todoist sub-tasks
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// How long a time-blocked task takes, in minutes (days count as 1440)
+    #[serde(default)]
+    pub duration_minutes: Option<u32>,
+    /// Todoist task that comes back with a new due date when completed
+    #[serde(default)]
+    pub is_recurring: bool,
+    /// Nesting depth under parents shown in the same group; 0 for top-level tasks
+    #[serde(default)]
+    pub indent_level: u8,
+    /// Menu keyboard shortcut (⌘1–⌘9) for completing this task
+    #[serde(default)]
+    pub shortcut: Option<u8>,
+    /// How pressing the task is; the date flags above agree with it
+    #[serde(default)]
+    pub urgency: TaskUrgency,
+    /// Todoist deadline (YYYY-MM-DD), the date the task must be done by
+    /// rather than when it's planned for
+    #[serde(default)]
+    pub deadline: Option<String>,
+    /// The deadline is today or has passed
+    #[serde(default)]
+    pub is_deadline_near: bool,
+}
+
+/// How pressing a task is, most urgent first
+#[derive(
+    uniffi::Enum,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
+pub enum TaskUrgency {
+    Overdue,
+    /// Due within the "due soon" window
+    DueSoon,
+    Today,
+    Tomorrow,
+    /// Due after tomorrow, or not due at all
+    #[default]
+    Later,
+    /// Linear issues being worked on
+    InProgress,
+}
+
+impl TaskUrgency {
+    fn from_flags(is_overdue: bool, is_due_soon: bool, is_today: bool, is_tomorrow: bool) -> Self {
+        if is_overdue {
+            Self::Overdue
+        } else if is_due_soon {
+            Self::DueSoon
+        } else if is_today {
+            Self::Today
+        } else if is_tomorrow {
+            Self::Tomorrow
+        } else {
+            Self::Later
+        }
+    }
 }
 
 impl TodoTask {
-    pub fn from_todoist(task: TodoistTask) -> Self {
-        let due_datetime = task.due.and_then(|d| parse_due_date(&d.date));
-        let (is_overdue, is_today, is_tomorrow) = date_flags(&due_datetime);
+    /// With `countdown`, today's upcoming timed tasks show as "in 2h" rather
+    /// than their time of day, which stays in `due_time`.
+    pub fn from_todoist(
+        task: TodoistTask,
+        time_format: TimeFormat,
+        due_soon_window: chrono::Duration,
+        countdown: bool,
+        language: Language,
+    ) -> Self {
+        let has_due_time = task.due.as_ref().is_some_and(TodoistDue::has_time);
+        let due_datetime = task.due.as_ref().and_then(TodoistDue::due_at);
+        let all_day = due_datetime.is_some() && !has_due_time;
+        let clock = SystemClock;
+        let (is_overdue, is_today, is_tomorrow) = date_flags(&due_datetime, &clock);
+        let is_due_soon = has_due_time
+            && due_datetime
+                .as_ref()
+                .is_some_and(|due| is_due_soon(due, clock.now(), due_soon_window));
 
-        let display_time = format_display_time(&due_datetime, is_overdue);
+        let duration_minutes = task.duration.as_ref().and_then(TodoistDuration::minutes);
+        let is_recurring = task.due.as_ref().is_some_and(|due| due.is_recurring);
+        let display_time = match (due_datetime, duration_minutes) {
+            (Some(start), Some(minutes)) if has_due_time && !is_overdue => {
+                format_time_range(&start.with_timezone(&Local), minutes, time_format)
+            }
+            _ => format_display_time(
+                &due_datetime,
+                is_overdue,
+                countdown && has_due_time && is_today,
+                time_format,
+                language,
+                &clock,
+            ),
+        };
+        let deadline = task.deadline.map(|deadline| deadline.date);
+        let (deadline_passed, deadline_today, _) =
+            date_flags(&deadline.as_deref().and_then(parse_due_date), &clock);
+        let due_time = due_datetime
+            .filter(|_| has_due_time)
+            .map(|due| time_format.format_time(&due.with_timezone(&Local)));
 
         Self {
             id: task.id,
+            raw_content: task.content.clone(),
             content: task.content,
             source: "todoist".to_string(),
             can_complete: true,
@@ -35,33 +180,180 @@ impl TodoTask {
             is_overdue,
             is_today,
             is_tomorrow,
+            all_day,
+            is_due_soon,
             display_time,
+            due_time,
+            // The API's 1 is Todoist's default p4, i.e. no priority set
+            priority: match task.priority {
+                priority @ 2..=4 => 5 - priority,
+                _ => 0,
+            },
+            project_name: None,
+            project_id: task.project_id,
+            section_name: None,
+            assignee_name: None,
+            assigner_name: None,
+            next_reminder: None,
+            labels: task.labels,
+            comment_count: task.comment_count,
+            parent_id: task.parent_id,
+            duration_minutes,
+            is_recurring,
+            indent_level: 0,
+            shortcut: None,
+            urgency: TaskUrgency::from_flags(is_overdue, is_due_soon, is_today, is_tomorrow),
+            deadline,
+            is_deadline_near: deadline_passed || deadline_today,
         }
     }
 
+    /// Raise `urgency` to match the deadline when that is closer than the
+    /// due date. Grouping still follows the due date.
+    pub fn apply_deadline_urgency(&mut self) {
+        let deadline = self.deadline.as_deref().and_then(parse_due_date);
+        let (is_overdue, is_today, is_tomorrow) = date_flags(&deadline, &SystemClock);
+        let by_deadline = TaskUrgency::from_flags(is_overdue, false, is_today, is_tomorrow);
+        self.urgency = self.urgency.min(by_deadline);
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn from_linear(
         id: String,
         identifier: String,
         title: String,
         due_date: Option<String>,
+        state_name: String,
+        priority: u8,
+        url: Option<String>,
+        language: Language,
     ) -> Self {
         let due_datetime = due_date.as_deref().and_then(parse_due_date);
-        let (is_overdue, is_today, is_tomorrow) = date_flags(&due_datetime);
-        let display_time = format_linear_display_time(&due_datetime);
+        let (is_overdue, is_today, is_tomorrow) = date_flags(&due_datetime, &SystemClock);
+        let display_time = format_linear_display_time(&due_datetime, &state_name, language);
+        // Linear due dates carry no time of day
+        let all_day = due_datetime.is_some();
 
+        let content = format!("[{}] {}", identifier, title);
         Self {
             id,
-            content: format!("[{}] {}", identifier, title),
+            raw_content: content.clone(),
+            content,
             source: "linear".to_string(),
-            can_complete: false,
-            open_url: Some(format!("https://linear.app/issue/{}", identifier)),
+            can_complete: true,
+            open_url: url.filter(|url| !url.is_empty()),
             due_datetime: due_datetime.map(|dt| dt.to_rfc3339()),
             is_overdue,
             is_today,
             is_tomorrow,
+            all_day,
+            is_due_soon: false,
             display_time,
+            due_time: None,
+            priority,
+            project_name: None,
+            project_id: None,
+            section_name: None,
+            assignee_name: None,
+            assigner_name: None,
+            next_reminder: None,
+            labels: Vec::new(),
+            comment_count: 0,
+            parent_id: None,
+            duration_minutes: None,
+            is_recurring: false,
+            indent_level: 0,
+            shortcut: None,
+            urgency: TaskUrgency::InProgress,
+            deadline: None,
+            is_deadline_near: false,
+        }
+    }
+
+    /// Put a Linear sub-issue's parent in front of its title,
+    /// e.g. "PROJ-12 ▸ [PROJ-34] Title".
+    pub fn with_linear_parent(mut self, parent_identifier: Option<&str>) -> Self {
+        if let Some(parent) = parent_identifier.filter(|parent| !parent.is_empty()) {
+            self.content = format!("{} ▸ {}", parent, self.content);
+            self.raw_content = self.content.clone();
+        }
+        self
+    }
+
+    /// Rebuild `content` from `raw_content` with `cleaning` applied.
+    pub fn clean_content(&mut self, cleaning: TitleCleaning) {
+        // Tasks cached before `raw_content` existed only have `content`
+        if self.raw_content.is_empty() {
+            self.raw_content = self.content.clone();
+        }
+        self.content = clean_title(&self.raw_content, cleaning);
+    }
+}
+
+/// How task titles are tidied up for the narrow menu
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TitleCleaning {
+    /// Reduce `[text](url)` links to their text and drop `**`/`__` emphasis
+    pub strip_markdown: bool,
+    /// Drop emoji at the start of the title
+    pub strip_leading_emoji: bool,
+}
+
+/// Apply `cleaning` to a title, keeping the original if nothing would be left.
+fn clean_title(title: &str, cleaning: TitleCleaning) -> String {
+    let mut cleaned = title.to_string();
+    if cleaning.strip_markdown {
+        cleaned = strip_markdown_links(&cleaned)
+            .replace("**", "")
+            .replace("__", "");
+    }
+    if cleaning.strip_leading_emoji {
+        cleaned = cleaned
+            .trim_start_matches(|c: char| is_emoji(c) || c.is_whitespace())
+            .to_string();
+    }
+    let cleaned = cleaned.trim();
+    if cleaned.is_empty() {
+        title.to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// Replace each `[text](url)` with `text`; anything else is kept as-is.
+fn strip_markdown_links(title: &str) -> String {
+    let mut result = String::new();
+    let mut rest = title;
+    while let Some(open) = rest.find('[') {
+        let after_open = &rest[open + 1..];
+        let link = after_open.find("](").and_then(|close| {
+            let after_text = &after_open[close + 2..];
+            after_text
+                .find(')')
+                .map(|end| (&after_open[..close], &after_text[end + 1..]))
+        });
+        match link {
+            Some((text, remaining)) if !text.contains('[') => {
+                result.push_str(&rest[..open]);
+                result.push_str(text);
+                rest = remaining;
+            }
+            _ => {
+                result.push_str(&rest[..=open]);
+                rest = after_open;
+            }
         }
     }
+    result.push_str(rest);
+    result
+}
+
+/// Emoji pictographs plus the joiners and modifiers that build them up
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0xFE0F | 0x200D | 0x20E3
+    )
 }
 
 /// Parse a due date from Todoist API
@@ -89,53 +381,109 @@ fn parse_due_date(date_str: &str) -> Option<DateTime<Utc>> {
     }
 }
 
-/// Format the display time for a task (24-hour clock)
-fn format_display_time(due_datetime: &Option<DateTime<Utc>>, is_overdue: bool) -> String {
+/// Format the display time for a task, as a countdown ("in 2h") when
+/// `countdown` is set and the task isn't overdue
+fn format_display_time(
+    due_datetime: &Option<DateTime<Utc>>,
+    is_overdue: bool,
+    countdown: bool,
+    time_format: TimeFormat,
+    language: Language,
+    clock: &dyn Clock,
+) -> String {
     if let Some(dt) = due_datetime {
         let local = dt.with_timezone(&Local);
         if is_overdue {
-            let now = Local::now();
-            let diff = now.signed_duration_since(local);
-            if diff.num_days() > 0 {
-                format!("{}d ago", diff.num_days())
-            } else if diff.num_hours() > 0 {
-                format!("{}h ago", diff.num_hours())
-            } else {
-                "overdue".to_string()
-            }
+            format_overdue_time(&local, &clock.local_now(), language)
+        } else if countdown {
+            format_countdown(&local, &clock.local_now(), language)
         } else {
-            local.format("%H:%M").to_string()
+            time_format.format_time(&local)
         }
     } else {
-        "no due date".to_string()
+        language.template(Text::NoDueDate).to_string()
+    }
+}
+
+/// How long ago an overdue task was due: "3h ago" or "overdue" earlier
+/// today, "Yesterday", the weekday within the last week, else the date.
+fn format_overdue_time(due: &DateTime<Local>, now: &DateTime<Local>, language: Language) -> String {
+    let days = (now.date_naive() - due.date_naive()).num_days();
+    match days {
+        ..=0 => {
+            let hours = now.signed_duration_since(due).num_hours();
+            if hours > 0 {
+                language.format(Text::HoursAgo, hours)
+            } else {
+                language.template(Text::Overdue).to_string()
+            }
+        }
+        1 => language.template(Text::Yesterday).to_string(),
+        2..=6 => language.weekday(due.weekday()).to_string(),
+        _ => language.short_date(due.date_naive()),
+    }
+}
+
+/// Time left until an upcoming task: "now" within the minute, "in 25m"
+/// within the hour, else "in 2h" rounded to the nearest hour.
+fn format_countdown(due: &DateTime<Local>, now: &DateTime<Local>, language: Language) -> String {
+    let minutes = due.signed_duration_since(now).num_minutes();
+    match minutes {
+        ..=0 => language.template(Text::Now).to_string(),
+        1..=59 => language.format(Text::InMinutes, minutes),
+        _ => language.format(Text::InHours, (minutes + 30) / 60),
     }
 }
 
-fn format_linear_display_time(due_datetime: &Option<DateTime<Utc>>) -> String {
+/// Start and end time of a time-blocked task, e.g. "14:00-14:30".
+///
+/// Blocks of whole days show the start time and the day count instead, since
+/// an end time on another day reads as earlier than the start.
+fn format_time_range(start: &DateTime<Local>, minutes: u32, time_format: TimeFormat) -> String {
+    const MINUTES_PER_DAY: u32 = 24 * 60;
+
+    if minutes >= MINUTES_PER_DAY && minutes.is_multiple_of(MINUTES_PER_DAY) {
+        return format!(
+            "{} ({}d)",
+            time_format.format_time(start),
+            minutes / MINUTES_PER_DAY
+        );
+    }
+    let end = *start + chrono::Duration::minutes(minutes.into());
+    format!(
+        "{}-{}",
+        time_format.format_time(start),
+        time_format.format_time(&end)
+    )
+}
+
+/// Linear issues show their due date, or the workflow state name when undated
+fn format_linear_display_time(
+    due_datetime: &Option<DateTime<Utc>>,
+    state_name: &str,
+    language: Language,
+) -> String {
     due_datetime
         .as_ref()
-        .map(|dt| dt.with_timezone(&Local).format("%b %-d").to_string())
-        .unwrap_or_else(|| "In progress".to_string())
+        .map(|dt| language.short_date(dt.with_timezone(&Local).date_naive()))
+        .unwrap_or_else(|| state_name.to_string())
 }
 
-fn date_flags(due_datetime: &Option<DateTime<Utc>>) -> (bool, bool, bool) {
-    let is_overdue = due_datetime
-        .as_ref()
-        .map(|dt| dt < &Utc::now())
-        .unwrap_or(false);
+/// Whether a due time is overdue, today or tomorrow as of `clock`'s now
+fn date_flags(due_datetime: &Option<DateTime<Utc>>, clock: &dyn Clock) -> (bool, bool, bool) {
+    let now = clock.now();
+    let today = clock.local_now().date_naive();
+    let is_overdue = due_datetime.as_ref().map(|dt| dt < &now).unwrap_or(false);
 
     let is_today = due_datetime
         .as_ref()
-        .map(|dt| {
-            let today = Local::now().date_naive();
-            dt.with_timezone(&Local).date_naive() == today
-        })
+        .map(|dt| dt.with_timezone(&Local).date_naive() == today)
         .unwrap_or(false);
 
     let is_tomorrow = due_datetime
         .as_ref()
         .map(|dt| {
-            let tomorrow = Local::now().date_naive() + chrono::Duration::days(1);
+            let tomorrow = today + chrono::Duration::days(1);
             dt.with_timezone(&Local).date_naive() == tomorrow
         })
         .unwrap_or(false);
@@ -143,27 +491,255 @@ fn date_flags(due_datetime: &Option<DateTime<Utc>>) -> (bool, bool, bool) {
     (is_overdue, is_today, is_tomorrow)
 }
 
+/// Whether `due` is still ahead of `now` but within `window`
+fn is_due_soon(due: &DateTime<Utc>, now: DateTime<Utc>, window: chrono::Duration) -> bool {
+    *due > now && *due - now <= window
+}
+
 /// Task from Todoist API
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct TodoistTask {
     pub id: String,
     pub content: String,
     pub due: Option<TodoistDue>,
+    #[serde(default)]
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Older API versions call this `note_count`
+    #[serde(default, alias = "note_count")]
+    pub comment_count: u32,
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    #[serde(default)]
+    pub duration: Option<TodoistDuration>,
+    /// Collaborator the task is assigned to, only set in shared projects
+    #[serde(default)]
+    pub assignee_id: Option<String>,
+    /// Collaborator who assigned the task
+    #[serde(default)]
+    pub assigner_id: Option<String>,
+    /// 4 for p1 (urgent) down to 1 for p4, the default; the reverse of
+    /// `TodoTask::priority`
+    #[serde(default = "default_todoist_priority")]
+    pub priority: u8,
+    /// Section within the project, if any
+    #[serde(default)]
+    pub section_id: Option<String>,
+    #[serde(default)]
+    pub deadline: Option<TodoistDeadline>,
+}
+
+fn default_todoist_priority() -> u8 {
+    1
+}
+
+/// Deadline from Todoist API, separate from the due date
+#[derive(Debug, Clone, Deserialize)]
+pub struct TodoistDeadline {
+    /// YYYY-MM-DD
+    pub date: String,
+}
+
+/// Time block length from Todoist API
+#[derive(Debug, Clone, Deserialize)]
+pub struct TodoistDuration {
+    pub amount: u32,
+    /// "minute" or "day"
+    pub unit: String,
+}
+
+impl TodoistDuration {
+    /// Length in minutes, or `None` for an unknown unit or an empty block.
+    fn minutes(&self) -> Option<u32> {
+        let minutes = match self.unit.as_str() {
+            "minute" => self.amount,
+            "day" => self.amount.checked_mul(24 * 60)?,
+            _ => return None,
+        };
+        (minutes > 0).then_some(minutes)
+    }
 }
 
 /// Due date from Todoist API
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct TodoistDue {
     pub date: String,
+    /// Exact due time; UTC for tasks fixed to `timezone`, otherwise floating local time
+    #[serde(default)]
+    pub datetime: Option<String>,
+    /// IANA zone of a fixed-timezone due time, e.g. "Europe/Stockholm"
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub is_recurring: bool,
+    /// The due date as the user typed it, e.g. "every monday at 9"
+    #[serde(default)]
+    pub string: Option<String>,
+}
+
+impl TodoistDue {
+    /// Whether the task is due at a time of day rather than on a whole day
+    fn has_time(&self) -> bool {
+        self.datetime.is_some() || self.date.contains('T')
+    }
+
+    /// When the task is due. An explicit `datetime` without an offset is read
+    /// in `timezone` when that names a known zone, and as local time otherwise.
+    pub(crate) fn due_at(&self) -> Option<DateTime<Utc>> {
+        let Some(datetime) = self.datetime.as_deref() else {
+            return parse_due_date(&self.date);
+        };
+        if let Ok(fixed) = DateTime::parse_from_rfc3339(datetime) {
+            return Some(fixed.with_timezone(&Utc));
+        }
+        let naive = NaiveDateTime::parse_from_str(datetime, "%Y-%m-%dT%H:%M:%S%.f").ok()?;
+        match self
+            .timezone
+            .as_deref()
+            .and_then(|tz| tz.parse::<Tz>().ok())
+        {
+            Some(tz) => naive
+                .and_local_timezone(tz)
+                .earliest()
+                .map(|due| due.with_timezone(&Utc)),
+            None => naive
+                .and_local_timezone(Local)
+                .earliest()
+                .map(|due| due.with_timezone(&Utc)),
+        }
+    }
 }
 
 /// Grouped task lists
-#[derive(uniffi::Record, Clone, Debug, Default)]
+#[derive(uniffi::Record, Clone, Debug, Default, Serialize, Deserialize)]
 pub struct TaskList {
     pub overdue: Vec<TodoTask>,
     pub today: Vec<TodoTask>,
     pub tomorrow: Vec<TodoTask>,
     pub in_progress: Vec<TodoTask>,
+    /// Todoist tasks from a custom filter that aren't due by tomorrow, or at all
+    #[serde(default)]
+    pub other: Vec<TodoTask>,
+}
+
+impl TaskList {
+    /// Todoist tasks in menu order: overdue, today, tomorrow, then other
+    pub fn todoist_tasks(&self) -> impl Iterator<Item = &TodoTask> {
+        self.overdue
+            .iter()
+            .chain(self.today.iter())
+            .chain(self.tomorrow.iter())
+            .chain(self.other.iter())
+    }
+}
+
+/// How tasks are arranged into menu sections
+#[derive(uniffi::Enum, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupingMode {
+    /// Overdue, Today, Tomorrow, In Progress
+    #[default]
+    ByDue,
+    /// One section per Todoist project, with In Progress last
+    ByProject,
+}
+
+/// Where tasks due today without a time of day go within the today group
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AllDayPosition {
+    /// Before today's timed tasks
+    First,
+    /// After today's timed tasks
+    #[default]
+    Last,
+}
+
+/// How much of each section the menu shows
+#[derive(uniffi::Enum, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MenuLayout {
+    /// Every task, with its time column
+    #[default]
+    Detailed,
+    /// Headers with counts and the first few tasks of each section
+    Compact,
+}
+
+/// Tasks shown per section in the compact layout
+pub const COMPACT_SECTION_LIMIT: usize = 3;
+
+/// Number of tasks that get a ⌘1–⌘9 shortcut
+const MAX_SHORTCUTS: u8 = 9;
+
+/// A titled group of tasks, in display order
+#[derive(uniffi::Record, Clone, Debug, Serialize)]
+pub struct TaskSection {
+    pub title: String,
+    pub tasks: Vec<TodoTask>,
+    /// Tasks left out of `tasks` by the compact layout or `max_items_per_section`
+    pub hidden_count: u32,
+}
+
+/// Section title for tasks without a project
+pub const INBOX_SECTION_TITLE: &str = "Inbox";
+
+/// Which Todoist tasks to keep, by label name (case-insensitive) and project
+#[derive(Clone, Debug, Default)]
+pub struct LabelFilter {
+    /// When non-empty, keep only tasks with at least one of these labels
+    pub include: Vec<String>,
+    /// Drop tasks with any of these labels
+    pub exclude: Vec<String>,
+    /// Drop tasks in these projects, by name (case-insensitive) or id
+    pub exclude_projects: Vec<String>,
+}
+
+impl LabelFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Self {
+        let normalize = |labels: &[String]| {
+            labels
+                .iter()
+                .map(|label| label.trim().to_lowercase())
+                .filter(|label| !label.is_empty())
+                .collect()
+        };
+        Self {
+            include: normalize(include),
+            exclude: normalize(exclude),
+            exclude_projects: Vec::new(),
+        }
+    }
+
+    /// Also drop tasks in `projects`, each a project name or id.
+    pub fn with_excluded_projects(mut self, projects: &[String]) -> Self {
+        self.exclude_projects = projects
+            .iter()
+            .map(|project| project.trim().to_lowercase())
+            .filter(|project| !project.is_empty())
+            .collect();
+        self
+    }
+
+    /// Whether `task` passes the filter; tasks from other sources always do.
+    pub fn matches(&self, task: &TodoTask) -> bool {
+        if task.source != "todoist" {
+            return true;
+        }
+        let has_any = |wanted: &[String]| {
+            task.labels
+                .iter()
+                .any(|label| wanted.contains(&label.to_lowercase()))
+        };
+        let in_excluded_project = [&task.project_name, &task.project_id]
+            .into_iter()
+            .flatten()
+            .any(|project| self.exclude_projects.contains(&project.to_lowercase()));
+        (self.include.is_empty() || has_any(&self.include))
+            && !has_any(&self.exclude)
+            && !in_excluded_project
+    }
 }
 
 /// Sort tasks: overdue first, then chronologically
@@ -186,35 +762,1279 @@ pub fn sort_tasks(tasks: &mut [TodoTask]) {
     });
 }
 
+/// Sort Linear issues: highest priority first (unprioritized last), then by due date
+pub fn sort_in_progress(tasks: &mut [TodoTask]) {
+    fn priority_rank(priority: u8) -> u8 {
+        if priority == 0 {
+            u8::MAX
+        } else {
+            priority
+        }
+    }
+
+    tasks.sort_by(|a, b| {
+        priority_rank(a.priority)
+            .cmp(&priority_rank(b.priority))
+            .then_with(|| match (&a.due_datetime, &b.due_datetime) {
+                (Some(dt_a), Some(dt_b)) => dt_a.cmp(dt_b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            })
+    });
+}
+
+/// Whether tomorrow's tasks are shown at `hour` (0-23 local time).
+///
+/// `after_hour` of `None` shows them all day.
+pub fn shows_tomorrow(after_hour: Option<u8>, hour: u32) -> bool {
+    after_hour.is_none_or(|after_hour| hour >= u32::from(after_hour))
+}
+
 /// Group tasks into overdue, today, and tomorrow
-pub fn group_tasks(mut tasks: Vec<TodoTask>) -> TaskList {
+///
+/// Each `(source, id)` lands in at most one group, with precedence
+/// overdue > today > tomorrow, so a task due earlier today only shows as overdue
+/// and duplicates from overlapping queries are dropped. Todoist tasks that fail
+/// `labels` are left out entirely.
+pub fn group_tasks(
+    mut tasks: Vec<TodoTask>,
+    labels: &LabelFilter,
+    all_day: AllDayPosition,
+) -> TaskList {
     sort_tasks(&mut tasks);
 
-    let overdue: Vec<_> = tasks
-        .iter()
-        .filter(|t| t.source == "todoist" && t.is_overdue)
-        .cloned()
-        .collect();
-    let today: Vec<_> = tasks
-        .iter()
-        .filter(|t| t.source == "todoist" && t.is_today && !t.is_overdue)
-        .cloned()
-        .collect();
-    let tomorrow: Vec<_> = tasks
-        .iter()
-        .filter(|t| t.source == "todoist" && t.is_tomorrow)
-        .cloned()
-        .collect();
-    let in_progress: Vec<_> = tasks
+    let mut grouped = TaskList::default();
+    let mut seen = HashSet::new();
+    for task in tasks {
+        if !labels.matches(&task) {
+            continue;
+        }
+        if !seen.insert((task.source.clone(), task.id.clone())) {
+            continue;
+        }
+
+        if task.source == "linear" {
+            grouped.in_progress.push(task);
+        } else if task.source != "todoist" {
+            continue;
+        } else if task.is_overdue {
+            grouped.overdue.push(task);
+        } else if task.is_today {
+            grouped.today.push(task);
+        } else if task.is_tomorrow {
+            grouped.tomorrow.push(task);
+        } else {
+            grouped.other.push(task);
+        }
+    }
+    sort_in_progress(&mut grouped.in_progress);
+    // All-day tasks sort as due at the end of the day, but have no real time
+    match all_day {
+        AllDayPosition::First => grouped.today.sort_by_key(|task| !task.all_day),
+        AllDayPosition::Last => grouped.today.sort_by_key(|task| task.all_day),
+    }
+    for group in [
+        &mut grouped.overdue,
+        &mut grouped.today,
+        &mut grouped.tomorrow,
+        &mut grouped.other,
+    ] {
+        nest_subtasks(group);
+    }
+
+    grouped
+}
+
+/// Move sub-tasks directly under their parents and set their indent level.
+///
+/// Only parents within `tasks` count; a sub-task whose parent is elsewhere
+/// stays top-level. Siblings keep their existing relative order.
+fn nest_subtasks(tasks: &mut Vec<TodoTask>) {
+    let ids: HashSet<String> = tasks.iter().map(|task| task.id.clone()).collect();
+    let mut roots = Vec::new();
+    let mut children: HashMap<String, Vec<TodoTask>> = HashMap::new();
+    for task in tasks.drain(..) {
+        match task.parent_id.clone().filter(|parent| ids.contains(parent)) {
+            Some(parent) => children.entry(parent).or_default().push(task),
+            None => roots.push(task),
+        }
+    }
+
+    fn push_with_children(
+        mut task: TodoTask,
+        level: u8,
+        children: &mut HashMap<String, Vec<TodoTask>>,
+        out: &mut Vec<TodoTask>,
+    ) {
+        task.indent_level = level;
+        let kids = children.remove(&task.id).unwrap_or_default();
+        out.push(task);
+        for child in kids {
+            push_with_children(child, level.saturating_add(1), children, out);
+        }
+    }
+
+    for root in roots {
+        push_with_children(root, 0, &mut children, tasks);
+    }
+    // Anything left is part of a parent cycle; show it flat rather than drop it.
+    for task in children.into_values().flatten() {
+        push_with_children(task, 0, &mut HashMap::new(), tasks);
+    }
+}
+
+/// Arrange grouped tasks into non-empty sections for the given mode and layout
+///
+/// Also numbers the first visible completable tasks for keyboard shortcuts,
+/// writing the same numbers back into `tasks`.
+/// `max_items` caps every section, on top of the compact layout's limit.
+pub fn build_task_sections(
+    tasks: &mut TaskList,
+    mode: GroupingMode,
+    layout: MenuLayout,
+    max_items: Option<usize>,
+) -> Vec<TaskSection> {
+    let mut sections = match mode {
+        GroupingMode::ByDue => vec![
+            section("Overdue", &tasks.overdue),
+            section("Today", &tasks.today),
+            section("Tomorrow", &tasks.tomorrow),
+            section("Other", &tasks.other),
+        ],
+        GroupingMode::ByProject => group_by_project(tasks),
+    };
+    sections.push(section("In Progress", &tasks.in_progress));
+    sections.retain(|section| !section.tasks.is_empty());
+    let layout_limit = (layout == MenuLayout::Compact).then_some(COMPACT_SECTION_LIMIT);
+    if let Some(limit) = layout_limit.into_iter().chain(max_items).min() {
+        for section in &mut sections {
+            truncate_section(section, limit);
+        }
+    }
+
+    let shortcuts = assign_shortcuts(&mut sections);
+    for task in tasks
+        .overdue
+        .iter_mut()
+        .chain(tasks.today.iter_mut())
+        .chain(tasks.tomorrow.iter_mut())
+        .chain(tasks.other.iter_mut())
+        .chain(tasks.in_progress.iter_mut())
+    {
+        task.shortcut = shortcuts
+            .get(&(task.source.clone(), task.id.clone()))
+            .copied();
+    }
+
+    sections
+}
+
+/// Give completable tasks shortcuts 1..=9 in display order, returning them by `(source, id)`
+fn assign_shortcuts(sections: &mut [TaskSection]) -> HashMap<(String, String), u8> {
+    let mut shortcuts = HashMap::new();
+    let mut next = 1;
+    for task in sections.iter_mut().flat_map(|s| s.tasks.iter_mut()) {
+        task.shortcut = None;
+        if task.can_complete && next <= MAX_SHORTCUTS {
+            task.shortcut = Some(next);
+            shortcuts.insert((task.source.clone(), task.id.clone()), next);
+            next += 1;
+        }
+    }
+    shortcuts
+}
+
+fn section(title: &str, tasks: &[TodoTask]) -> TaskSection {
+    TaskSection {
+        title: title.to_string(),
+        tasks: tasks.to_vec(),
+        hidden_count: 0,
+    }
+}
+
+/// Keep the first `limit` tasks, counting the rest in `hidden_count`
+fn truncate_section(section: &mut TaskSection, limit: usize) {
+    if section.tasks.len() > limit {
+        section.hidden_count += (section.tasks.len() - limit) as u32;
+        section.tasks.truncate(limit);
+    }
+}
+
+/// Inbox first, then projects alphabetically; tasks keep their due order
+fn group_by_project(tasks: &TaskList) -> Vec<TaskSection> {
+    // Keyed by (not inbox, project, section) so the Inbox comes first and each
+    // project's loose tasks come before its sections.
+    let mut groups: BTreeMap<(bool, String, Option<String>), Vec<TodoTask>> = BTreeMap::new();
+    groups.insert((false, INBOX_SECTION_TITLE.to_string(), None), Vec::new());
+    for task in tasks.todoist_tasks() {
+        let project = task
+            .project_name
+            .as_deref()
+            .filter(|name| !name.is_empty() && *name != INBOX_SECTION_TITLE);
+        let section = task.section_name.clone().filter(|name| !name.is_empty());
+        let key = (
+            project.is_some(),
+            project.unwrap_or(INBOX_SECTION_TITLE).to_string(),
+            section,
+        );
+        groups.entry(key).or_default().push(task.clone());
+    }
+
+    groups
+        .into_iter()
+        .map(|((_, project, section), tasks)| TaskSection {
+            title: match section {
+                Some(section) => format!("{} / {}", project, section),
+                None => project,
+            },
+            tasks,
+            hidden_count: 0,
+        })
+        .collect()
+}
+
+/// Overdue, today's and tomorrow's tasks as one list, each group in the order
+/// `group_tasks` left it. Tomorrow's are only in `tasks` while they're being
+/// shown.
+pub fn flat_today_list(tasks: &TaskList) -> Vec<TodoTask> {
+    tasks
+        .overdue
         .iter()
-        .filter(|t| t.source == "linear")
+        .chain(&tasks.today)
+        .chain(&tasks.tomorrow)
         .cloned()
-        .collect();
+        .collect()
+}
+
+/// Keep only tasks whose content contains `query` (case-insensitive), preserving groups
+pub fn filter_task_list(tasks: &TaskList, query: &str) -> TaskList {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return tasks.clone();
+    }
+
+    let filter = |group: &[TodoTask]| -> Vec<TodoTask> {
+        group
+            .iter()
+            .filter(|task| {
+                task.content.to_lowercase().contains(&query)
+                    || task
+                        .project_name
+                        .as_deref()
+                        .is_some_and(|name| name.to_lowercase().contains(&query))
+            })
+            .cloned()
+            .collect()
+    };
 
     TaskList {
-        overdue,
-        today,
-        tomorrow,
-        in_progress,
+        overdue: filter(&tasks.overdue),
+        today: filter(&tasks.today),
+        tomorrow: filter(&tasks.tomorrow),
+        in_progress: filter(&tasks.in_progress),
+        other: filter(&tasks.other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use chrono::TimeZone;
+
+    fn linear_task(id: &str, priority: u8, due_date: Option<&str>) -> TodoTask {
+        TodoTask::from_linear(
+            id.to_string(),
+            id.to_uppercase(),
+            "Issue".to_string(),
+            due_date.map(str::to_string),
+            "In Progress".to_string(),
+            priority,
+            None,
+            Language::English,
+        )
+    }
+
+    fn ids(tasks: &[TodoTask]) -> Vec<&str> {
+        tasks.iter().map(|t| t.id.as_str()).collect()
+    }
+
+    #[test]
+    fn high_priority_linear_issues_sort_first() {
+        let grouped = group_tasks(
+            vec![
+                linear_task("none", 0, Some("2000-01-01")),
+                linear_task("low", 4, None),
+                linear_task("urgent", 1, None),
+                linear_task("high", 2, None),
+            ],
+            &LabelFilter::default(),
+            AllDayPosition::default(),
+        );
+
+        assert_eq!(
+            ids(&grouped.in_progress),
+            vec!["urgent", "high", "low", "none"]
+        );
+    }
+
+    #[test]
+    fn equal_priority_linear_issues_sort_by_due_date() {
+        let grouped = group_tasks(
+            vec![
+                linear_task("undated", 2, None),
+                linear_task("later", 2, Some("2099-02-01")),
+                linear_task("sooner", 2, Some("2099-01-01")),
+            ],
+            &LabelFilter::default(),
+            AllDayPosition::default(),
+        );
+
+        assert_eq!(
+            ids(&grouped.in_progress),
+            vec!["sooner", "later", "undated"]
+        );
+    }
+
+    fn todoist_task(id: &str, due_date: &str) -> TodoTask {
+        todoist_task_from(&format!(
+            r#"{{"id": "{}", "content": "Task {}", "due": {{"date": "{}"}}}}"#,
+            id, id, due_date
+        ))
+    }
+
+    /// Task as `from_todoist` builds it from the API's `json`
+    fn todoist_task_from(json: &str) -> TodoTask {
+        TodoTask::from_todoist(
+            serde_json::from_str(json).unwrap(),
+            TimeFormat::default(),
+            chrono::Duration::minutes(60),
+            false,
+            Language::English,
+        )
+    }
+
+    #[test]
+    fn cleaning_strips_markdown_links_and_keeps_the_raw_title() {
+        let mut task = todoist_task("1", "2099-01-01");
+        task.content = "🚀 Read **[RFC 42](https://example.com/rfc/42)** today".to_string();
+        task.raw_content = task.content.clone();
+
+        task.clean_content(TitleCleaning {
+            strip_markdown: true,
+            strip_leading_emoji: false,
+        });
+        assert_eq!(task.content, "🚀 Read RFC 42 today");
+        assert_eq!(
+            task.raw_content,
+            "🚀 Read **[RFC 42](https://example.com/rfc/42)** today"
+        );
+
+        task.clean_content(TitleCleaning {
+            strip_markdown: true,
+            strip_leading_emoji: true,
+        });
+        assert_eq!(task.content, "Read RFC 42 today");
+    }
+
+    #[test]
+    fn cleaning_leaves_text_that_is_not_a_link_alone() {
+        let cleaning = TitleCleaning {
+            strip_markdown: true,
+            strip_leading_emoji: true,
+        };
+
+        assert_eq!(
+            clean_title("[WIP] draft (v2)", cleaning),
+            "[WIP] draft (v2)"
+        );
+        assert_eq!(clean_title("a [b [c](d)", cleaning), "a [b c");
+        assert_eq!(clean_title("🎉", cleaning), "🎉");
+    }
+
+    #[test]
+    fn due_datetime_is_read_in_its_own_timezone() {
+        let due: TodoistDue = serde_json::from_str(
+            r#"{"date": "2026-03-01T09:00:00", "datetime": "2026-03-01T09:00:00",
+                "timezone": "America/New_York", "is_recurring": false, "string": "Mar 1 9am"}"#,
+        )
+        .unwrap();
+        assert!(due.has_time());
+        assert_eq!(
+            due.due_at(),
+            Some(Utc.with_ymd_and_hms(2026, 3, 1, 14, 0, 0).unwrap())
+        );
+
+        let fixed = TodoistDue {
+            date: "2026-03-01".to_string(),
+            datetime: Some("2026-03-01T14:00:00.000000Z".to_string()),
+            timezone: Some("America/New_York".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            fixed.due_at(),
+            Some(Utc.with_ymd_and_hms(2026, 3, 1, 14, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn floating_due_datetime_falls_back_to_local_time() {
+        let due = TodoistDue {
+            date: "2026-03-01".to_string(),
+            datetime: Some("2026-03-01T09:00:00".to_string()),
+            timezone: Some("Not/AZone".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            due.due_at(),
+            Some(
+                Local
+                    .with_ymd_and_hms(2026, 3, 1, 9, 0, 0)
+                    .unwrap()
+                    .with_timezone(&Utc)
+            )
+        );
+    }
+
+    #[test]
+    fn overdue_times_read_as_relative_days() {
+        // Thursday afternoon
+        let now = Local.with_ymd_and_hms(2026, 3, 12, 15, 0, 0).unwrap();
+        let at = |day: u32, hour: u32| Local.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap();
+
+        let half_hour_ago = now - chrono::Duration::minutes(30);
+        assert_eq!(
+            format_overdue_time(&half_hour_ago, &now, Language::English),
+            "overdue"
+        );
+        assert_eq!(
+            format_overdue_time(&at(12, 9), &now, Language::English),
+            "6h ago"
+        );
+        assert_eq!(
+            format_overdue_time(&at(11, 23), &now, Language::English),
+            "Yesterday"
+        );
+        assert_eq!(
+            format_overdue_time(&at(9, 10), &now, Language::English),
+            "Mon"
+        );
+        assert_eq!(
+            format_overdue_time(&at(6, 10), &now, Language::English),
+            "Fri"
+        );
+        assert_eq!(
+            format_overdue_time(&at(5, 10), &now, Language::English),
+            "Mar 5"
+        );
+    }
+
+    #[test]
+    fn overdue_times_follow_the_language() {
+        let now = Local.with_ymd_and_hms(2026, 3, 12, 15, 0, 0).unwrap();
+        let at = |day: u32, hour: u32| Local.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap();
+
+        assert_eq!(
+            format_overdue_time(&at(12, 9), &now, Language::Swedish),
+            "6 tim sedan"
+        );
+        assert_eq!(
+            format_overdue_time(&at(11, 9), &now, Language::Swedish),
+            "Igår"
+        );
+        assert_eq!(
+            format_overdue_time(&at(9, 10), &now, Language::Swedish),
+            "mån"
+        );
+        assert_eq!(
+            format_overdue_time(&at(5, 10), &now, Language::Swedish),
+            "5 mars"
+        );
+        let half_hour_ago = now - chrono::Duration::minutes(30);
+        assert_eq!(
+            format_overdue_time(&half_hour_ago, &now, Language::Swedish),
+            "overdue"
+        );
+    }
+
+    #[test]
+    fn countdown_reads_as_time_left() {
+        let now = Local.with_ymd_and_hms(2026, 3, 12, 12, 0, 0).unwrap();
+        let later = |minutes| now + chrono::Duration::minutes(minutes);
+
+        assert_eq!(format_countdown(&now, &now, Language::English), "now");
+        assert_eq!(
+            format_countdown(&later(1), &now, Language::English),
+            "in 1m"
+        );
+        assert_eq!(
+            format_countdown(&later(25), &now, Language::English),
+            "in 25m"
+        );
+        assert_eq!(
+            format_countdown(&later(59), &now, Language::English),
+            "in 59m"
+        );
+        assert_eq!(
+            format_countdown(&later(60), &now, Language::English),
+            "in 1h"
+        );
+        assert_eq!(
+            format_countdown(&later(89), &now, Language::English),
+            "in 1h"
+        );
+        assert_eq!(
+            format_countdown(&later(90), &now, Language::English),
+            "in 2h"
+        );
+        assert_eq!(
+            format_countdown(&later(10 * 60 + 5), &now, Language::English),
+            "in 10h"
+        );
+    }
+
+    #[test]
+    fn countdown_only_replaces_upcoming_times() {
+        let clock = FixedClock(
+            Local
+                .with_ymd_and_hms(2026, 3, 12, 12, 0, 0)
+                .unwrap()
+                .to_utc(),
+        );
+        let due = |hour| {
+            Some(
+                Local
+                    .with_ymd_and_hms(2026, 3, 12, hour, 0, 0)
+                    .unwrap()
+                    .to_utc(),
+            )
+        };
+
+        let upcoming = format_display_time(
+            &due(14),
+            false,
+            true,
+            TimeFormat::default(),
+            Language::English,
+            &clock,
+        );
+        assert_eq!(upcoming, "in 2h");
+        let absolute = format_display_time(
+            &due(14),
+            false,
+            false,
+            TimeFormat::default(),
+            Language::English,
+            &clock,
+        );
+        assert_eq!(absolute, "14:00");
+        let overdue = format_display_time(
+            &due(9),
+            true,
+            true,
+            TimeFormat::default(),
+            Language::English,
+            &clock,
+        );
+        assert_eq!(overdue, "3h ago");
+    }
+
+    #[test]
+    fn todoist_display_time_follows_time_format() {
+        let task = |time_format| {
+            TodoTask::from_todoist(
+                serde_json::from_str(
+                    r#"{"id": "1", "content": "Call", "due": {"date": "2099-01-01T14:30:00"}}"#,
+                )
+                .unwrap(),
+                time_format,
+                chrono::Duration::minutes(60),
+                false,
+                Language::English,
+            )
+        };
+
+        assert_eq!(task(TimeFormat::TwentyFourHour).display_time, "14:30");
+        assert_eq!(task(TimeFormat::TwelveHour).display_time, "2:30 PM");
+    }
+
+    #[test]
+    fn timed_task_with_duration_shows_time_range() {
+        let start = Local.with_ymd_and_hms(2099, 1, 1, 14, 0, 0).unwrap();
+
+        assert_eq!(
+            format_time_range(&start, 30, TimeFormat::TwentyFourHour),
+            "14:00-14:30"
+        );
+        assert_eq!(
+            format_time_range(&start, 90, TimeFormat::TwelveHour),
+            "2:00 PM-3:30 PM"
+        );
+        assert_eq!(
+            format_time_range(&start, 2 * 24 * 60, TimeFormat::TwentyFourHour),
+            "14:00 (2d)"
+        );
+    }
+
+    #[test]
+    fn duration_parses_minutes_and_days() {
+        let parse = |json: &str| {
+            serde_json::from_str::<TodoistDuration>(json)
+                .unwrap()
+                .minutes()
+        };
+
+        assert_eq!(parse(r#"{"amount": 30, "unit": "minute"}"#), Some(30));
+        assert_eq!(parse(r#"{"amount": 1, "unit": "day"}"#), Some(1440));
+        assert_eq!(parse(r#"{"amount": 0, "unit": "minute"}"#), None);
+        assert_eq!(parse(r#"{"amount": 1, "unit": "week"}"#), None);
+
+        let task: TodoistTask =
+            serde_json::from_str(r#"{"id": "1", "content": "a", "due": null}"#).unwrap();
+        assert!(task.duration.is_none());
+    }
+
+    #[test]
+    fn comment_count_parses_and_defaults_to_zero() {
+        let parse = |json: &str| {
+            serde_json::from_str::<TodoistTask>(json)
+                .unwrap()
+                .comment_count
+        };
+
+        assert_eq!(parse(r#"{"id": "1", "content": "a", "due": null}"#), 0);
+        assert_eq!(
+            parse(r#"{"id": "1", "content": "a", "due": null, "comment_count": 3}"#),
+            3
+        );
+        assert_eq!(
+            parse(r#"{"id": "1", "content": "a", "due": null, "note_count": 2}"#),
+            2
+        );
+    }
+
+    fn subtask(id: &str, parent: Option<&str>, due_date: &str) -> TodoTask {
+        let mut task = todoist_task(id, due_date);
+        task.is_today = true;
+        task.parent_id = parent.map(str::to_string);
+        task
+    }
+
+    #[test]
+    fn subtasks_follow_their_parents_with_indent_levels() {
+        let grouped = group_tasks(
+            vec![
+                subtask("grandchild", Some("child"), "2099-01-01"),
+                subtask("child", Some("parent"), "2099-01-02"),
+                subtask("other", None, "2099-01-03"),
+                subtask("parent", None, "2099-01-04"),
+                subtask("orphan", Some("not-fetched"), "2099-01-05"),
+            ],
+            &LabelFilter::default(),
+            AllDayPosition::default(),
+        );
+
+        assert_eq!(
+            ids(&grouped.today),
+            vec!["other", "parent", "child", "grandchild", "orphan"]
+        );
+        let levels: Vec<u8> = grouped.today.iter().map(|t| t.indent_level).collect();
+        assert_eq!(levels, vec![0, 0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn task_that_is_overdue_and_today_only_lands_in_overdue() {
+        let mut task = todoist_task("1", "2099-01-01");
+        task.is_overdue = true;
+        task.is_today = true;
+
+        let grouped = group_tasks(
+            vec![task],
+            &LabelFilter::default(),
+            AllDayPosition::default(),
+        );
+
+        assert_eq!(ids(&grouped.overdue), vec!["1"]);
+        assert!(grouped.today.is_empty());
+    }
+
+    #[test]
+    fn duplicate_tasks_keep_the_most_urgent_copy() {
+        let mut later = todoist_task("1", "2099-01-01");
+        later.is_tomorrow = true;
+        let mut earlier = later.clone();
+        earlier.is_tomorrow = false;
+        earlier.is_overdue = true;
+        let other_source = linear_task("1", 0, None);
+
+        let grouped = group_tasks(
+            vec![later, earlier, other_source],
+            &LabelFilter::default(),
+            AllDayPosition::default(),
+        );
+
+        assert_eq!(ids(&grouped.overdue), vec!["1"]);
+        assert!(grouped.tomorrow.is_empty());
+        assert_eq!(ids(&grouped.in_progress), vec!["1"]);
+    }
+
+    #[test]
+    fn filter_matches_content_case_insensitively() {
+        let tasks = TaskList {
+            today: vec![
+                todoist_task("1", "2099-01-01"),
+                todoist_task("2", "2099-01-01"),
+            ],
+            in_progress: vec![linear_task("eng-7", 0, None)],
+            ..Default::default()
+        };
+
+        let filtered = filter_task_list(&tasks, "TASK 2");
+        assert_eq!(ids(&filtered.today), vec!["2"]);
+        assert!(filtered.in_progress.is_empty());
+
+        let filtered = filter_task_list(&tasks, "eng-7");
+        assert!(filtered.today.is_empty());
+        assert_eq!(ids(&filtered.in_progress), vec!["eng-7"]);
+    }
+
+    #[test]
+    fn filter_preserves_group_membership() {
+        let tasks = TaskList {
+            overdue: vec![todoist_task("late", "2099-01-01")],
+            today: vec![todoist_task("now", "2099-01-01")],
+            ..Default::default()
+        };
+
+        let filtered = filter_task_list(&tasks, "task");
+        assert_eq!(ids(&filtered.overdue), vec!["late"]);
+        assert_eq!(ids(&filtered.today), vec!["now"]);
+
+        let everything = filter_task_list(&tasks, "  ");
+        assert_eq!(everything.overdue.len() + everything.today.len(), 2);
+    }
+
+    fn labeled_task(id: &str, labels: &[&str]) -> TodoTask {
+        let mut task = todoist_task(id, "2099-01-01");
+        task.is_today = true;
+        task.labels = labels.iter().map(|l| l.to_string()).collect();
+        task
+    }
+
+    #[test]
+    fn label_filter_keeps_included_and_drops_excluded_labels() {
+        let tasks = || {
+            vec![
+                labeled_task("work", &["Work"]),
+                labeled_task("both", &["work", "someday"]),
+                labeled_task("home", &["home"]),
+                labeled_task("bare", &[]),
+                linear_task("eng-1", 0, None),
+            ]
+        };
+
+        let unfiltered = group_tasks(tasks(), &LabelFilter::default(), AllDayPosition::default());
+        assert_eq!(unfiltered.today.len(), 4);
+
+        let filter = LabelFilter::new(&["work".to_string()], &["Someday".to_string()]);
+        let grouped = group_tasks(tasks(), &filter, AllDayPosition::default());
+        assert_eq!(ids(&grouped.today), vec!["work"]);
+        assert_eq!(ids(&grouped.in_progress), vec!["eng-1"]);
+
+        let filter = LabelFilter::new(&[], &["home".to_string()]);
+        let grouped = group_tasks(tasks(), &filter, AllDayPosition::default());
+        assert_eq!(ids(&grouped.today), vec!["work", "both", "bare"]);
+    }
+
+    fn project_task(id: &str, project: Option<&str>) -> TodoTask {
+        let mut task = todoist_task(id, "2099-01-01");
+        task.project_name = project.map(str::to_string);
+        task
+    }
+
+    #[test]
+    fn excluded_projects_are_dropped_from_every_group() {
+        let mut late = project_task("late", Some("Someday"));
+        late.is_overdue = true;
+        let mut by_id = project_task("by-id", Some("Errands"));
+        by_id.is_tomorrow = true;
+        by_id.project_id = Some("p-errands".to_string());
+        let mut kept = project_task("kept", Some("Work"));
+        kept.is_today = true;
+        kept.labels = vec!["focus".to_string()];
+        let mut hidden_label = project_task("hidden-label", Some("Work"));
+        hidden_label.is_today = true;
+        let tasks = vec![
+            late,
+            by_id,
+            kept,
+            hidden_label,
+            linear_task("eng-1", 0, None),
+        ];
+
+        let filter = LabelFilter::new(&["focus".to_string()], &[])
+            .with_excluded_projects(&["someday".to_string(), "p-errands".to_string()]);
+        let grouped = group_tasks(tasks, &filter, AllDayPosition::default());
+
+        assert!(grouped.overdue.is_empty());
+        assert!(grouped.tomorrow.is_empty());
+        assert_eq!(ids(&grouped.today), vec!["kept"]);
+        assert_eq!(ids(&grouped.in_progress), vec!["eng-1"]);
+    }
+
+    fn titles(sections: &[TaskSection]) -> Vec<&str> {
+        sections.iter().map(|s| s.title.as_str()).collect()
+    }
+
+    #[test]
+    fn by_project_grouping_puts_projectless_tasks_in_inbox() {
+        let mut tasks = TaskList {
+            overdue: vec![project_task("late", Some("Work"))],
+            today: vec![
+                project_task("loose", None),
+                project_task("errand", Some("Home")),
+                project_task("report", Some("Work")),
+            ],
+            in_progress: vec![linear_task("eng-1", 0, None)],
+            ..Default::default()
+        };
+
+        let sections = build_task_sections(
+            &mut tasks,
+            GroupingMode::ByProject,
+            MenuLayout::Detailed,
+            None,
+        );
+
+        assert_eq!(
+            titles(&sections),
+            vec!["Inbox", "Home", "Work", "In Progress"]
+        );
+        assert_eq!(ids(&sections[0].tasks), vec!["loose"]);
+        assert_eq!(ids(&sections[2].tasks), vec!["late", "report"]);
+    }
+
+    #[test]
+    fn by_project_grouping_nests_sections_after_loose_tasks() {
+        let in_section = |id: &str, section: &str| {
+            let mut task = project_task(id, Some("Work"));
+            task.section_name = Some(section.to_string());
+            task
+        };
+        let mut tasks = TaskList {
+            today: vec![
+                in_section("review", "Backlog"),
+                project_task("standup", Some("Work")),
+                in_section("deploy", "Active"),
+            ],
+            ..Default::default()
+        };
+
+        let sections = build_task_sections(
+            &mut tasks,
+            GroupingMode::ByProject,
+            MenuLayout::Detailed,
+            None,
+        );
+
+        assert_eq!(
+            titles(&sections),
+            vec!["Work", "Work / Active", "Work / Backlog"]
+        );
+        assert_eq!(ids(&sections[0].tasks), vec!["standup"]);
+    }
+
+    #[test]
+    fn by_due_grouping_skips_empty_sections() {
+        let mut tasks = TaskList {
+            today: vec![project_task("1", Some("Work"))],
+            ..Default::default()
+        };
+
+        let sections =
+            build_task_sections(&mut tasks, GroupingMode::ByDue, MenuLayout::Detailed, None);
+
+        assert_eq!(titles(&sections), vec!["Today"]);
+    }
+
+    #[test]
+    fn flat_today_list_keeps_the_grouped_order() {
+        let task = |id: &str, due: &str, flag: fn(&mut TodoTask)| {
+            let mut task = todoist_task(id, due);
+            flag(&mut task);
+            task
+        };
+        let tasks = vec![
+            task("next", "2099-01-03T08:00:00", |t| t.is_tomorrow = true),
+            task("afternoon", "2099-01-02T15:00:00", |t| t.is_today = true),
+            task("errand", "2099-01-02", |t| t.is_today = true),
+            task("late", "2099-01-01T12:00:00", |t| t.is_overdue = true),
+            task("morning", "2099-01-02T09:00:00", |t| t.is_today = true),
+            task("later", "2099-01-01", |t| t.is_overdue = true),
+            linear_task("eng-1", 1, None),
+        ];
+        let grouped = group_tasks(tasks, &LabelFilter::default(), AllDayPosition::First);
+
+        assert_eq!(
+            ids(&flat_today_list(&grouped)),
+            vec!["late", "later", "errand", "morning", "afternoon", "next"]
+        );
+    }
+
+    #[test]
+    fn filter_matches_project_name() {
+        let tasks = TaskList {
+            today: vec![
+                project_task("1", Some("Groceries")),
+                project_task("2", None),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(ids(&filter_task_list(&tasks, "grocer").today), vec!["1"]);
+    }
+
+    #[test]
+    fn date_flags_follow_the_clock() {
+        let noon = Local.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let clock = FixedClock(noon.with_timezone(&Utc));
+        let minute = chrono::Duration::minutes(1);
+
+        let a_minute_ago = Some((noon - minute).with_timezone(&Utc));
+        assert_eq!(date_flags(&a_minute_ago, &clock), (true, true, false));
+        let in_a_minute = Some((noon + minute).with_timezone(&Utc));
+        assert_eq!(date_flags(&in_a_minute, &clock), (false, true, false));
+
+        let before_midnight = Local.with_ymd_and_hms(2026, 3, 10, 23, 59, 30).unwrap();
+        let clock = FixedClock(before_midnight.with_timezone(&Utc));
+        let after_midnight = Some((before_midnight + minute).with_timezone(&Utc));
+        assert_eq!(date_flags(&after_midnight, &clock), (false, false, true));
+        assert_eq!(date_flags(&None, &clock), (false, false, false));
+    }
+
+    #[test]
+    fn due_soon_covers_the_window_boundaries() {
+        let now = Utc::now();
+        let window = chrono::Duration::minutes(60);
+
+        assert!(is_due_soon(
+            &(now + chrono::Duration::minutes(59)),
+            now,
+            window
+        ));
+        assert!(is_due_soon(
+            &(now + chrono::Duration::minutes(60)),
+            now,
+            window
+        ));
+        assert!(!is_due_soon(
+            &(now + chrono::Duration::minutes(61)),
+            now,
+            window
+        ));
+        assert!(!is_due_soon(
+            &(now - chrono::Duration::minutes(1)),
+            now,
+            window
+        ));
+    }
+
+    #[test]
+    fn date_only_and_overdue_tasks_are_never_due_soon() {
+        let soon = (Local::now() + chrono::Duration::minutes(30))
+            .format("%Y-%m-%dT%H:%M:%S")
+            .to_string();
+        let late = (Local::now() - chrono::Duration::minutes(30))
+            .format("%Y-%m-%dT%H:%M:%S")
+            .to_string();
+        let date_only = Local::now().format("%Y-%m-%d").to_string();
+
+        assert!(todoist_task("soon", &soon).is_due_soon);
+        assert!(!todoist_task("late", &late).is_due_soon);
+        assert!(!todoist_task("date", &date_only).is_due_soon);
+        assert!(!linear_task("eng-1", 0, Some(&date_only)).is_due_soon);
+    }
+
+    #[test]
+    fn compact_layout_keeps_first_tasks_and_counts_the_rest() {
+        let mut tasks = TaskList {
+            overdue: (1..=5)
+                .map(|i| todoist_task(&i.to_string(), "2099-01-01"))
+                .collect(),
+            today: vec![todoist_task("only", "2099-01-01")],
+            ..Default::default()
+        };
+
+        let sections =
+            build_task_sections(&mut tasks, GroupingMode::ByDue, MenuLayout::Compact, None);
+
+        assert_eq!(ids(&sections[0].tasks), vec!["1", "2", "3"]);
+        assert_eq!(sections[0].hidden_count, 2);
+        assert_eq!(ids(&sections[1].tasks), vec!["only"]);
+        assert_eq!(sections[1].hidden_count, 0);
+    }
+
+    #[test]
+    fn max_items_caps_every_section_but_keeps_all_tasks() {
+        let mut tasks = TaskList {
+            overdue: (1..=20)
+                .map(|i| todoist_task(&i.to_string(), "2099-01-01"))
+                .collect(),
+            today: vec![todoist_task("only", "2099-01-01")],
+            ..Default::default()
+        };
+
+        let sections = build_task_sections(
+            &mut tasks,
+            GroupingMode::ByDue,
+            MenuLayout::Detailed,
+            Some(5),
+        );
+
+        assert_eq!(ids(&sections[0].tasks), vec!["1", "2", "3", "4", "5"]);
+        assert_eq!(sections[0].hidden_count, 15);
+        assert_eq!(sections[1].hidden_count, 0);
+        assert_eq!(tasks.overdue.len(), 20);
+
+        let sections = build_task_sections(
+            &mut tasks,
+            GroupingMode::ByDue,
+            MenuLayout::Compact,
+            Some(5),
+        );
+        assert_eq!(sections[0].tasks.len(), COMPACT_SECTION_LIMIT);
+    }
+
+    #[test]
+    fn detailed_layout_hides_nothing() {
+        let mut tasks = TaskList {
+            today: (1..=5)
+                .map(|i| todoist_task(&i.to_string(), "2099-01-01"))
+                .collect(),
+            ..Default::default()
+        };
+
+        let sections =
+            build_task_sections(&mut tasks, GroupingMode::ByDue, MenuLayout::Detailed, None);
+
+        assert_eq!(sections[0].tasks.len(), 5);
+        assert_eq!(sections[0].hidden_count, 0);
+    }
+
+    fn shortcut_ids(sections: &[TaskSection]) -> Vec<(u8, &str)> {
+        sections
+            .iter()
+            .flat_map(|s| s.tasks.iter())
+            .filter_map(|t| t.shortcut.map(|n| (n, t.id.as_str())))
+            .collect()
+    }
+
+    #[test]
+    fn shortcuts_number_completable_tasks_in_display_order() {
+        let mut readonly = todoist_task("readonly", "2099-01-01");
+        readonly.can_complete = false;
+        let mut tasks = TaskList {
+            overdue: vec![todoist_task("late", "2099-01-01")],
+            today: vec![readonly, todoist_task("now", "2099-01-01")],
+            in_progress: vec![linear_task("eng-1", 0, None)],
+            ..Default::default()
+        };
+
+        let sections =
+            build_task_sections(&mut tasks, GroupingMode::ByDue, MenuLayout::Detailed, None);
+
+        assert_eq!(
+            shortcut_ids(&sections),
+            vec![(1, "late"), (2, "now"), (3, "eng-1")]
+        );
+        assert_eq!(tasks.today[1].shortcut, Some(2));
+        assert_eq!(tasks.today[0].shortcut, None);
+        assert_eq!(tasks.in_progress[0].shortcut, Some(3));
+    }
+
+    #[test]
+    fn shortcuts_stop_after_nine_tasks() {
+        let mut tasks = TaskList {
+            today: (1..=12)
+                .map(|i| todoist_task(&i.to_string(), "2099-01-01"))
+                .collect(),
+            ..Default::default()
+        };
+
+        let sections =
+            build_task_sections(&mut tasks, GroupingMode::ByDue, MenuLayout::Detailed, None);
+
+        let numbered = shortcut_ids(&sections);
+        assert_eq!(numbered.len(), 9);
+        assert_eq!(numbered.last(), Some(&(9, "9")));
+        assert_eq!(tasks.today[9].shortcut, None);
+    }
+
+    #[test]
+    fn all_day_flag_tracks_whether_the_due_had_a_time() {
+        assert!(todoist_task("date", "2099-01-01").all_day);
+        assert!(!todoist_task("timed", "2099-01-01T09:00:00").all_day);
+    }
+
+    #[test]
+    fn all_day_tasks_go_first_or_last_in_today() {
+        let tasks = || {
+            ["2099-01-01T09:00:00", "2099-01-01", "2099-01-01T17:00:00"]
+                .into_iter()
+                .map(|due| {
+                    let mut task = todoist_task(due, due);
+                    task.is_today = true;
+                    task
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let first = group_tasks(tasks(), &LabelFilter::default(), AllDayPosition::First);
+        assert_eq!(
+            ids(&first.today),
+            vec!["2099-01-01", "2099-01-01T09:00:00", "2099-01-01T17:00:00"]
+        );
+        let last = group_tasks(tasks(), &LabelFilter::default(), AllDayPosition::Last);
+        assert_eq!(
+            ids(&last.today),
+            vec!["2099-01-01T09:00:00", "2099-01-01T17:00:00", "2099-01-01"]
+        );
+    }
+
+    #[test]
+    fn tomorrow_is_shown_from_the_configured_hour() {
+        assert!(!shows_tomorrow(Some(12), 11));
+        assert!(shows_tomorrow(Some(12), 12));
+        assert!(shows_tomorrow(Some(12), 13));
+        assert!(shows_tomorrow(None, 11));
+        assert!(shows_tomorrow(Some(0), 0));
+    }
+
+    #[test]
+    fn urgency_follows_due_date() {
+        let local_date = |days: i64| {
+            (Local::now() + chrono::Duration::days(days))
+                .format("%Y-%m-%d")
+                .to_string()
+        };
+        let in_half_an_hour = (Local::now() + chrono::Duration::minutes(30))
+            .format("%Y-%m-%dT%H:%M:%S")
+            .to_string();
+
+        let cases = [
+            (local_date(-1), TaskUrgency::Overdue),
+            (in_half_an_hour, TaskUrgency::DueSoon),
+            (local_date(0), TaskUrgency::Today),
+            (local_date(1), TaskUrgency::Tomorrow),
+            (local_date(3), TaskUrgency::Later),
+        ];
+        for (due_date, expected) in cases {
+            assert_eq!(
+                todoist_task("1", &due_date).urgency,
+                expected,
+                "{}",
+                due_date
+            );
+        }
+
+        let undated = todoist_task_from(r#"{"id": "2", "content": "Someday", "due": null}"#);
+        assert_eq!(undated.urgency, TaskUrgency::Later);
+
+        let linear = linear_task("abc", 1, Some(&local_date(-1)));
+        assert_eq!(linear.urgency, TaskUrgency::InProgress);
+    }
+
+    #[test]
+    fn deadline_parses_and_raises_urgency_only_when_applied() {
+        let date = |days: i64| {
+            (Local::now() + chrono::Duration::days(days))
+                .format("%Y-%m-%d")
+                .to_string()
+        };
+        let json = format!(
+            r#"{{"id": "1", "content": "File taxes", "due": {{"date": "{}"}}, "deadline": {{"date": "{}", "lang": "en"}}}}"#,
+            date(5),
+            date(-1)
+        );
+        let parsed: TodoistTask = serde_json::from_str(&json).unwrap();
+        let mut task = TodoTask::from_todoist(
+            parsed,
+            TimeFormat::default(),
+            chrono::Duration::minutes(60),
+            false,
+            Language::English,
+        );
+
+        assert_eq!(task.deadline, Some(date(-1)));
+        assert!(task.is_deadline_near);
+        assert_eq!(task.urgency, TaskUrgency::Later);
+
+        task.apply_deadline_urgency();
+        assert_eq!(task.urgency, TaskUrgency::Overdue);
+        assert!(!task.is_overdue);
+
+        // A deadline further out than the due date leaves urgency alone
+        let mut soon = todoist_task("2", &date(0));
+        soon.deadline = Some(date(3));
+        soon.apply_deadline_urgency();
+        assert_eq!(soon.urgency, TaskUrgency::Today);
+    }
+
+    #[test]
+    fn todoist_priorities_map_to_urgent_first_with_the_default_as_none() {
+        let priority = |json: &str| {
+            TodoTask::from_todoist(
+                serde_json::from_str(json).unwrap(),
+                TimeFormat::default(),
+                chrono::Duration::minutes(60),
+                false,
+                Language::English,
+            )
+            .priority
+        };
+
+        assert_eq!(
+            priority(r#"{"id": "1", "content": "p1", "priority": 4}"#),
+            1
+        );
+        assert_eq!(
+            priority(r#"{"id": "2", "content": "p3", "priority": 2}"#),
+            3
+        );
+        assert_eq!(
+            priority(r#"{"id": "3", "content": "p4", "priority": 1}"#),
+            0
+        );
+        assert_eq!(priority(r#"{"id": "4", "content": "unset"}"#), 0);
+    }
+
+    #[test]
+    fn tasks_outside_the_due_groups_land_in_other() {
+        let undated = todoist_task_from(r#"{"id": "undated", "content": "Someday", "due": null}"#);
+        let next_week = (Local::now() + chrono::Duration::days(7))
+            .format("%Y-%m-%d")
+            .to_string();
+        let tasks = vec![
+            undated,
+            todoist_task("later", &next_week),
+            todoist_task("today", &Local::now().format("%Y-%m-%d").to_string()),
+        ];
+
+        let grouped = group_tasks(tasks, &LabelFilter::default(), AllDayPosition::default());
+
+        assert_eq!(ids(&grouped.today), vec!["today"]);
+        assert_eq!(ids(&grouped.other), vec!["later", "undated"]);
+        let titles: Vec<_> = build_task_sections(
+            &mut grouped.clone(),
+            GroupingMode::ByDue,
+            MenuLayout::Detailed,
+            None,
+        )
+        .into_iter()
+        .map(|section| section.title)
+        .collect();
+        assert_eq!(titles, vec!["Today", "Other"]);
     }
 }
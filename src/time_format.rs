@@ -0,0 +1,71 @@
+//! Clock format shared by every source's display times
+
+use chrono::{DateTime, TimeZone};
+use serde::Deserialize;
+use std::fmt::Display;
+
+/// 24-hour ("14:30") or 12-hour ("2:30 PM") clock
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum TimeFormat {
+    #[default]
+    #[serde(rename = "24h", alias = "twenty_four_hour")]
+    TwentyFourHour,
+    #[serde(rename = "12h", alias = "twelve_hour")]
+    TwelveHour,
+}
+
+impl TimeFormat {
+    /// Format the time-of-day part of `dt` in its own timezone.
+    pub fn format_time<Tz>(self, dt: &DateTime<Tz>) -> String
+    where
+        Tz: TimeZone,
+        Tz::Offset: Display,
+    {
+        let pattern = match self {
+            TimeFormat::TwentyFourHour => "%H:%M",
+            TimeFormat::TwelveHour => "%-I:%M %p",
+        };
+        dt.format(pattern).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TimeFormat;
+    use chrono::{FixedOffset, TimeZone};
+
+    #[test]
+    fn formats_the_same_timestamp_in_both_clocks() {
+        let offset = FixedOffset::east_opt(0).unwrap();
+        let afternoon = offset.with_ymd_and_hms(2026, 3, 1, 14, 30, 0).unwrap();
+        let morning = offset.with_ymd_and_hms(2026, 3, 1, 9, 5, 0).unwrap();
+
+        assert_eq!(TimeFormat::TwentyFourHour.format_time(&afternoon), "14:30");
+        assert_eq!(TimeFormat::TwelveHour.format_time(&afternoon), "2:30 PM");
+        assert_eq!(TimeFormat::TwentyFourHour.format_time(&morning), "09:05");
+        assert_eq!(TimeFormat::TwelveHour.format_time(&morning), "9:05 AM");
+    }
+
+    #[test]
+    fn midnight_is_twelve_am() {
+        let midnight = FixedOffset::east_opt(0)
+            .unwrap()
+            .with_ymd_and_hms(2026, 3, 1, 0, 0, 0)
+            .unwrap();
+
+        assert_eq!(TimeFormat::TwelveHour.format_time(&midnight), "12:00 AM");
+    }
+
+    #[test]
+    fn parses_config_values() {
+        #[derive(serde::Deserialize)]
+        struct Wrapper {
+            time_format: TimeFormat,
+        }
+
+        let parsed: Wrapper = toml::from_str("time_format = \"12h\"").unwrap();
+        assert_eq!(parsed.time_format, TimeFormat::TwelveHour);
+        let parsed: Wrapper = toml::from_str("time_format = \"twenty_four_hour\"").unwrap();
+        assert_eq!(parsed.time_format, TimeFormat::TwentyFourHour);
+    }
+}
@@ -0,0 +1,92 @@
+//! Menu bar title templates, e.g. `"! {overdue} · {prs}"`
+
+/// Counts a title template can refer to
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TitleCounts {
+    pub overdue: u32,
+    pub today: u32,
+    pub tomorrow: u32,
+    /// In-progress Linear issues
+    pub linear: u32,
+    /// Unread GitHub notifications
+    pub github: u32,
+    pub calendar: u32,
+}
+
+impl TitleCounts {
+    fn get(&self, placeholder: &str) -> Option<u32> {
+        match placeholder {
+            "overdue" => Some(self.overdue),
+            "today" => Some(self.today),
+            "tomorrow" => Some(self.tomorrow),
+            "linear" => Some(self.linear),
+            "github" | "prs" => Some(self.github),
+            "calendar" => Some(self.calendar),
+            _ => None,
+        }
+    }
+}
+
+/// Fill each `{placeholder}` in `template` with its count.
+///
+/// Fails on unknown placeholders and unbalanced braces, so the caller can
+/// fall back to the default title.
+pub fn render(template: &str, counts: &TitleCounts) -> Result<String, String> {
+    let mut title = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        if rest[start..].starts_with('}') {
+            return Err(format!("Unmatched '}}' in title format '{}'", template));
+        }
+        title.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| format!("Unclosed '{{' in title format '{}'", template))?;
+        let name = after[..end].trim();
+        let count = counts
+            .get(name)
+            .ok_or_else(|| format!("Unknown placeholder '{{{}}}' in title format", name))?;
+        title.push_str(&count.to_string());
+        rest = &after[end + 1..];
+    }
+    title.push_str(rest);
+    Ok(title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render, TitleCounts};
+
+    fn counts() -> TitleCounts {
+        TitleCounts {
+            overdue: 2,
+            today: 5,
+            tomorrow: 1,
+            linear: 3,
+            github: 4,
+            calendar: 0,
+        }
+    }
+
+    #[test]
+    fn placeholders_are_replaced_by_counts() {
+        assert_eq!(render("! {overdue} · {prs}", &counts()).unwrap(), "! 2 · 4");
+        assert_eq!(
+            render(
+                "{today}/{tomorrow} L{linear} G{github} C{calendar}",
+                &counts()
+            )
+            .unwrap(),
+            "5/1 L3 G4 C0"
+        );
+        assert_eq!(render("Tasks", &counts()).unwrap(), "Tasks");
+    }
+
+    #[test]
+    fn invalid_templates_are_rejected() {
+        for template in ["{unknown}", "{overdue", "overdue}", "{}"] {
+            assert!(render(template, &counts()).is_err(), "{}", template);
+        }
+    }
+}
@@ -1,110 +1,946 @@
 //! Todoist API client
 
-use crate::task::{TodoTask, TodoistTask};
+use crate::http::{status_error, CursorGuard, HttpTransport};
+use crate::locale::Language;
+use crate::task::{TodoTask, TodoistDue, TodoistTask};
+use crate::time_format::TimeFormat;
 use anyhow::{Context, Result};
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 const TODOIST_API_URL: &str = "https://api.todoist.com/api/v1";
 
+/// Filter used for the task list when the config doesn't set one
+pub const DEFAULT_TASK_FILTER_QUERY: &str = "today | overdue | tomorrow";
+
 /// Todoist API client
 pub struct TodoistClient {
+    /// Builds requests; `http` sends them
     client: Client,
+    http: Arc<dyn HttpTransport>,
     api_token: String,
+    time_format: TimeFormat,
+    due_soon_window: chrono::Duration,
+    task_filter_query: String,
+    /// Name of a saved Todoist filter whose query replaces `task_filter_query`
+    saved_filter: Option<String>,
+    /// Query of `saved_filter`, looked up again only after a failed fetch
+    saved_filter_query: Mutex<Option<String>>,
+    /// Set when tasks are fetched incrementally through the Sync API
+    sync: Option<Mutex<SyncCache>>,
+    /// API root without a trailing slash, [`TODOIST_API_URL`] unless overridden
+    base_url: String,
+    /// Collaborator names by user id, kept across refreshes since they rarely change
+    collaborators: Mutex<HashMap<String, String>>,
+    /// Section names by section id, kept across refreshes like collaborators
+    sections: Mutex<HashMap<String, String>>,
+    /// Project names by project id, kept across refreshes like sections
+    projects: Mutex<HashMap<String, String>>,
+    /// Fetch reminders to fill `TodoTask::next_reminder`
+    show_reminders: bool,
+    /// Set once Todoist refuses reminders, e.g. on a free plan, to stop asking
+    reminders_unavailable: AtomicBool,
+    /// Show today's upcoming timed tasks as a countdown ("in 2h")
+    countdown_times: bool,
+    /// Let a near deadline raise a task's urgency above its due date's
+    deadline_urgency: bool,
+    language: Language,
 }
 
 impl TodoistClient {
-    pub fn new(api_token: String) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .expect("Failed to create HTTP client");
+    /// `task_filter_query` falls back to [`DEFAULT_TASK_FILTER_QUERY`] when unset or blank.
+    ///
+    /// `saved_filter` names a filter saved in Todoist, used instead of
+    /// `task_filter_query` when set. `incremental_sync` only applies with the
+    /// default filter, since the Sync API can't evaluate filter queries.
+    ///
+    /// Requests are sent through `http`, shared with the other clients.
+    pub fn new(
+        api_token: String,
+        time_format: TimeFormat,
+        due_soon_window: chrono::Duration,
+        task_filter_query: Option<String>,
+        saved_filter: Option<String>,
+        incremental_sync: bool,
+        http: Arc<dyn HttpTransport>,
+    ) -> Self {
+        let client = Client::new();
+        let task_filter_query = task_filter_query
+            .map(|query| query.trim().to_string())
+            .filter(|query| !query.is_empty())
+            .unwrap_or_else(|| DEFAULT_TASK_FILTER_QUERY.to_string());
+        let saved_filter = saved_filter
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty());
+        let sync = (incremental_sync
+            && saved_filter.is_none()
+            && task_filter_query == DEFAULT_TASK_FILTER_QUERY)
+            .then(|| Mutex::new(SyncCache::default()));
+
+        Self {
+            client,
+            http,
+            api_token,
+            time_format,
+            due_soon_window,
+            task_filter_query,
+            saved_filter,
+            saved_filter_query: Mutex::new(None),
+            sync,
+            base_url: TODOIST_API_URL.to_string(),
+            collaborators: Mutex::new(HashMap::new()),
+            sections: Mutex::new(HashMap::new()),
+            projects: Mutex::new(HashMap::new()),
+            show_reminders: false,
+            reminders_unavailable: AtomicBool::new(false),
+            countdown_times: false,
+            deadline_urgency: false,
+            language: Language::default(),
+        }
+    }
+
+    /// Talk to the API at `base_url` instead of the public one, e.g. a proxy
+    /// or a local mock server.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Also fetch reminders on every refresh, one extra request, to fill in
+    /// `TodoTask::next_reminder`.
+    pub fn with_reminders(mut self, show_reminders: bool) -> Self {
+        self.show_reminders = show_reminders;
+        self
+    }
+
+    /// Show today's upcoming timed tasks as "in 2h" rather than "14:30".
+    /// The countdown is worked out on each refresh.
+    pub fn with_countdown_times(mut self, countdown_times: bool) -> Self {
+        self.countdown_times = countdown_times;
+        self
+    }
+
+    /// Mark tasks as urgent by their deadline when it's closer than the due
+    /// date. Tasks stay grouped by due date either way.
+    pub fn with_deadline_urgency(mut self, deadline_urgency: bool) -> Self {
+        self.deadline_urgency = deadline_urgency;
+        self
+    }
+
+    /// Write relative times like "3h ago" in `language`.
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = language;
+        self
+    }
 
-        Self { client, api_token }
+    /// Check the API token with a cheap request, returning the account's name.
+    ///
+    /// Fails with [`InvalidTokenError`](crate::http::InvalidTokenError) when
+    /// Todoist rejects the token.
+    pub async fn verify_token(&self) -> Result<String> {
+        let url = format!("{}/user", self.base_url);
+
+        let response = self
+            .http
+            .send(
+                self.client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", self.api_token)),
+            )
+            .await
+            .context("Failed to connect to Todoist API")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            return Err(status_error(
+                status,
+                &body,
+                "Todoist token",
+                anyhow::anyhow!("Todoist API error ({}): {}", status, body),
+            ));
+        }
+
+        #[derive(Deserialize)]
+        struct User {
+            #[serde(default)]
+            full_name: String,
+            #[serde(default)]
+            email: String,
+        }
+
+        let user: User = response
+            .json()
+            .context("Failed to parse Todoist user response")?;
+
+        Ok(if user.full_name.is_empty() {
+            user.email
+        } else {
+            user.full_name
+        })
     }
 
-    /// Get tasks for today, overdue, and tomorrow
+    /// Get tasks matching the configured filter (today, overdue, and tomorrow by default)
+    ///
+    /// With incremental sync enabled, only changes since the last refresh are
+    /// fetched. A failed sync falls back to the full filter fetch and starts
+    /// the next sync from scratch.
+    #[tracing::instrument(name = "todoist_fetch", skip_all, fields(synced, tasks))]
     pub async fn get_tasks(&self) -> Result<Vec<TodoTask>> {
-        let url = format!("{}/tasks/filter", TODOIST_API_URL);
+        // Synced items aren't filtered server-side, so only keep the ones the
+        // default filter would have returned.
+        let (tasks, synced) = match &self.sync {
+            Some(cache) => match self.sync_tasks(cache).await {
+                Ok(tasks) => (tasks, true),
+                Err(e) => {
+                    tracing::warn!(error = %e, "Todoist sync failed, fetching all tasks");
+                    *cache.lock().unwrap() = SyncCache::default();
+                    (self.get_filtered_tasks().await?, false)
+                }
+            },
+            None => (self.get_filtered_tasks().await?, false),
+        };
+        tracing::Span::current().record("synced", synced);
+
+        self.fetch_missing_projects(&tasks).await;
+        self.fetch_missing_collaborators(&tasks).await;
+        self.fetch_missing_sections(&tasks).await;
+        let reminders = self.get_reminders().await;
+        let now = chrono::Utc::now();
+        let collaborators = self.collaborators.lock().unwrap().clone();
+        let collaborator_name =
+            |id: &Option<String>| id.as_ref().and_then(|id| collaborators.get(id)).cloned();
+        let sections = self.sections.lock().unwrap().clone();
+        let project_names = self.projects.lock().unwrap().clone();
+
+        let tasks = tasks
+            .into_iter()
+            .map(|task| {
+                let project_name = task
+                    .project_id
+                    .as_ref()
+                    .and_then(|id| project_names.get(id))
+                    .cloned();
+                let assignee_name = collaborator_name(&task.assignee_id);
+                let assigner_name = collaborator_name(&task.assigner_id);
+                let section_name = task
+                    .section_id
+                    .as_ref()
+                    .and_then(|id| sections.get(id))
+                    .cloned();
+                let next_reminder = next_reminder(&reminders, &task, now);
+                let mut task = TodoTask::from_todoist(
+                    task,
+                    self.time_format,
+                    self.due_soon_window,
+                    self.countdown_times,
+                    self.language,
+                );
+                task.project_name = project_name;
+                task.assignee_name = assignee_name;
+                task.assigner_name = assigner_name;
+                task.section_name = section_name;
+                task.next_reminder = next_reminder.map(|at| at.to_rfc3339());
+                if self.deadline_urgency {
+                    task.apply_deadline_urgency();
+                }
+                task
+            })
+            .filter(|task| !synced || task.is_overdue || task.is_today || task.is_tomorrow)
+            .collect::<Vec<_>>();
+        tracing::Span::current().record("tasks", tasks.len());
+        Ok(tasks)
+    }
+
+    /// Fetch changed items through the Sync API and merge them into `cache`.
+    ///
+    /// Without a stored token this is a full sync. Returns every cached item
+    /// with a due date.
+    async fn sync_tasks(&self, cache: &Mutex<SyncCache>) -> Result<Vec<TodoistTask>> {
+        let sync_token = cache
+            .lock()
+            .unwrap()
+            .sync_token
+            .clone()
+            .unwrap_or_else(|| "*".to_string());
+
+        let response = self
+            .http
+            .send(self.sync_request(&sync_token, r#"["items"]"#))
+            .await
+            .context("Failed to connect to Todoist API")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(status_error(
+                status,
+                &body,
+                "Todoist token",
+                anyhow::anyhow!("Todoist sync error ({}): {}", status, body),
+            ));
+        }
+
+        let data: SyncResponse = response
+            .json()
+            .context("Failed to parse Todoist sync response")?;
+
+        let mut cache = cache.lock().unwrap();
+        cache.apply(data);
+        Ok(cache.due_items())
+    }
+
+    fn sync_request(&self, sync_token: &str, resource_types: &str) -> RequestBuilder {
+        self.client
+            .post(format!("{}/sync", self.base_url))
+            .header("Authorization", format!("Bearer {}", self.api_token))
+            .form(&[
+                ("sync_token", sync_token),
+                ("resource_types", resource_types),
+            ])
+    }
+
+    /// Every reminder, when enabled and the plan includes them.
+    ///
+    /// Reminders are cosmetic, so failures only log and give no reminders. A
+    /// 403 means the plan lacks reminders, and they aren't asked for again.
+    async fn get_reminders(&self) -> Vec<TodoistReminder> {
+        if !self.show_reminders || self.reminders_unavailable.load(Ordering::Relaxed) {
+            return Vec::new();
+        }
+        match self.fetch_reminders().await {
+            Ok(Some(reminders)) => reminders,
+            Ok(None) => {
+                tracing::info!("Todoist reminders aren't available on this plan");
+                self.reminders_unavailable.store(true, Ordering::Relaxed);
+                Vec::new()
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to fetch Todoist reminders");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Reminders through the Sync API, or `None` when the plan has none.
+    async fn fetch_reminders(&self) -> Result<Option<Vec<TodoistReminder>>> {
+        let response = self
+            .http
+            .send(self.sync_request("*", r#"["reminders"]"#))
+            .await
+            .context("Failed to connect to Todoist API")?;
+
+        if response.status() == reqwest::StatusCode::FORBIDDEN {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(status_error(
+                status,
+                &body,
+                "Todoist token",
+                anyhow::anyhow!("Todoist API error ({}): {}", status, body),
+            ));
+        }
+
+        #[derive(Deserialize)]
+        struct RemindersResponse {
+            #[serde(default)]
+            reminders: Vec<TodoistReminder>,
+        }
+
+        let data: RemindersResponse = response
+            .json()
+            .context("Failed to parse Todoist reminders response")?;
+        Ok(Some(data.reminders))
+    }
+
+    /// The query of the saved filter called `name`, from the last lookup if
+    /// there was one.
+    async fn saved_filter_query(&self, name: &str) -> Result<String> {
+        let cached = self.saved_filter_query.lock().unwrap().clone();
+        if let Some(query) = cached {
+            return Ok(query);
+        }
+        let query = self.fetch_saved_filter_query(name).await?;
+        *self.saved_filter_query.lock().unwrap() = Some(query.clone());
+        Ok(query)
+    }
+
+    /// Look up the query of the saved filter called `name`.
+    async fn fetch_saved_filter_query(&self, name: &str) -> Result<String> {
+        let response = self
+            .http
+            .send(self.sync_request("*", r#"["filters"]"#))
+            .await
+            .context("Failed to connect to Todoist API")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(status_error(
+                status,
+                &body,
+                "Todoist token",
+                anyhow::anyhow!("Todoist API error ({}): {}", status, body),
+            ));
+        }
+
+        #[derive(Deserialize)]
+        struct FiltersResponse {
+            #[serde(default)]
+            filters: Vec<SavedFilter>,
+        }
+
+        let data: FiltersResponse = response
+            .json()
+            .context("Failed to parse Todoist filters response")?;
+
+        find_saved_filter(&data.filters, name)
+            .map(str::to_string)
+            .with_context(|| format!("No saved Todoist filter named '{}'", name))
+    }
+
+    /// Fetch every page of the task filter
+    ///
+    /// A failure forgets the saved filter's query, in case the filter was
+    /// edited or deleted in Todoist.
+    async fn get_filtered_tasks(&self) -> Result<Vec<TodoistTask>> {
+        let query = match &self.saved_filter {
+            Some(name) => self.saved_filter_query(name).await?,
+            None => self.task_filter_query.clone(),
+        };
+        let result = self.get_filter_pages(&query).await;
+        if result.is_err() {
+            *self.saved_filter_query.lock().unwrap() = None;
+        }
+        result
+    }
+
+    /// Fetch every page of tasks matching `query`
+    async fn get_filter_pages(&self, query: &str) -> Result<Vec<TodoistTask>> {
+        let url = format!("{}/tasks/filter", self.base_url);
         let mut all_tasks = Vec::new();
         let mut cursor: Option<String> = None;
+        let mut pages = CursorGuard::new("Todoist tasks");
 
         // Fetch all pages
+        loop {
+            let response = self
+                .http
+                .send(
+                    self.client
+                        .get(&url)
+                        .header("Authorization", format!("Bearer {}", self.api_token))
+                        .query(&task_filter_params(query, cursor.as_deref())),
+                )
+                .await
+                .context("Failed to connect to Todoist API")?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().unwrap_or_default();
+                return Err(status_error(
+                    status,
+                    &body,
+                    "Todoist token",
+                    anyhow::anyhow!("Todoist API error ({}): {}", status, body),
+                ));
+            }
+
+            #[derive(Deserialize)]
+            struct FilterResponse {
+                results: Vec<TodoistTask>,
+                next_cursor: Option<String>,
+            }
+
+            let data: FilterResponse = response
+                .json()
+                .context("Failed to parse Todoist response")?;
+
+            all_tasks.extend(data.results);
+
+            // Check if there are more pages
+            match data.next_cursor {
+                Some(next) if pages.follow(&next) => cursor = Some(next),
+                _ => break,
+            }
+        }
+
+        Ok(all_tasks)
+    }
+
+    /// Map of project id to project name
+    async fn get_project_names(&self) -> Result<HashMap<String, String>> {
+        Ok(self
+            .list_projects()
+            .await?
+            .into_iter()
+            .map(|p| (p.id, p.name))
+            .collect())
+    }
+
+    /// Every project, in Todoist's own order
+    pub async fn list_projects(&self) -> Result<Vec<TodoistProject>> {
+        let url = format!("{}/projects", self.base_url);
+        let mut projects = Vec::new();
+        let mut cursor: Option<String> = None;
+        let mut pages = CursorGuard::new("Todoist projects");
+
         loop {
             let mut request = self
                 .client
                 .get(&url)
                 .header("Authorization", format!("Bearer {}", self.api_token))
-                .query(&[("query", "today | overdue | tomorrow")])
-                .query(&[("limit", "100")]);
+                .query(&[("limit", "200")]);
 
             if let Some(ref c) = cursor {
                 request = request.query(&[("cursor", c.as_str())]);
             }
 
-            let response = request
-                .send()
+            let response = self
+                .http
+                .send(request)
                 .await
                 .context("Failed to connect to Todoist API")?;
 
             if !response.status().is_success() {
                 let status = response.status();
-                let body = response.text().await.unwrap_or_default();
-                return Err(anyhow::anyhow!("Todoist API error ({}): {}", status, body));
+                let body = response.text().unwrap_or_default();
+                return Err(status_error(
+                    status,
+                    &body,
+                    "Todoist token",
+                    anyhow::anyhow!("Todoist API error ({}): {}", status, body),
+                ));
             }
 
             #[derive(Deserialize)]
-            struct FilterResponse {
-                results: Vec<TodoistTask>,
+            struct ProjectsResponse {
+                results: Vec<TodoistProject>,
                 next_cursor: Option<String>,
             }
 
-            let data: FilterResponse = response
+            let data: ProjectsResponse = response
                 .json()
+                .context("Failed to parse Todoist projects response")?;
+
+            projects.extend(data.results);
+
+            match data.next_cursor {
+                Some(next) if pages.follow(&next) => cursor = Some(next),
+                _ => break,
+            }
+        }
+
+        Ok(projects)
+    }
+
+    /// Look up collaborators of shared projects whose tasks mention an
+    /// unknown assignee or assigner.
+    ///
+    /// Names are cosmetic like project names, so failures are only logged.
+    async fn fetch_missing_collaborators(&self, tasks: &[TodoistTask]) {
+        let project_ids: HashSet<&str> = {
+            let known = self.collaborators.lock().unwrap();
+            tasks
+                .iter()
+                .filter(|task| {
+                    [&task.assignee_id, &task.assigner_id]
+                        .into_iter()
+                        .flatten()
+                        .any(|id| !known.contains_key(id))
+                })
+                .filter_map(|task| task.project_id.as_deref())
+                .collect()
+        };
+
+        for project_id in project_ids {
+            match self.get_collaborator_names(project_id).await {
+                Ok(names) => self.collaborators.lock().unwrap().extend(names),
+                Err(e) => {
+                    tracing::warn!(error = %e, project_id, "Failed to fetch Todoist collaborators")
+                }
+            }
+        }
+    }
+
+    /// Fetch project names when a task belongs to a project not seen before.
+    ///
+    /// Project names are cosmetic, so a failed lookup only logs.
+    async fn fetch_missing_projects(&self, tasks: &[TodoistTask]) {
+        let missing = {
+            let known = self.projects.lock().unwrap();
+            tasks
+                .iter()
+                .filter_map(|task| task.project_id.as_ref())
+                .any(|id| !known.contains_key(id))
+        };
+        if !missing {
+            return;
+        }
+
+        match self.get_project_names().await {
+            Ok(names) => self.projects.lock().unwrap().extend(names),
+            Err(e) => tracing::warn!(error = %e, "Failed to fetch Todoist projects"),
+        }
+    }
+
+    /// Look up section names once a task mentions a section we haven't seen.
+    ///
+    /// Like collaborators, failures are only logged and the task shows
+    /// without a section.
+    async fn fetch_missing_sections(&self, tasks: &[TodoistTask]) {
+        let missing = {
+            let known = self.sections.lock().unwrap();
+            tasks
+                .iter()
+                .filter_map(|task| task.section_id.as_ref())
+                .any(|id| !known.contains_key(id))
+        };
+        if !missing {
+            return;
+        }
+
+        match self.get_section_names().await {
+            Ok(names) => self.sections.lock().unwrap().extend(names),
+            Err(e) => tracing::warn!(error = %e, "Failed to fetch Todoist sections"),
+        }
+    }
+
+    /// Map of section id to section name across all projects
+    async fn get_section_names(&self) -> Result<HashMap<String, String>> {
+        let url = format!("{}/sections", self.base_url);
+        let mut names = HashMap::new();
+        let mut cursor: Option<String> = None;
+        let mut pages = CursorGuard::new("Todoist sections");
+
+        loop {
+            let mut request = self
+                .client
+                .get(&url)
+                .header("Authorization", format!("Bearer {}", self.api_token))
+                .query(&[("limit", "200")]);
+
+            if let Some(ref c) = cursor {
+                request = request.query(&[("cursor", c.as_str())]);
+            }
+
+            let response = self
+                .http
+                .send(request)
                 .await
-                .context("Failed to parse Todoist response")?;
+                .context("Failed to connect to Todoist API")?;
 
-            all_tasks.extend(data.results);
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().unwrap_or_default();
+                return Err(status_error(
+                    status,
+                    &body,
+                    "Todoist token",
+                    anyhow::anyhow!("Todoist API error ({}): {}", status, body),
+                ));
+            }
+
+            #[derive(Deserialize)]
+            struct Section {
+                id: String,
+                name: String,
+            }
+
+            #[derive(Deserialize)]
+            struct SectionsResponse {
+                results: Vec<Section>,
+                next_cursor: Option<String>,
+            }
+
+            let data: SectionsResponse = response
+                .json()
+                .context("Failed to parse Todoist sections response")?;
+
+            names.extend(data.results.into_iter().map(|s| (s.id, s.name)));
+
+            match data.next_cursor {
+                Some(next) if pages.follow(&next) => cursor = Some(next),
+                _ => break,
+            }
+        }
+
+        Ok(names)
+    }
+
+    /// Map of user id to name for everyone sharing a project
+    async fn get_collaborator_names(&self, project_id: &str) -> Result<HashMap<String, String>> {
+        let url = format!("{}/projects/{}/collaborators", self.base_url, project_id);
+        let mut names = HashMap::new();
+        let mut cursor: Option<String> = None;
+        let mut pages = CursorGuard::new("Todoist collaborators");
+
+        loop {
+            let mut request = self
+                .client
+                .get(&url)
+                .header("Authorization", format!("Bearer {}", self.api_token))
+                .query(&[("limit", "200")]);
+
+            if let Some(ref c) = cursor {
+                request = request.query(&[("cursor", c.as_str())]);
+            }
+
+            let response = self
+                .http
+                .send(request)
+                .await
+                .context("Failed to connect to Todoist API")?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().unwrap_or_default();
+                return Err(status_error(
+                    status,
+                    &body,
+                    "Todoist token",
+                    anyhow::anyhow!("Todoist API error ({}): {}", status, body),
+                ));
+            }
+
+            #[derive(Deserialize)]
+            struct Collaborator {
+                id: String,
+                name: String,
+            }
+
+            #[derive(Deserialize)]
+            struct CollaboratorsResponse {
+                results: Vec<Collaborator>,
+                next_cursor: Option<String>,
+            }
+
+            let data: CollaboratorsResponse = response
+                .json()
+                .context("Failed to parse Todoist collaborators response")?;
+
+            names.extend(data.results.into_iter().map(|c| (c.id, c.name)));
 
-            // Check if there are more pages
             match data.next_cursor {
-                Some(next) => cursor = Some(next),
-                None => break,
+                Some(next) if pages.follow(&next) => cursor = Some(next),
+                _ => break,
             }
         }
 
-        Ok(all_tasks.into_iter().map(TodoTask::from_todoist).collect())
+        Ok(names)
     }
 
     /// Complete a task
     pub async fn complete_task(&self, task_id: &str) -> Result<()> {
-        let url = format!("{}/tasks/{}/close", TODOIST_API_URL, task_id);
+        let url = format!("{}/tasks/{}/close", self.base_url, task_id);
 
         let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_token))
-            .send()
+            .http
+            .send(
+                self.client
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", self.api_token)),
+            )
             .await
             .context("Failed to connect to Todoist API")?;
 
         if !response.status().is_success() {
             let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!(
-                "Failed to complete task ({}): {}",
+            let body = response.text().unwrap_or_default();
+            return Err(status_error(
                 status,
-                body
+                &body,
+                "Todoist token",
+                anyhow::anyhow!("Failed to complete task ({}): {}", status, body),
             ));
         }
 
         Ok(())
     }
 
+    /// Reopen a completed task
+    pub async fn reopen_task(&self, task_id: &str) -> Result<()> {
+        let url = format!("{}/tasks/{}/reopen", self.base_url, task_id);
+
+        let response = self
+            .http
+            .send(
+                self.client
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", self.api_token)),
+            )
+            .await
+            .context("Failed to connect to Todoist API")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(status_error(
+                status,
+                &body,
+                "Todoist token",
+                anyhow::anyhow!("Failed to reopen task ({}): {}", status, body),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Delete a task for good. Unlike completing, this can't be reopened.
+    pub async fn delete_task(&self, task_id: &str) -> Result<()> {
+        let url = format!("{}/tasks/{}", self.base_url, task_id);
+
+        let response = self
+            .http
+            .send(
+                self.client
+                    .delete(&url)
+                    .header("Authorization", format!("Bearer {}", self.api_token)),
+            )
+            .await
+            .context("Failed to connect to Todoist API")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(status_error(
+                status,
+                &body,
+                "Todoist token",
+                anyhow::anyhow!("Failed to delete task ({}): {}", status, body),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Move a task to another project, e.g. out of the inbox.
+    pub async fn move_task(&self, task_id: &str, project_id: &str) -> Result<()> {
+        let url = format!("{}/tasks/{}/move", self.base_url, task_id);
+
+        #[derive(Serialize)]
+        struct MoveTaskRequest<'a> {
+            project_id: &'a str,
+        }
+
+        let response = self
+            .http
+            .send(
+                self.client
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", self.api_token))
+                    .json(&MoveTaskRequest { project_id }),
+            )
+            .await
+            .context("Failed to connect to Todoist API")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(status_error(
+                status,
+                &body,
+                "Todoist token",
+                anyhow::anyhow!("Failed to move task ({}): {}", status, body),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Move a task to a date with no time of day.
+    pub async fn update_task_due_date(&self, task_id: &str, due_date: &str) -> Result<()> {
+        let url = format!("{}/tasks/{}", self.base_url, task_id);
+
+        #[derive(Serialize)]
+        struct UpdateTaskRequest<'a> {
+            due_date: &'a str,
+        }
+
+        let response = self
+            .http
+            .send(
+                self.client
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", self.api_token))
+                    .json(&UpdateTaskRequest { due_date }),
+            )
+            .await
+            .context("Failed to connect to Todoist API")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(status_error(
+                status,
+                &body,
+                "Todoist token",
+                anyhow::anyhow!("Failed to update task due date ({}): {}", status, body),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Add a task from a line in Todoist's quick-add syntax, e.g.
+    /// "buy milk tomorrow #Shopping @errands", and report how Todoist read it.
+    ///
+    /// Todoist has no parse-only mode, so the task is created.
+    pub async fn quick_add(&self, text: &str) -> Result<QuickAddResult> {
+        let text = text.trim();
+        if text.is_empty() {
+            anyhow::bail!("Type a task to add");
+        }
+        let url = format!("{}/tasks/quick", self.base_url);
+
+        #[derive(Serialize)]
+        struct QuickAddRequest<'a> {
+            text: &'a str,
+        }
+
+        let response = self
+            .http
+            .send(
+                self.client
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", self.api_token))
+                    .json(&QuickAddRequest { text }),
+            )
+            .await
+            .context("Failed to connect to Todoist API")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            return Err(status_error(
+                status,
+                &body,
+                "Todoist token",
+                anyhow::anyhow!("Failed to add task ({}): {}", status, body),
+            ));
+        }
+
+        let task: TodoistTask = response
+            .json()
+            .context("Todoist couldn't make a task of that")?;
+        self.fetch_missing_projects(std::slice::from_ref(&task))
+            .await;
+        let project_name = task
+            .project_id
+            .as_ref()
+            .and_then(|id| self.projects.lock().unwrap().get(id).cloned());
+        Ok(QuickAddResult::from_task(task, project_name))
+    }
+
     /// Update a task due datetime.
     pub async fn update_task_due_datetime(&self, task_id: &str, due_datetime: &str) -> Result<()> {
-        let url = format!("{}/tasks/{}", TODOIST_API_URL, task_id);
+        let url = format!("{}/tasks/{}", self.base_url, task_id);
 
         #[derive(Serialize)]
         struct UpdateTaskRequest<'a> {
@@ -112,24 +948,814 @@ impl TodoistClient {
         }
 
         let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_token))
-            .json(&UpdateTaskRequest { due_datetime })
-            .send()
+            .http
+            .send(
+                self.client
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", self.api_token))
+                    .json(&UpdateTaskRequest { due_datetime }),
+            )
             .await
             .context("Failed to connect to Todoist API")?;
 
         if !response.status().is_success() {
             let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!(
-                "Failed to update task due date ({}): {}",
+            let body = response.text().unwrap_or_default();
+            return Err(status_error(
                 status,
-                body
+                &body,
+                "Todoist token",
+                anyhow::anyhow!("Failed to update task due date ({}): {}", status, body),
             ));
         }
 
         Ok(())
     }
 }
+
+/// A Todoist project a task can be moved to
+#[derive(uniffi::Record, Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct TodoistProject {
+    pub id: String,
+    pub name: String,
+    #[serde(default, rename = "inbox_project")]
+    pub is_inbox: bool,
+}
+
+/// A task added through quick add, as Todoist understood the line
+#[derive(uniffi::Record, Clone, Debug, PartialEq, Eq)]
+pub struct QuickAddResult {
+    pub task_id: String,
+    /// The line with the date, project and labels taken out
+    pub content: String,
+    /// The due date as Todoist read it, e.g. "tomorrow" or "every monday"
+    pub due_string: Option<String>,
+    /// YYYY-MM-DD
+    pub due_date: Option<String>,
+    pub is_recurring: bool,
+    /// `None` for the inbox or when the project couldn't be looked up
+    pub project_name: Option<String>,
+    pub labels: Vec<String>,
+}
+
+impl QuickAddResult {
+    fn from_task(task: TodoistTask, project_name: Option<String>) -> Self {
+        Self {
+            task_id: task.id,
+            content: task.content,
+            due_string: task.due.as_ref().and_then(|due| due.string.clone()),
+            due_date: task.due.as_ref().map(|due| due.date.clone()),
+            is_recurring: task.due.as_ref().is_some_and(|due| due.is_recurring),
+            project_name,
+            labels: task.labels,
+        }
+    }
+}
+
+/// Reminder from the Sync API
+#[derive(Debug, Deserialize)]
+struct TodoistReminder {
+    item_id: String,
+    /// "absolute" at `due`, "relative" to the task's due time, or "location"
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    due: Option<TodoistDue>,
+    /// Minutes before the task's due time, for relative reminders
+    #[serde(default)]
+    minute_offset: Option<i64>,
+    #[serde(default)]
+    is_deleted: bool,
+}
+
+/// Earliest of `task`'s timed reminders that is still ahead of `now`
+fn next_reminder(
+    reminders: &[TodoistReminder],
+    task: &TodoistTask,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<chrono::DateTime<chrono::Utc>> {
+    reminders
+        .iter()
+        .filter(|reminder| reminder.item_id == task.id && !reminder.is_deleted)
+        .filter_map(|reminder| match reminder.kind.as_str() {
+            "absolute" => reminder.due.as_ref()?.due_at(),
+            "relative" => {
+                let due = task.due.as_ref()?.due_at()?;
+                Some(due - chrono::Duration::minutes(reminder.minute_offset?))
+            }
+            _ => None,
+        })
+        .filter(|at| *at > now)
+        .min()
+}
+
+/// Query parameters for one page of the task filter request
+fn task_filter_params<'a>(query: &'a str, cursor: Option<&'a str>) -> Vec<(&'static str, &'a str)> {
+    let mut params = vec![("query", query), ("limit", "100")];
+    if let Some(cursor) = cursor {
+        params.push(("cursor", cursor));
+    }
+    params
+}
+
+/// Filter saved in Todoist
+#[derive(Debug, Deserialize)]
+struct SavedFilter {
+    name: String,
+    query: String,
+    #[serde(default)]
+    is_deleted: bool,
+}
+
+/// Query of the saved filter called `name`, ignoring case and deleted filters
+fn find_saved_filter<'a>(filters: &'a [SavedFilter], name: &str) -> Option<&'a str> {
+    filters
+        .iter()
+        .find(|filter| !filter.is_deleted && filter.name.trim().eq_ignore_ascii_case(name))
+        .map(|filter| filter.query.as_str())
+}
+
+/// Items kept between Sync API calls, so later syncs only fetch what changed
+#[derive(Default)]
+struct SyncCache {
+    sync_token: Option<String>,
+    items: HashMap<String, TodoistTask>,
+}
+
+impl SyncCache {
+    /// Merge a sync response. Deleted and completed items are dropped; the
+    /// rest replace their cached copy. A full sync starts from an empty cache.
+    fn apply(&mut self, response: SyncResponse) {
+        if response.full_sync {
+            self.items.clear();
+        }
+        for item in response.items {
+            if item.is_deleted || item.checked {
+                self.items.remove(&item.task.id);
+            } else {
+                self.items.insert(item.task.id.clone(), item.task);
+            }
+        }
+        self.sync_token = Some(response.sync_token);
+    }
+
+    /// Cached items that have a due date
+    fn due_items(&self) -> Vec<TodoistTask> {
+        self.items
+            .values()
+            .filter(|task| task.due.is_some())
+            .cloned()
+            .collect()
+    }
+}
+
+/// Response from the Sync API
+#[derive(Debug, Deserialize)]
+struct SyncResponse {
+    sync_token: String,
+    #[serde(default)]
+    full_sync: bool,
+    #[serde(default)]
+    items: Vec<SyncItem>,
+}
+
+/// Item from the Sync API, which also reports completed and deleted items
+#[derive(Debug, Deserialize)]
+struct SyncItem {
+    #[serde(flatten)]
+    task: TodoistTask,
+    #[serde(default)]
+    checked: bool,
+    #[serde(default)]
+    is_deleted: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        find_saved_filter, task_filter_params, SavedFilter, SyncCache, SyncResponse, TodoistClient,
+        DEFAULT_TASK_FILTER_QUERY,
+    };
+    use crate::http::{InvalidTokenError, MockTransport};
+    use crate::task::TaskUrgency;
+    use crate::time_format::TimeFormat;
+    use std::sync::Arc;
+
+    /// Client with the default filter, answered by `http`
+    fn client(http: Arc<MockTransport>) -> TodoistClient {
+        configured_client(http, None, None, false)
+    }
+
+    /// Client with the given filter settings, answered by `http`
+    fn configured_client(
+        http: Arc<MockTransport>,
+        task_filter_query: Option<&str>,
+        saved_filter: Option<&str>,
+        incremental_sync: bool,
+    ) -> TodoistClient {
+        TodoistClient::new(
+            "token".to_string(),
+            TimeFormat::default(),
+            chrono::Duration::minutes(60),
+            task_filter_query.map(str::to_string),
+            saved_filter.map(str::to_string),
+            incremental_sync,
+            http,
+        )
+    }
+
+    fn sync_response(json: &str) -> SyncResponse {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn cached_contents(cache: &SyncCache) -> Vec<(String, String)> {
+        let mut contents: Vec<_> = cache
+            .items
+            .values()
+            .map(|task| (task.id.clone(), task.content.clone()))
+            .collect();
+        contents.sort();
+        contents
+    }
+
+    #[test]
+    fn configured_filter_query_is_sent() {
+        let client = configured_client(
+            Arc::new(MockTransport::default()),
+            Some(" @work & (today | overdue) "),
+            None,
+            false,
+        );
+        assert_eq!(
+            task_filter_params(&client.task_filter_query, None),
+            vec![("query", "@work & (today | overdue)"), ("limit", "100")]
+        );
+        assert_eq!(
+            task_filter_params(&client.task_filter_query, Some("abc"))[2],
+            ("cursor", "abc")
+        );
+    }
+
+    #[test]
+    fn missing_or_blank_filter_query_uses_default() {
+        for query in [None, Some("  ")] {
+            assert_eq!(
+                task_filter_params(
+                    &configured_client(Arc::new(MockTransport::default()), query, None, false)
+                        .task_filter_query,
+                    None,
+                )[0],
+                ("query", DEFAULT_TASK_FILTER_QUERY)
+            );
+        }
+    }
+
+    #[test]
+    fn incremental_sync_merges_updates_and_deletes() {
+        let mut cache = SyncCache::default();
+        cache.apply(sync_response(
+            r#"{"sync_token": "t1", "full_sync": true, "items": [
+                {"id": "1", "content": "Write report", "due": {"date": "2026-10-16"}},
+                {"id": "2", "content": "Call Bob", "due": {"date": "2026-10-16"}},
+                {"id": "3", "content": "Water plants", "due": {"date": "2026-10-17"}}
+            ]}"#,
+        ));
+
+        cache.apply(sync_response(
+            r#"{"sync_token": "t2", "full_sync": false, "items": [
+                {"id": "1", "content": "Write final report", "due": {"date": "2026-10-17"}},
+                {"id": "2", "content": "Call Bob", "due": null, "is_deleted": true},
+                {"id": "3", "content": "Water plants", "due": null, "checked": true}
+            ]}"#,
+        ));
+
+        assert_eq!(cache.sync_token.as_deref(), Some("t2"));
+        assert_eq!(
+            cached_contents(&cache),
+            vec![("1".to_string(), "Write final report".to_string())]
+        );
+        assert_eq!(
+            cache.items["1"].due.as_ref().map(|due| due.date.as_str()),
+            Some("2026-10-17")
+        );
+    }
+
+    #[test]
+    fn full_sync_replaces_cached_items() {
+        let mut cache = SyncCache::default();
+        cache.apply(sync_response(
+            r#"{"sync_token": "t1", "full_sync": true, "items": [
+                {"id": "1", "content": "Old", "due": {"date": "2026-10-16"}}
+            ]}"#,
+        ));
+        cache.apply(sync_response(
+            r#"{"sync_token": "t2", "full_sync": true, "items": [
+                {"id": "2", "content": "New", "due": null}
+            ]}"#,
+        ));
+
+        assert_eq!(
+            cached_contents(&cache),
+            vec![("2".to_string(), "New".to_string())]
+        );
+        assert!(cache.due_items().is_empty());
+    }
+
+    #[test]
+    fn incremental_sync_only_applies_to_default_filter() {
+        let new = |query| configured_client(Arc::new(MockTransport::default()), query, None, true);
+        assert!(new(None).sync.is_some());
+        assert!(new(Some("@work")).sync.is_none());
+    }
+
+    #[tokio::test]
+    async fn task_pages_are_fetched_until_no_cursor_is_left() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(
+            200,
+            r#"{"results": [{"id": "1", "content": "First", "due": null, "project_id": "p1"}],
+                "next_cursor": "page-2"}"#,
+        );
+        http.respond(
+            200,
+            r#"{"results": [{"id": "2", "content": "Second", "due": null}], "next_cursor": null}"#,
+        );
+        http.respond(
+            200,
+            r#"{"results": [{"id": "p1", "name": "Work"}], "next_cursor": null}"#,
+        );
+        let client = client(http.clone());
+
+        let tasks = client.get_tasks().await.unwrap();
+
+        let ids: Vec<_> = tasks.iter().map(|task| task.id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2"]);
+        assert_eq!(tasks[0].project_name.as_deref(), Some("Work"));
+
+        let requests = http.take_requests();
+        let paths: Vec<_> = requests
+            .iter()
+            .map(|request| request.url().path())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/api/v1/tasks/filter",
+                "/api/v1/tasks/filter",
+                "/api/v1/projects"
+            ]
+        );
+        let cursor = |index: usize| {
+            requests[index]
+                .url()
+                .query_pairs()
+                .find(|(key, _)| key == "cursor")
+                .map(|(_, value)| value.into_owned())
+        };
+        assert_eq!(cursor(0), None);
+        assert_eq!(cursor(1).as_deref(), Some("page-2"));
+    }
+
+    #[tokio::test]
+    async fn assignees_are_resolved_from_cached_collaborators() {
+        let http = Arc::new(MockTransport::default());
+        let shared_task = r#"{"results": [
+            {"id": "1", "content": "Review", "due": null, "project_id": "p1",
+             "assignee_id": "u1", "assigner_id": "u2"},
+            {"id": "2", "content": "Mine", "due": null, "project_id": "p2"}
+        ], "next_cursor": null}"#;
+        let projects = r#"{"results": [], "next_cursor": null}"#;
+        http.respond(200, shared_task);
+        http.respond(200, projects);
+        http.respond(
+            200,
+            r#"{"results": [{"id": "u1", "name": "Ada"}, {"id": "u2", "name": "Grace"}],
+                "next_cursor": null}"#,
+        );
+        http.respond(200, shared_task);
+        http.respond(200, projects);
+        let client = client(http.clone());
+
+        let tasks = client.get_tasks().await.unwrap();
+
+        assert_eq!(tasks[0].assignee_name.as_deref(), Some("Ada"));
+        assert_eq!(tasks[0].assigner_name.as_deref(), Some("Grace"));
+        assert_eq!(tasks[1].assignee_name, None);
+        let paths: Vec<_> = http
+            .take_requests()
+            .iter()
+            .map(|request| request.url().path().to_string())
+            .collect();
+        assert_eq!(paths[2], "/api/v1/projects/p1/collaborators");
+
+        // Known collaborators aren't fetched again
+        let tasks = client.get_tasks().await.unwrap();
+
+        assert_eq!(tasks[0].assignee_name.as_deref(), Some("Ada"));
+        assert_eq!(http.take_requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn section_names_are_resolved_and_cached() {
+        let http = Arc::new(MockTransport::default());
+        let tasks = r#"{"results": [
+            {"id": "1", "content": "Plan", "due": null, "project_id": "p1", "section_id": "s1"},
+            {"id": "2", "content": "Loose", "due": null, "project_id": "p1"}
+        ], "next_cursor": null}"#;
+        let projects = r#"{"results": [{"id": "p1", "name": "Work"}], "next_cursor": null}"#;
+        http.respond(200, tasks);
+        http.respond(200, projects);
+        http.respond(
+            200,
+            r#"{"results": [{"id": "s1", "name": "Backlog", "project_id": "p1"}],
+                "next_cursor": null}"#,
+        );
+        http.respond(200, tasks);
+        let client = client(http.clone());
+
+        let fetched = client.get_tasks().await.unwrap();
+
+        assert_eq!(fetched[0].section_name.as_deref(), Some("Backlog"));
+        assert_eq!(fetched[0].project_name.as_deref(), Some("Work"));
+        assert_eq!(fetched[1].section_name, None);
+        let paths: Vec<_> = http
+            .take_requests()
+            .iter()
+            .map(|request| request.url().path().to_string())
+            .collect();
+        assert_eq!(paths[2], "/api/v1/sections");
+
+        // Known sections and projects aren't fetched again
+        let fetched = client.get_tasks().await.unwrap();
+
+        assert_eq!(fetched[0].section_name.as_deref(), Some("Backlog"));
+        assert_eq!(fetched[0].project_name.as_deref(), Some("Work"));
+        assert_eq!(http.take_requests().len(), 1);
+    }
+
+    fn reminder_client(http: Arc<MockTransport>) -> TodoistClient {
+        client(http).with_reminders(true)
+    }
+
+    const DUE_TASK: &str = r#"{"results": [
+        {"id": "1", "content": "Call", "due": {"date": "2099-01-01T10:00:00Z"}}
+    ], "next_cursor": null}"#;
+
+    #[tokio::test]
+    async fn next_upcoming_reminder_is_attached_to_its_task() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(200, DUE_TASK);
+        http.respond(
+            200,
+            r#"{"reminders": [
+                {"id": "r1", "item_id": "1", "type": "relative", "minute_offset": 30},
+                {"id": "r2", "item_id": "1", "type": "absolute", "due": {"date": "2099-01-01T09:00:00Z"}},
+                {"id": "r3", "item_id": "1", "type": "absolute", "due": {"date": "2000-01-01T09:00:00Z"}},
+                {"id": "r4", "item_id": "1", "type": "absolute", "due": {"date": "2099-01-01T08:00:00Z"}, "is_deleted": true},
+                {"id": "r5", "item_id": "2", "type": "absolute", "due": {"date": "2099-01-01T07:00:00Z"}}
+            ]}"#,
+        );
+        let client = reminder_client(http.clone());
+
+        let tasks = client.get_tasks().await.unwrap();
+
+        assert_eq!(
+            tasks[0].next_reminder.as_deref(),
+            Some("2099-01-01T09:00:00+00:00")
+        );
+        assert_eq!(http.take_requests()[1].url().path(), "/api/v1/sync");
+    }
+
+    #[tokio::test]
+    async fn plans_without_reminders_are_not_asked_again() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(200, DUE_TASK);
+        http.respond(403, r#"{"error": "Premium only feature"}"#);
+        http.respond(200, DUE_TASK);
+        let client = reminder_client(http.clone());
+
+        let tasks = client.get_tasks().await.unwrap();
+        assert_eq!(tasks[0].next_reminder, None);
+        assert_eq!(http.take_requests().len(), 2);
+
+        client.get_tasks().await.unwrap();
+        assert_eq!(http.take_requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn repeated_task_cursor_stops_pagination() {
+        let http = Arc::new(MockTransport::default());
+        for id in ["1", "2"] {
+            http.respond(
+                200,
+                &format!(
+                    r#"{{"results": [{{"id": "{}", "content": "Task", "due": null}}],
+                        "next_cursor": "stuck"}}"#,
+                    id
+                ),
+            );
+        }
+        let client = client(http.clone());
+
+        let tasks = client.get_tasks().await.unwrap();
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(http.take_requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_task_page_stops_pagination() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(
+            200,
+            r#"{"results": [{"id": "1", "content": "First", "due": null}], "next_cursor": "page-2"}"#,
+        );
+        http.respond(502, "upstream");
+        let client = client(http.clone());
+
+        let error = client.get_tasks().await.unwrap_err();
+
+        assert!(error.to_string().contains("502"), "{}", error);
+        assert_eq!(http.take_requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn unauthorized_response_reports_an_invalid_token() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(401, "Unauthorized");
+        let client = client(http);
+
+        let error = client.get_tasks().await.unwrap_err();
+
+        assert!(error.downcast_ref::<InvalidTokenError>().is_some());
+        assert_eq!(error.to_string(), "Todoist token is invalid or expired");
+    }
+
+    #[test]
+    fn saved_filter_is_found_by_name_ignoring_case_and_deleted_ones() {
+        let filter = |name: &str, query: &str, is_deleted| SavedFilter {
+            name: name.to_string(),
+            query: query.to_string(),
+            is_deleted,
+        };
+        let filters = vec![
+            filter("Work", "@old", true),
+            filter("Work", "@work & !@waiting", false),
+            filter("Errands", "#Errands", false),
+        ];
+
+        assert_eq!(
+            find_saved_filter(&filters, "work"),
+            Some("@work & !@waiting")
+        );
+        assert_eq!(find_saved_filter(&filters, "Home"), None);
+    }
+
+    #[tokio::test]
+    async fn deadline_urgency_raises_urgency_only_when_enabled() {
+        let date = |days: i64| {
+            (chrono::Local::now() + chrono::Duration::days(days))
+                .format("%Y-%m-%d")
+                .to_string()
+        };
+        let tasks = format!(
+            r#"{{"results": [
+                {{"id": "1", "content": "File taxes", "due": {{"date": "{later}"}},
+                  "deadline": {{"date": "{past}"}}}},
+                {{"id": "2", "content": "Send invoice", "due": {{"date": "{later}"}},
+                  "deadline": {{"date": "{today}"}}}}
+            ], "next_cursor": null}}"#,
+            later = date(5),
+            past = date(-1),
+            today = date(0),
+        );
+        for (enabled, expected) in [
+            (false, [TaskUrgency::Later, TaskUrgency::Later]),
+            (true, [TaskUrgency::Overdue, TaskUrgency::Today]),
+        ] {
+            let http = Arc::new(MockTransport::default());
+            http.respond(200, &tasks);
+            let client = client(http).with_deadline_urgency(enabled);
+
+            let fetched = client.get_tasks().await.unwrap();
+
+            let urgencies: Vec<_> = fetched.iter().map(|task| task.urgency).collect();
+            assert_eq!(urgencies, expected, "deadline_urgency = {}", enabled);
+            // Grouping still follows the due date
+            assert!(fetched
+                .iter()
+                .all(|task| !task.is_overdue && !task.is_today));
+            assert!(fetched.iter().all(|task| task.is_deadline_near));
+        }
+    }
+
+    #[tokio::test]
+    async fn saved_filter_query_is_used_for_the_task_request() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(
+            200,
+            r#"{"sync_token": "t1", "filters": [{"id": "f1", "name": "Deep work", "query": "p1 & @focus"}]}"#,
+        );
+        http.respond(
+            200,
+            r#"{"results": [{"id": "1", "content": "Someday", "due": null}], "next_cursor": null}"#,
+        );
+        http.respond(200, r#"{"results": [], "next_cursor": null}"#);
+        let client = configured_client(http.clone(), None, Some("Deep work"), true);
+
+        let tasks = client.get_tasks().await.unwrap();
+
+        assert_eq!(tasks.len(), 1);
+        let requests = http.take_requests();
+        let query = requests[1]
+            .url()
+            .query_pairs()
+            .find(|(key, _)| key == "query")
+            .map(|(_, value)| value.into_owned());
+        assert_eq!(query.as_deref(), Some("p1 & @focus"));
+    }
+
+    #[tokio::test]
+    async fn saved_filter_query_is_looked_up_again_only_after_a_failure() {
+        let http = Arc::new(MockTransport::default());
+        let filters = r#"{"sync_token": "t1", "filters": [{"id": "f1", "name": "Deep work", "query": "p1"}]}"#;
+        let no_tasks = r#"{"results": [], "next_cursor": null}"#;
+        http.respond(200, filters);
+        http.respond(200, no_tasks);
+        http.respond(200, no_tasks);
+        http.respond(400, "Invalid filter");
+        http.respond(200, filters);
+        http.respond(200, no_tasks);
+        let client = configured_client(http.clone(), None, Some("Deep work"), false);
+
+        client.get_filtered_tasks().await.unwrap();
+        client.get_filtered_tasks().await.unwrap();
+        assert!(client.get_filtered_tasks().await.is_err());
+        client.get_filtered_tasks().await.unwrap();
+
+        let paths: Vec<_> = http
+            .take_requests()
+            .iter()
+            .map(|request| request.url().path().to_string())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/api/v1/sync",
+                "/api/v1/tasks/filter",
+                "/api/v1/tasks/filter",
+                "/api/v1/tasks/filter",
+                "/api/v1/sync",
+                "/api/v1/tasks/filter",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn reopening_posts_to_the_task_reopen_endpoint() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(204, "");
+        let client = client(http.clone());
+
+        client.reopen_task("42").await.unwrap();
+
+        let requests = http.take_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method(), "POST");
+        assert_eq!(
+            requests[0].url().as_str(),
+            "https://api.todoist.com/api/v1/tasks/42/reopen"
+        );
+    }
+
+    #[tokio::test]
+    async fn deleting_sends_a_delete_for_the_task() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(204, "");
+        let client = client(http.clone());
+
+        client.delete_task("42").await.unwrap();
+
+        let requests = http.take_requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method(), "DELETE");
+        assert_eq!(
+            requests[0].url().as_str(),
+            "https://api.todoist.com/api/v1/tasks/42"
+        );
+    }
+
+    #[tokio::test]
+    async fn quick_add_reports_the_parsed_due_date_project_and_labels() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(
+            200,
+            r#"{
+                "id": "7",
+                "content": "buy milk",
+                "project_id": "p2",
+                "labels": ["errands"],
+                "due": {"date": "2026-10-17", "string": "tomorrow", "is_recurring": false}
+            }"#,
+        );
+        http.respond(
+            200,
+            r#"{"results": [{"id": "p2", "name": "Shopping"}], "next_cursor": null}"#,
+        );
+        let client = client(http.clone());
+
+        let added = client
+            .quick_add(" buy milk tomorrow #Shopping @errands ")
+            .await
+            .unwrap();
+
+        assert_eq!(added.task_id, "7");
+        assert_eq!(added.content, "buy milk");
+        assert_eq!(added.due_string.as_deref(), Some("tomorrow"));
+        assert_eq!(added.due_date.as_deref(), Some("2026-10-17"));
+        assert_eq!(added.project_name.as_deref(), Some("Shopping"));
+        assert_eq!(added.labels, vec!["errands"]);
+        let requests = http.take_requests();
+        assert_eq!(requests[0].url().path(), "/api/v1/tasks/quick");
+        let body = std::str::from_utf8(requests[0].body().unwrap().as_bytes().unwrap()).unwrap();
+        assert_eq!(body, r#"{"text":"buy milk tomorrow #Shopping @errands"}"#);
+    }
+
+    #[tokio::test]
+    async fn move_task_posts_the_target_project() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(200, r#"{"id": "1", "content": "Plan", "project_id": "p2"}"#);
+        let client = client(http.clone());
+
+        client.move_task("1", "p2").await.unwrap();
+
+        let requests = http.take_requests();
+        assert_eq!(requests[0].method(), reqwest::Method::POST);
+        assert_eq!(requests[0].url().path(), "/api/v1/tasks/1/move");
+        let body = std::str::from_utf8(requests[0].body().unwrap().as_bytes().unwrap()).unwrap();
+        assert_eq!(body, r#"{"project_id":"p2"}"#);
+    }
+
+    #[tokio::test]
+    async fn projects_are_listed_across_pages_in_order() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(
+            200,
+            r#"{"results": [
+                {"id": "p0", "name": "Inbox", "inbox_project": true, "color": "grey"},
+                {"id": "p2", "name": "Work"}
+            ], "next_cursor": "page-2"}"#,
+        );
+        http.respond(
+            200,
+            r#"{"results": [{"id": "p1", "name": "Home", "inbox_project": false}],
+                "next_cursor": null}"#,
+        );
+        let client = client(http.clone());
+
+        let projects = client.list_projects().await.unwrap();
+
+        let names: Vec<_> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["Inbox", "Work", "Home"]);
+        assert!(projects[0].is_inbox);
+        assert!(!projects[1].is_inbox);
+        assert_eq!(projects[2].id, "p1");
+    }
+
+    #[tokio::test]
+    async fn quick_add_rejects_blank_and_unreadable_input() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(200, r#"{"error": "no content"}"#);
+        let client = client(http.clone());
+
+        assert!(client.quick_add("   ").await.is_err());
+        assert!(http.take_requests().is_empty());
+
+        let error = client.quick_add("#Shopping").await.unwrap_err();
+        assert!(error.to_string().contains("couldn't make a task"));
+    }
+
+    #[tokio::test]
+    async fn requests_go_to_the_overridden_base_url() {
+        let http = Arc::new(MockTransport::default());
+        http.respond(200, r#"{"results": [], "next_cursor": null}"#);
+        http.respond(200, r#"{"results": [], "next_cursor": null}"#);
+        let client = client(http.clone()).with_base_url(" http://localhost:8080/todoist/ ");
+
+        client.get_tasks().await.unwrap();
+
+        let requests = http.take_requests();
+        assert!(!requests.is_empty());
+        for request in &requests {
+            assert!(
+                request
+                    .url()
+                    .as_str()
+                    .starts_with("http://localhost:8080/todoist/"),
+                "{}",
+                request.url()
+            );
+        }
+        assert_eq!(requests[0].url().path(), "/todoist/tasks/filter");
+    }
+}